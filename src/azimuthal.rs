@@ -0,0 +1,413 @@
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use convert;
+use ellipsoid;
+use geodesic;
+use projection::Projection;
+
+/// Calculate the east, north and up unit vectors of the local tangent plane at lambda0, phi0.
+///
+fn enu_basis(lambda0:f64, phi0:f64) -> ([f64; 3], [f64; 3], [f64; 3]) {
+    let (sl, cl) = (lambda0.sin(), lambda0.cos());
+    let (sp, cp) = (phi0.sin(), phi0.cos());
+
+    ([-sl, cl, 0.0], [-sp * cl, -sp * sl, cp], [cp * cl, cp * sl, sp])
+}
+
+fn dot(u:[f64; 3], v:[f64; 3]) -> f64 {
+    u[0] * v[0] + u[1] * v[1] + u[2] * v[2]
+}
+
+/// Calculate longitude and latitude of an ECEF position lying on the ellipsoid surface.
+///
+fn surface_lla(x:f64, y:f64, z:f64, ellps:ellipsoid::Ellipsoid) -> (f64, f64) {
+    let p:f64 = (x * x + y * y).sqrt();
+    (y.atan2(x), z.atan2(p * (1.0 - ellps.eccsq)))
+}
+
+/// Azimuthal Equidistant projection on the ellipsoid, in any aspect.
+///
+/// Distance and azimuth from the center are preserved by solving geodesics between the center and
+/// each point. Points nearly antipodal to the center, where the geodesic fails to converge,
+/// project to NaN.
+///
+/// References:
+/// -----------
+/// Vincenty, T. (1975). Direct and inverse solutions of geodesics on the
+/// ellipsoid with application of nested equations. Survey Review 23(176):88-93.
+///
+#[derive(Copy, Clone)]
+pub struct AzimuthalEquidistant {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// longitude of the projection center (radians)
+    pub lambda0 : f64,
+    /// latitude of the projection center (radians)
+    pub phi0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64
+}
+
+impl AzimuthalEquidistant {
+
+    /// Construct a projection centered on lambda0, phi0.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi0:f64, x0:f64, y0:f64) -> AzimuthalEquidistant {
+        AzimuthalEquidistant {ellps: ellps, lambda0: lambda0, phi0: phi0, x0: x0, y0: y0}
+    }
+}
+
+impl Projection for AzimuthalEquidistant {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        match geodesic::inverse(self.ellps, self.lambda0, self.phi0, lambda, phi) {
+            Some((s12, azi1, _azi2)) => (self.x0 + s12 * azi1.sin(), self.y0 + s12 * azi1.cos()),
+            None => (f64::NAN, f64::NAN)
+        }
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let dx:f64 = x - self.x0;
+        let dy:f64 = y - self.y0;
+        let s12:f64 = (dx * dx + dy * dy).sqrt();
+        if s12 < 1.0e-10 {
+            return (self.lambda0, self.phi0);
+        }
+        let (lambda, phi, _azi2) = geodesic::direct(self.ellps, self.lambda0, self.phi0, dx.atan2(dy), s12);
+
+        (lambda, phi)
+    }
+}
+
+/// Orthographic projection on the ellipsoid, in any aspect.
+///
+/// Points on the ellipsoid are projected orthogonally onto the plane tangent to the ellipsoid at
+/// the center. Points on the far side of the horizon overlap the near side and the inverse returns
+/// the near side.
+///
+/// References:
+/// -----------
+/// IOGP (2019). Geomatics Guidance Note 7-2, Orthographic, EPSG method 9840.
+///
+#[derive(Copy, Clone)]
+pub struct Orthographic {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// longitude of the projection center (radians)
+    pub lambda0 : f64,
+    /// latitude of the projection center (radians)
+    pub phi0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64
+}
+
+impl Orthographic {
+
+    /// Construct a projection centered on lambda0, phi0.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi0:f64, x0:f64, y0:f64) -> Orthographic {
+        Orthographic {ellps: ellps, lambda0: lambda0, phi0: phi0, x0: x0, y0: y0}
+    }
+}
+
+impl Projection for Orthographic {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let (east, north, _up) = enu_basis(self.lambda0, self.phi0);
+        let (cx, cy, cz) = convert::lla2ecef(self.lambda0, self.phi0, 0.0, self.ellps);
+        let (px, py, pz) = convert::lla2ecef(lambda, phi, 0.0, self.ellps);
+        let d = [px - cx, py - cy, pz - cz];
+
+        (self.x0 + dot(d, east), self.y0 + dot(d, north))
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let (east, north, up) = enu_basis(self.lambda0, self.phi0);
+        let (cx, cy, cz) = convert::lla2ecef(self.lambda0, self.phi0, 0.0, self.ellps);
+        let dx:f64 = x - self.x0;
+        let dy:f64 = y - self.y0;
+
+        // intersect the line through the grid point, along the center normal, with the ellipsoid
+        let mut q = [0.0; 3];
+        for i in 0..3 {
+            q[i] = [cx, cy, cz][i] + dx * east[i] + dy * north[i];
+        }
+        let w = [1.0 / self.ellps.asq, 1.0 / self.ellps.asq, 1.0 / self.ellps.bsq];
+        let qa:f64 = (0..3).map(|i| w[i] * up[i] * up[i]).sum::<f64>();
+        let qb:f64 = (0..3).map(|i| 2.0 * w[i] * q[i] * up[i]).sum::<f64>();
+        let qc:f64 = (0..3).map(|i| w[i] * q[i] * q[i]).sum::<f64>() - 1.0;
+        let disc:f64 = (qb * qb - 4.0 * qa * qc).max(0.0);
+        let t:f64 = (-qb + disc.sqrt()) / (2.0 * qa);
+
+        surface_lla(q[0] + t * up[0], q[1] + t * up[1], q[2] + t * up[2], self.ellps)
+    }
+}
+
+/// Gnomonic projection on the ellipsoid, in any aspect.
+///
+/// Points on the ellipsoid are projected from the center of the ellipsoid onto the plane tangent
+/// to the ellipsoid at the projection center, so that great ellipses map to straight lines. Points
+/// beyond the horizon of the center have no projection and return infinite coordinates.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, pp. 164-168.
+///
+#[derive(Copy, Clone)]
+pub struct Gnomonic {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// longitude of the projection center (radians)
+    pub lambda0 : f64,
+    /// latitude of the projection center (radians)
+    pub phi0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64
+}
+
+impl Gnomonic {
+
+    /// Construct a projection centered on lambda0, phi0.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi0:f64, x0:f64, y0:f64) -> Gnomonic {
+        Gnomonic {ellps: ellps, lambda0: lambda0, phi0: phi0, x0: x0, y0: y0}
+    }
+}
+
+impl Projection for Gnomonic {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let (east, north, up) = enu_basis(self.lambda0, self.phi0);
+        let (cx, cy, cz) = convert::lla2ecef(self.lambda0, self.phi0, 0.0, self.ellps);
+        let (px, py, pz) = convert::lla2ecef(lambda, phi, 0.0, self.ellps);
+        let c = [cx, cy, cz];
+        let p = [px, py, pz];
+
+        let cosz:f64 = dot(p, up);
+        if cosz <= 0.0 {
+            return (f64::INFINITY, f64::INFINITY);
+        }
+        let t:f64 = dot(c, up) / cosz;
+        let d = [t * p[0] - c[0], t * p[1] - c[1], t * p[2] - c[2]];
+
+        (self.x0 + dot(d, east), self.y0 + dot(d, north))
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let (east, north, _up) = enu_basis(self.lambda0, self.phi0);
+        let (cx, cy, cz) = convert::lla2ecef(self.lambda0, self.phi0, 0.0, self.ellps);
+        let dx:f64 = x - self.x0;
+        let dy:f64 = y - self.y0;
+
+        let mut q = [0.0; 3];
+        for i in 0..3 {
+            q[i] = [cx, cy, cz][i] + dx * east[i] + dy * north[i];
+        }
+        // scale the ray from the center of the ellipsoid to meet its surface
+        let s:f64 = 1.0 / ((q[0] * q[0] + q[1] * q[1]) / self.ellps.asq + q[2] * q[2] / self.ellps.bsq).sqrt();
+
+        surface_lla(s * q[0], s * q[1], s * q[2], self.ellps)
+    }
+}
+
+/// Oblique Stereographic projection by way of a conformal sphere, as used for the Dutch RD grid.
+///
+/// References:
+/// -----------
+/// IOGP (2019). Geomatics Guidance Note 7-2, Oblique Stereographic, EPSG method 9809.
+///
+#[derive(Copy, Clone)]
+pub struct ObliqueStereographic {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// longitude of the projection center (radians)
+    pub lambda0 : f64,
+    /// latitude of the projection center (radians)
+    pub phi0 : f64,
+    /// scale factor at the projection center
+    pub k0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64,
+
+    pub r : f64,
+    pub n : f64,
+    pub c : f64,
+    pub chi0 : f64
+}
+
+impl ObliqueStereographic {
+
+    /// Construct a projection centered on lambda0, phi0 with scale factor k0.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi0:f64, k0:f64, x0:f64, y0:f64) -> ObliqueStereographic {
+        let (_r, rn, rm) = ellps.radii(phi0);
+        let r:f64 = (rn * rm).sqrt();
+        let n:f64 = (1.0 + ellps.eccsq * phi0.cos().powi(4) / (1.0 - ellps.eccsq)).sqrt();
+
+        let sp:f64 = phi0.sin();
+        let s1:f64 = (1.0 + sp) / (1.0 - sp);
+        let s2:f64 = (1.0 - ellps.ecc * sp) / (1.0 + ellps.ecc * sp);
+        let w1:f64 = (s1 * s2.powf(ellps.ecc)).powf(n);
+        let sin_chi00:f64 = (w1 - 1.0) / (w1 + 1.0);
+        let c:f64 = (n + sp) * (1.0 - sin_chi00) / ((n - sp) * (1.0 + sin_chi00));
+        let w2:f64 = c * w1;
+        let chi0:f64 = ((w2 - 1.0) / (w2 + 1.0)).asin();
+
+        ObliqueStereographic {ellps: ellps, lambda0: lambda0, phi0: phi0, k0: k0, x0: x0, y0: y0, r: r, n: n, c: c, chi0: chi0}
+    }
+}
+
+impl Projection for ObliqueStereographic {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let ecc:f64 = self.ellps.ecc;
+        let dlambda:f64 = self.n * (lambda - self.lambda0);
+
+        let sp:f64 = phi.sin();
+        let sa:f64 = (1.0 + sp) / (1.0 - sp);
+        let sb:f64 = (1.0 - ecc * sp) / (1.0 + ecc * sp);
+        let w:f64 = self.c * (sa * sb.powf(ecc)).powf(self.n);
+        let chi:f64 = ((w - 1.0) / (w + 1.0)).asin();
+
+        let b:f64 = 1.0 + chi.sin() * self.chi0.sin() + chi.cos() * self.chi0.cos() * dlambda.cos();
+        let x:f64 = 2.0 * self.r * self.k0 * chi.cos() * dlambda.sin() / b;
+        let y:f64 = 2.0 * self.r * self.k0 * (chi.sin() * self.chi0.cos() - chi.cos() * self.chi0.sin() * dlambda.cos()) / b;
+
+        (self.x0 + x, self.y0 + y)
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let ecc:f64 = self.ellps.ecc;
+        let dx:f64 = x - self.x0;
+        let dy:f64 = y - self.y0;
+        let rk:f64 = self.r * self.k0;
+
+        let g:f64 = 2.0 * rk * (FRAC_PI_4 - self.chi0 / 2.0).tan();
+        let h:f64 = 4.0 * rk * self.chi0.tan() + g;
+        let i:f64 = (dx / (h + dy)).atan();
+        let j:f64 = (dx / (g - dy)).atan() - i;
+        let chi:f64 = self.chi0 + 2.0 * ((dy - dx * (j / 2.0).tan()) / (2.0 * rk)).atan();
+        let dlambda:f64 = j + 2.0 * i;
+        let lambda:f64 = dlambda / self.n + self.lambda0;
+
+        // recover geodetic latitude from the conformal sphere by iteration
+        let sc:f64 = chi.sin();
+        let psi:f64 = 0.5 * ((1.0 + sc) / (self.c * (1.0 - sc))).ln() / self.n;
+        let mut phi:f64 = 2.0 * psi.exp().atan() - FRAC_PI_2;
+        for _ in 0..15 {
+            let esin:f64 = ecc * phi.sin();
+            let psi_i:f64 = ((phi / 2.0 + FRAC_PI_4).tan() * ((1.0 - esin) / (1.0 + esin)).powf(ecc / 2.0)).ln();
+            let next:f64 = phi - (psi_i - psi) * phi.cos() * (1.0 - esin * esin) / (1.0 - self.ellps.eccsq);
+            let delta:f64 = (next - phi).abs();
+            phi = next;
+            if delta < 1.0e-14 {
+                break;
+            }
+        }
+
+        (lambda, phi)
+    }
+}
+
+#[test]
+fn test_aeqd() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let lambda0:f64 = -76.26108657f64.to_radians();
+    let phi0:f64 = 39.99277705f64.to_radians();
+    let proj = AzimuthalEquidistant::new(wgs84, lambda0, phi0, 0.0, 0.0);
+
+    let (lambda, phi) = (-0.1275f64.to_radians(), 51.5072f64.to_radians());
+    let (x, y) = proj.forward(lambda, phi);
+    let (s12, _azi1, _azi2) = geodesic::inverse(wgs84, lambda0, phi0, lambda, phi).unwrap();
+    assert!(((x * x + y * y).sqrt() - s12).abs() < 1.0e-6);
+
+    let (lambda2, phi2) = proj.inverse(x, y);
+    assert!((lambda2 - lambda).abs() < 1.0e-11);
+    assert!((phi2 - phi).abs() < 1.0e-11);
+
+    // the geodesic to a nearly antipodal point does not converge
+    let (x, y) = AzimuthalEquidistant::new(wgs84, 0.0, 0.0, 0.0, 0.0).forward(179.7f64.to_radians(), 0.5f64.to_radians());
+    assert!(x.is_nan() && y.is_nan());
+}
+
+#[test]
+fn test_orthographic() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let proj = Orthographic::new(wgs84, 5f64.to_radians(), 55f64.to_radians(), 0.0, 0.0);
+
+    let (x, y) = proj.forward(5f64.to_radians(), 55f64.to_radians());
+    assert!(x.abs() < 1.0e-6 && y.abs() < 1.0e-6);
+
+    // points on the central meridian lie on the y axis
+    let (x, y) = proj.forward(5f64.to_radians(), 40f64.to_radians());
+    assert!(x.abs() < 1.0e-6 && y < 0.0);
+
+    let (x, y) = proj.forward(-10f64.to_radians(), 30f64.to_radians());
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() + 10.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - 30.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_gnomonic() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let proj = Gnomonic::new(wgs84, 0.0, 0.0, 0.0, 0.0);
+
+    // the equator, a great ellipse, maps to the x axis
+    let (_x, y) = proj.forward(30f64.to_radians(), 0.0);
+    assert!(y.abs() < 1.0e-6);
+
+    let (x, y) = proj.forward(20f64.to_radians(), -35f64.to_radians());
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() - 20.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() + 35.0).abs() < 1.0e-9);
+
+    let (x, _y) = proj.forward(120f64.to_radians(), 0.0);
+    assert!(x.is_infinite());
+}
+
+#[test]
+fn test_oblique_stereographic() {
+    // EPSG Guidance Note 7-2 example, Amersfoort / RD New
+    let expected = (196105.283, 557057.739);
+    let tolerance:f64 = 0.001;
+
+    let bessel1841 = ellipsoid::Ellipsoid::new(6377397.155, 299.1528128);
+    let lambda0:f64 = 5.0 + 23.0/60.0 + 15.500/3600.0;
+    let phi0:f64 = 52.0 + 9.0/60.0 + 22.178/3600.0;
+    let proj = ObliqueStereographic::new(bessel1841, lambda0.to_radians(), phi0.to_radians(), 0.9999079, 155000.0, 463000.0);
+
+    let (x, y) = proj.forward(6f64.to_radians(), 53f64.to_radians());
+    println!("x {0} y {1}", x, y);
+    assert!((x - expected.0).abs() < tolerance);
+    assert!((y - expected.1).abs() < tolerance);
+
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() - 6.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - 53.0).abs() < 1.0e-9);
+}
@@ -8,7 +8,7 @@ pub fn longitude(x:f64, y:f64) -> f64 {
     let lambda:f64 = if (x.abs() + y.abs()) < 1.0e-10 {
         0.0
     } else {
-        (y/x).atan()
+        y.atan2(x)
     };
     return lambda;
 }
@@ -80,3 +80,16 @@ fn test_lla2ecef() {
         assert!(false);
     }
 }
+
+#[test]
+fn test_round_trip() {
+    // longitudes beyond 90 degrees, where x is negative, must come back in the right hemisphere
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    for &(lat, lon) in [(35.0f64, 150.0f64), (-33.9, -120.0), (10.0, 179.5), (-45.0, -91.0), (60.0, 30.0)].iter() {
+        let (x, y, z) = lla2ecef(lon.to_radians(), lat.to_radians(), 100.0, wgs84);
+        for algo in ["olson", "bowring"].iter() {
+            let (lon2, lat2, h) = ecef2lla(x, y, z, wgs84, algo);
+            assert!((lon2 - lon).abs() < 1.0e-9 && (lat2 - lat).abs() < 1.0e-6 && (h - 100.0).abs() < 1.0e-2);
+        }
+    }
+}
@@ -0,0 +1,528 @@
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+use ellipsoid;
+use projection;
+use projection::Projection;
+
+/// Equidistant Cylindrical (equirectangular) projection on the ellipsoid.
+///
+/// Distances along meridians are true to scale, as are distances along the standard parallels.
+///
+/// References:
+/// -----------
+/// IOGP (2019). Geomatics Guidance Note 7-2, Equidistant Cylindrical, EPSG method 1028.
+///
+#[derive(Copy, Clone)]
+pub struct Equirectangular {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// central meridian (radians)
+    pub lambda0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64,
+
+    pub rp : f64
+}
+
+impl Equirectangular {
+
+    /// Construct a projection with standard parallels at +/- phi1.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi1:f64, x0:f64, y0:f64) -> Equirectangular {
+        let (_r, rn, _rm) = ellps.radii(phi1);
+
+        Equirectangular {ellps: ellps, lambda0: lambda0, x0: x0, y0: y0, rp: rn * phi1.cos()}
+    }
+}
+
+impl Projection for Equirectangular {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        (self.x0 + self.rp * (lambda - self.lambda0), self.y0 + projection::mlfn(phi, self.ellps))
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        (self.lambda0 + (x - self.x0) / self.rp, projection::inv_mlfn(y - self.y0, self.ellps))
+    }
+}
+
+/// Mercator projection on the ellipsoid.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, pp. 38-47.
+///
+#[derive(Copy, Clone)]
+pub struct Mercator {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// central meridian (radians)
+    pub lambda0 : f64,
+    /// scale factor at the equator
+    pub k0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64
+}
+
+impl Mercator {
+
+    /// Construct a projection with scale factor k0 at the equator (1SP).
+    ///
+    pub fn new_1sp(ellps:ellipsoid::Ellipsoid, lambda0:f64, k0:f64, x0:f64, y0:f64) -> Mercator {
+        Mercator {ellps: ellps, lambda0: lambda0, k0: k0, x0: x0, y0: y0}
+    }
+
+    /// Construct a projection true to scale along the standard parallels at +/- phi1 (2SP).
+    ///
+    pub fn new_2sp(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi1:f64, x0:f64, y0:f64) -> Mercator {
+        Mercator {ellps: ellps, lambda0: lambda0, k0: projection::msfn(phi1, ellps.ecc), x0: x0, y0: y0}
+    }
+}
+
+impl Projection for Mercator {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let ak:f64 = self.ellps.a * self.k0;
+
+        (self.x0 + ak * (lambda - self.lambda0), self.y0 - ak * projection::tsfn(phi, self.ellps.ecc).ln())
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let ak:f64 = self.ellps.a * self.k0;
+        let t:f64 = (-(y - self.y0) / ak).exp();
+
+        (self.lambda0 + (x - self.x0) / ak, projection::phi2(t, self.ellps.ecc))
+    }
+}
+
+/// Miller Cylindrical projection, on a sphere of radius a.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, pp. 86-89.
+///
+#[derive(Copy, Clone)]
+pub struct Miller {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// central meridian (radians)
+    pub lambda0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64
+}
+
+impl Miller {
+
+    /// Construct a projection with central meridian lambda0.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, x0:f64, y0:f64) -> Miller {
+        Miller {ellps: ellps, lambda0: lambda0, x0: x0, y0: y0}
+    }
+}
+
+impl Projection for Miller {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let r:f64 = self.ellps.a;
+
+        (self.x0 + r * (lambda - self.lambda0), self.y0 + r * 1.25 * (FRAC_PI_4 + 0.4 * phi).tan().ln())
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let r:f64 = self.ellps.a;
+
+        (self.lambda0 + (x - self.x0) / r, 2.5 * (0.8 * (y - self.y0) / r).exp().atan() - 0.625 * PI)
+    }
+}
+
+/// Robinson table of parallel length (X) and distance from the equator (Y) at 5 degree intervals.
+///
+static ROBINSON: [(f64, f64); 19] = [
+    (1.0000, 0.0000), (0.9986, 0.0620), (0.9954, 0.1240), (0.9900, 0.1860),
+    (0.9822, 0.2480), (0.9730, 0.3100), (0.9600, 0.3720), (0.9427, 0.4340),
+    (0.9216, 0.4958), (0.8962, 0.5571), (0.8679, 0.6176), (0.8350, 0.6769),
+    (0.7986, 0.7346), (0.7597, 0.7903), (0.7186, 0.8435), (0.6732, 0.8936),
+    (0.6213, 0.9394), (0.5722, 0.9761), (0.5322, 1.0000)
+];
+
+/// Robinson pseudo-cylindrical projection, on a sphere of radius a.
+///
+/// The tabulated coordinates are interpolated with four point Lagrange polynomials.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1990). The Robinson projection: A computation algorithm.
+/// Cartography and Geographic Information Systems 17(4):301-305.
+///
+#[derive(Copy, Clone)]
+pub struct Robinson {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// central meridian (radians)
+    pub lambda0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64
+}
+
+impl Robinson {
+
+    /// Construct a projection with central meridian lambda0.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, x0:f64, y0:f64) -> Robinson {
+        Robinson {ellps: ellps, lambda0: lambda0, x0: x0, y0: y0}
+    }
+
+    /// Interpolate the table at an absolute latitude in degrees.
+    ///
+    fn table(&self, deg:f64) -> (f64, f64) {
+        let u:f64 = (deg / 5.0).max(0.0).min(18.0);
+        let i:usize = (u.floor() as usize).max(1).min(16) - 1;
+        let mut x:f64 = 0.0;
+        let mut y:f64 = 0.0;
+        for j in 0..4 {
+            let mut w:f64 = 1.0;
+            for k in 0..4 {
+                if k != j {
+                    w = w * (u - (i + k) as f64) / (j as f64 - k as f64);
+                }
+            }
+            x = x + w * ROBINSON[i + j].0;
+            y = y + w * ROBINSON[i + j].1;
+        }
+        (x, y)
+    }
+}
+
+impl Projection for Robinson {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let r:f64 = self.ellps.a;
+        let (px, py) = self.table(phi.abs().to_degrees());
+
+        (self.x0 + 0.8487 * r * px * (lambda - self.lambda0), self.y0 + 1.3523 * r * py * phi.signum())
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let r:f64 = self.ellps.a;
+        let (x, y) = (x - self.x0, y - self.y0);
+        let target:f64 = (y.abs() / (1.3523 * r)).min(1.0);
+
+        // Y increases monotonically with latitude, so bisect for it
+        let mut lo:f64 = 0.0;
+        let mut hi:f64 = 90.0;
+        for _ in 0..60 {
+            let mid:f64 = (lo + hi) / 2.0;
+            if self.table(mid).1 < target {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let deg:f64 = (lo + hi) / 2.0;
+        let (px, _py) = self.table(deg);
+
+        (self.lambda0 + x / (0.8487 * r * px), deg.to_radians() * y.signum())
+    }
+}
+
+/// Mollweide pseudo-cylindrical equal area projection, on the authalic sphere.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, pp. 249-252.
+///
+#[derive(Copy, Clone)]
+pub struct Mollweide {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// central meridian (radians)
+    pub lambda0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64,
+
+    pub rq : f64
+}
+
+impl Mollweide {
+
+    /// Construct a projection with central meridian lambda0.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, x0:f64, y0:f64) -> Mollweide {
+        let rq:f64 = ellps.a * (projection::qsfn(FRAC_PI_2, ellps.ecc) / 2.0).sqrt();
+
+        Mollweide {ellps: ellps, lambda0: lambda0, x0: x0, y0: y0, rq: rq}
+    }
+}
+
+impl Projection for Mollweide {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let beta:f64 = projection::authalic(phi, self.ellps.ecc);
+
+        // solve 2 theta + sin 2 theta = pi sin beta by Newton iteration
+        let target:f64 = PI * beta.sin();
+        let mut theta:f64 = beta;
+        for _ in 0..30 {
+            let f:f64 = 2.0 * theta + (2.0 * theta).sin() - target;
+            let df:f64 = 2.0 + 2.0 * (2.0 * theta).cos();
+            if df.abs() < 1.0e-15 {
+                break;
+            }
+            let delta:f64 = f / df;
+            theta = theta - delta;
+            if delta.abs() < 1.0e-14 {
+                break;
+            }
+        }
+
+        let x:f64 = 2.0 * 2f64.sqrt() / PI * self.rq * (lambda - self.lambda0) * theta.cos();
+        let y:f64 = 2f64.sqrt() * self.rq * theta.sin();
+
+        (self.x0 + x, self.y0 + y)
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let (x, y) = (x - self.x0, y - self.y0);
+        let theta:f64 = (y / (2f64.sqrt() * self.rq)).max(-1.0).min(1.0).asin();
+        let beta:f64 = ((2.0 * theta + (2.0 * theta).sin()) / PI).max(-1.0).min(1.0).asin();
+        let lambda:f64 = if theta.cos().abs() < 1.0e-12 {
+            self.lambda0
+        } else {
+            self.lambda0 + PI * x / (2.0 * 2f64.sqrt() * self.rq * theta.cos())
+        };
+
+        (lambda, projection::authalic_inverse(beta, self.ellps.ecc))
+    }
+}
+
+const EE_A1: f64 = 1.340264;
+const EE_A2: f64 = -0.081106;
+const EE_A3: f64 = 0.000893;
+const EE_A4: f64 = 0.003796;
+
+/// Equal Earth pseudo-cylindrical equal area projection, on the ellipsoid by way of authalic latitude.
+///
+/// References:
+/// -----------
+/// Šavrič, B., Patterson, T. and Jenny, B. (2019). The Equal Earth map
+/// projection. International Journal of Geographical Information Science 33(3):454-465.
+///
+/// IOGP (2019). Geomatics Guidance Note 7-2, Equal Earth, EPSG method 1078.
+///
+#[derive(Copy, Clone)]
+pub struct EqualEarth {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// central meridian (radians)
+    pub lambda0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64,
+
+    pub rq : f64
+}
+
+impl EqualEarth {
+
+    /// Construct a projection with central meridian lambda0.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, x0:f64, y0:f64) -> EqualEarth {
+        let rq:f64 = ellps.a * (projection::qsfn(FRAC_PI_2, ellps.ecc) / 2.0).sqrt();
+
+        EqualEarth {ellps: ellps, lambda0: lambda0, x0: x0, y0: y0, rq: rq}
+    }
+}
+
+impl Projection for EqualEarth {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let m:f64 = 3f64.sqrt() / 2.0;
+        let beta:f64 = projection::authalic(phi, self.ellps.ecc);
+        let theta:f64 = (m * beta.sin()).asin();
+        let t2:f64 = theta * theta;
+        let t6:f64 = t2 * t2 * t2;
+
+        let d:f64 = EE_A1 + 3.0 * EE_A2 * t2 + t6 * (7.0 * EE_A3 + 9.0 * EE_A4 * t2);
+        let x:f64 = self.rq * 2.0 * (lambda - self.lambda0) * theta.cos() / (3f64.sqrt() * d);
+        let y:f64 = self.rq * theta * (EE_A1 + EE_A2 * t2 + t6 * (EE_A3 + EE_A4 * t2));
+
+        (self.x0 + x, self.y0 + y)
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let m:f64 = 3f64.sqrt() / 2.0;
+        let yr:f64 = (y - self.y0) / self.rq;
+
+        // solve for the parametric latitude theta by Newton iteration
+        let mut theta:f64 = yr / EE_A1;
+        for _ in 0..15 {
+            let t2:f64 = theta * theta;
+            let t6:f64 = t2 * t2 * t2;
+            let f:f64 = theta * (EE_A1 + EE_A2 * t2 + t6 * (EE_A3 + EE_A4 * t2)) - yr;
+            let df:f64 = EE_A1 + 3.0 * EE_A2 * t2 + t6 * (7.0 * EE_A3 + 9.0 * EE_A4 * t2);
+            let delta:f64 = f / df;
+            theta = theta - delta;
+            if delta.abs() < 1.0e-14 {
+                break;
+            }
+        }
+
+        let t2:f64 = theta * theta;
+        let t6:f64 = t2 * t2 * t2;
+        let d:f64 = EE_A1 + 3.0 * EE_A2 * t2 + t6 * (7.0 * EE_A3 + 9.0 * EE_A4 * t2);
+        let lambda:f64 = self.lambda0 + 3f64.sqrt() * (x - self.x0) / self.rq * d / (2.0 * theta.cos());
+        let beta:f64 = (theta.sin() / m).max(-1.0).min(1.0).asin();
+
+        (lambda, projection::authalic_inverse(beta, self.ellps.ecc))
+    }
+}
+
+#[test]
+fn test_equirectangular() {
+    // EPSG Guidance Note 7-2 example, WGS 84 / World Equidistant Cylindrical
+    let expected = (1113194.91, 6097230.31);
+    let tolerance:f64 = 0.01;
+
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let proj = Equirectangular::new(wgs84, 0.0, 0.0, 0.0, 0.0);
+
+    let (x, y) = proj.forward(10f64.to_radians(), 55f64.to_radians());
+    println!("x {0} y {1}", x, y);
+    assert!((x - expected.0).abs() < tolerance);
+    assert!((y - expected.1).abs() < tolerance);
+
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() - 10.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - 55.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_mercator() {
+    // EPSG Guidance Note 7-2 example, Makassar / NEIEZ
+    let expected = (5009726.58, 569150.82);
+    let tolerance:f64 = 0.01;
+
+    let bessel1841 = ellipsoid::Ellipsoid::new(6377397.155, 299.1528128);
+    let proj = Mercator::new_1sp(bessel1841, 110f64.to_radians(), 0.997, 3900000.0, 900000.0);
+
+    let (x, y) = proj.forward(120f64.to_radians(), -3f64.to_radians());
+    println!("x {0} y {1}", x, y);
+    assert!((x - expected.0).abs() < tolerance);
+    assert!((y - expected.1).abs() < tolerance);
+
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() - 120.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() + 3.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_miller() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let proj = Miller::new(wgs84, 0.0, 0.0, 0.0);
+
+    let (x, y) = proj.forward(-60f64.to_radians(), 70f64.to_radians());
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() + 60.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - 70.0).abs() < 1.0e-9);
+
+    // the false origin offsets the grid
+    let offset = Miller::new(wgs84, 0.0, 500000.0, 200000.0);
+    let (x2, y2) = offset.forward(-60f64.to_radians(), 70f64.to_radians());
+    assert!((x2 - x - 500000.0).abs() < 1.0e-6 && (y2 - y - 200000.0).abs() < 1.0e-6);
+    let (lambda, phi) = offset.inverse(x2, y2);
+    assert!((lambda.to_degrees() + 60.0).abs() < 1.0e-9 && (phi.to_degrees() - 70.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_robinson() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let proj = Robinson::new(wgs84, 0.0, 0.0, 0.0);
+
+    // tabulated values are reproduced at the nodes
+    let (x, y) = proj.forward(90f64.to_radians(), 45f64.to_radians());
+    assert!((x - 0.8487 * wgs84.a * 0.8962 * 90f64.to_radians()).abs() < 1.0e-6);
+    assert!((y - 1.3523 * wgs84.a * 0.5571).abs() < 1.0e-6);
+
+    let (x, y) = proj.forward(-123f64.to_radians(), -37.3f64.to_radians());
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() + 123.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() + 37.3).abs() < 1.0e-9);
+
+    let offset = Robinson::new(wgs84, 0.0, 500000.0, 200000.0);
+    let (x2, y2) = offset.forward(-123f64.to_radians(), -37.3f64.to_radians());
+    assert!((x2 - x - 500000.0).abs() < 1.0e-6 && (y2 - y - 200000.0).abs() < 1.0e-6);
+    let (lambda, phi) = offset.inverse(x2, y2);
+    assert!((lambda.to_degrees() + 123.0).abs() < 1.0e-9 && (phi.to_degrees() + 37.3).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_mollweide() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let proj = Mollweide::new(wgs84, 0.0, 0.0, 0.0);
+
+    let factors = proj.factors(40f64.to_radians(), 50f64.to_radians());
+    assert!((factors.s - 1.0).abs() < 1.0e-9);
+
+    let (x, y) = proj.forward(40f64.to_radians(), 50f64.to_radians());
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() - 40.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - 50.0).abs() < 1.0e-9);
+
+    let offset = Mollweide::new(wgs84, 0.0, 500000.0, 200000.0);
+    let (x2, y2) = offset.forward(40f64.to_radians(), 50f64.to_radians());
+    assert!((x2 - x - 500000.0).abs() < 1.0e-6 && (y2 - y - 200000.0).abs() < 1.0e-6);
+    let (lambda, phi) = offset.inverse(x2, y2);
+    assert!((lambda.to_degrees() - 40.0).abs() < 1.0e-9 && (phi.to_degrees() - 50.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_equal_earth() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let proj = EqualEarth::new(wgs84, 0.0, 0.0, 0.0);
+
+    let factors = proj.factors(-150f64.to_radians(), -20f64.to_radians());
+    assert!((factors.s - 1.0).abs() < 1.0e-9);
+
+    let (x, y) = proj.forward(-150f64.to_radians(), -20f64.to_radians());
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() + 150.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() + 20.0).abs() < 1.0e-9);
+}
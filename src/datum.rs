@@ -0,0 +1,133 @@
+use convert;
+use ellipsoid;
+use helmert;
+use helmert::Convention::{CoordinateFrame, PositionVector};
+
+/// Geodetic datum: a reference ellipsoid and the transformation of its ECEF frame to WGS84.
+///
+#[derive(Copy, Clone)]
+pub struct Datum {
+    /// datum name
+    pub name : &'static str,
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// transformation from the datum to WGS84
+    pub to_wgs84 : helmert::Helmert
+}
+
+impl Datum {
+
+    /// Construct a datum from an ellipsoid and its transformation to WGS84.
+    ///
+    pub fn new(name:&'static str, ellps:ellipsoid::Ellipsoid, to_wgs84:helmert::Helmert) -> Datum {
+        Datum {name: name, ellps: ellps, to_wgs84: to_wgs84}
+    }
+}
+
+/// Names of the datums in the catalogue.
+///
+pub static NAMES: &'static [&'static str] = &[
+    "WGS84", "WGS72", "NAD27", "NAD83", "ED50", "ETRS89", "OSGB36", "DHDN", "Tokyo",
+    "GDA94", "GDA2020", "NZGD49", "Pulkovo1942", "SIRGAS2000"
+];
+
+/// Look up a datum by name, e.g. NAD27, ED50 or OSGB36.
+///
+/// Transformations are the EPSG country or continent wide parameter sets and are generally good
+/// to a few meters; modern datums aligned with the ITRF are taken as equal to WGS84.
+///
+/// References:
+/// -----------
+/// IOGP. EPSG Geodetic Parameter Dataset. https://epsg.org/
+///
+pub fn named(name:&str) -> Option<Datum> {
+    let ellps = |name:&str| ellipsoid::named(name).unwrap();
+    let null = helmert::Helmert::translation(0.0, 0.0, 0.0);
+
+    let (name, ellps, to_wgs84) = match name {
+        "WGS84" => ("WGS84", ellps("WGS84"), null),
+        "WGS72" => ("WGS72", ellps("WGS72"), helmert::Helmert::new(0.0, 0.0, 4.5, 0.0, 0.0, 0.554, 0.219, PositionVector)),
+        // EPSG 1173, contiguous United States
+        "NAD27" => ("NAD27", ellps("Clarke1866"), helmert::Helmert::translation(-8.0, 160.0, 176.0)),
+        "NAD83" => ("NAD83", ellps("GRS80"), null),
+        // EPSG 1133, western Europe
+        "ED50" => ("ED50", ellps("Intl1924"), helmert::Helmert::translation(-87.0, -98.0, -121.0)),
+        "ETRS89" => ("ETRS89", ellps("GRS80"), null),
+        // EPSG 1314, Great Britain
+        "OSGB36" => ("OSGB36", ellps("Airy1830"), helmert::Helmert::new(446.448, -125.157, 542.060, 0.1502, 0.2470, 0.8421, -20.4894, PositionVector)),
+        // EPSG 1777, Germany
+        "DHDN" => ("DHDN", ellps("Bessel1841"), helmert::Helmert::new(598.1, 73.7, 418.2, 0.202, 0.045, -2.455, 6.7, CoordinateFrame)),
+        // EPSG 1231, Japan
+        "Tokyo" => ("Tokyo", ellps("Bessel1841"), helmert::Helmert::translation(-148.0, 507.0, 685.0)),
+        "GDA94" => ("GDA94", ellps("GRS80"), null),
+        "GDA2020" => ("GDA2020", ellps("GRS80"), null),
+        // EPSG 1564, New Zealand
+        "NZGD49" => ("NZGD49", ellps("Intl1924"), helmert::Helmert::new(59.47, -5.04, 187.44, 0.47, -0.1, 1.024, -4.5993, CoordinateFrame)),
+        // EPSG 1254, Russia
+        "Pulkovo1942" => ("Pulkovo1942", ellps("Krassovsky1940"), helmert::Helmert::translation(28.0, -130.0, -95.0)),
+        "SIRGAS2000" => ("SIRGAS2000", ellps("GRS80"), null),
+        _ => return None
+    };
+    Some(Datum::new(name, ellps, to_wgs84))
+}
+
+/// Transform geodetic coordinates from one datum to another by way of ECEF and WGS84.
+///
+/// Longitude lambda and latitude phi are given and returned in radians, height h in meters.
+/// The ECEF to geodetic conversion uses the named algorithm, as for convert::ecef2lla.
+///
+pub fn transform(lambda:f64, phi:f64, h:f64, from:&Datum, to:&Datum, algo:&str) -> (f64, f64, f64) {
+    let (x, y, z) = convert::lla2ecef(lambda, phi, h, from.ellps);
+    let (x, y, z) = from.to_wgs84.transform(x, y, z);
+    let (x, y, z) = to.to_wgs84.inverse(x, y, z);
+    let (lon, lat, hae) = convert::ecef2lla(x, y, z, to.ellps, algo);
+
+    (lon.to_radians(), lat.to_radians(), hae)
+}
+
+#[test]
+fn test_catalogue() {
+    for name in NAMES.iter() {
+        let datum = named(name).unwrap();
+        assert_eq!(datum.name, *name);
+    }
+    assert!(named("Atlantis").is_none());
+}
+
+#[test]
+fn test_osgb36() {
+    // Ordnance Survey example, Caister water tower, accurate to a few meters
+    let osgb36 = named("OSGB36").unwrap();
+    let wgs84 = named("WGS84").unwrap();
+    let lambda:f64 = (1.0 + 42.0/60.0 + 57.8663/3600.0f64).to_radians();
+    let phi:f64 = (52.0 + 39.0/60.0 + 28.8282/3600.0f64).to_radians();
+
+    let (lambda2, phi2, _h2) = transform(lambda, phi, 0.0, &wgs84, &osgb36, "olson");
+    let (lambda3, phi3, _h3) = transform(lambda2, phi2, 0.0, &osgb36, &wgs84, "olson");
+    println!("lambda {0} phi {1}", lambda2.to_degrees(), phi2.to_degrees());
+    // published OSGB36 position 52 39 27.2531 N, 1 43 4.5177 E
+    let (_r, rn, rm) = wgs84.ellps.radii(phi);
+    let east:f64 = (lambda2 - (1.0 + 43.0/60.0 + 4.5177/3600.0f64).to_radians()) * rn * phi.cos();
+    let north:f64 = (phi2 - (52.0 + 39.0/60.0 + 27.2531/3600.0f64).to_radians()) * rm;
+    assert!(east.abs() < 5.0 && north.abs() < 5.0);
+    assert!((lambda3 - lambda).abs() < 1.0e-9 && (phi3 - phi).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_tokyo() {
+    // Japanese datum origin in Tokyo, 35 39 17.5148 N, 139 44 40.5020 E on the Tokyo datum and
+    // 35 39 29.1572 N, 139 44 28.8759 E on JGD2000, which agrees with WGS84 to centimeters
+    let tokyo = named("Tokyo").unwrap();
+    let wgs84 = named("WGS84").unwrap();
+    let lambda:f64 = (139.0 + 44.0/60.0 + 40.5020/3600.0f64).to_radians();
+    let phi:f64 = (35.0 + 39.0/60.0 + 17.5148/3600.0f64).to_radians();
+
+    let (lambda2, phi2, _h2) = transform(lambda, phi, 0.0, &tokyo, &wgs84, "olson");
+    let (_r, rn, rm) = wgs84.ellps.radii(phi);
+    let east:f64 = (lambda2 - (139.0 + 44.0/60.0 + 28.8759/3600.0f64).to_radians()) * rn * phi.cos();
+    let north:f64 = (phi2 - (35.0 + 39.0/60.0 + 29.1572/3600.0f64).to_radians()) * rm;
+    assert!(east.abs() < 5.0 && north.abs() < 5.0);
+
+    let (lambda3, phi3, _h3) = transform(lambda2, phi2, 0.0, &wgs84, &tokyo, "olson");
+    assert!((lambda3 - lambda).abs() < 1.0e-9 && (phi3 - phi).abs() < 1.0e-9);
+}
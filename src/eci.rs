@@ -0,0 +1,485 @@
+use std::f64::consts::PI;
+use std::io;
+
+use grid;
+use grid::invalid;
+use matrix;
+use matrix::Matrix3;
+use time;
+use time::MJD0;
+
+/// Radians per arc-second.
+///
+const ARCSEC:f64 = PI / 648000.0;
+
+/// Julian date of the J2000 epoch, 2000-01-01 12:00 TT.
+///
+pub const J2000:f64 = 2451545.0;
+
+/// Earth orientation at an instant: the UT1 and TT time scales, the pole coordinates and the
+/// celestial pole offsets.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Epoch {
+    /// Julian date in UT1
+    pub jd_ut1 : f64,
+    /// Julian date in terrestrial time
+    pub jd_tt : f64,
+    /// x coordinate of the celestial intermediate pole (radians)
+    pub xp : f64,
+    /// y coordinate of the celestial intermediate pole (radians)
+    pub yp : f64,
+    /// celestial pole offset in longitude from the IAU 1980 nutation (radians)
+    pub ddpsi : f64,
+    /// celestial pole offset in obliquity from the IAU 1980 nutation (radians)
+    pub ddeps : f64
+}
+
+impl Epoch {
+
+    /// Construct an epoch from a UTC Julian date, TAI-UTC in seconds, UT1-UTC in seconds and pole
+    /// coordinates in arc-seconds.
+    ///
+    pub fn new(jd_utc:f64, tai_utc:f64, dut1:f64, xp:f64, yp:f64) -> Epoch {
+        Epoch {jd_ut1: jd_utc + dut1 / 86400.0, jd_tt: jd_utc + (tai_utc + time::TT_TAI) / 86400.0,
+            xp: xp * ARCSEC, yp: yp * ARCSEC, ddpsi: 0.0, ddeps: 0.0}
+    }
+
+    /// Set the celestial pole offsets dψ and dε of the IAU 1980 nutation, in arc-seconds, as
+    /// published with the Earth orientation parameters.
+    ///
+    pub fn with_offsets(self, ddpsi:f64, ddeps:f64) -> Epoch {
+        Epoch {ddpsi: ddpsi * ARCSEC, ddeps: ddeps * ARCSEC, ..self}
+    }
+}
+
+/// Earth orientation parameters for one day.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct EopRecord {
+    /// modified Julian date in UTC
+    pub mjd : f64,
+    /// x pole coordinate (arc-seconds)
+    pub xp : f64,
+    /// y pole coordinate (arc-seconds)
+    pub yp : f64,
+    /// UT1-UTC (s)
+    pub dut1 : f64
+}
+
+/// Table of daily Earth orientation parameters.
+///
+#[derive(Clone, Debug)]
+pub struct Eop {
+    /// records in increasing date
+    pub records : Vec<EopRecord>
+}
+
+impl Eop {
+
+    /// Interpolate the parameters linearly at a UTC Julian date, or None outside the table.
+    ///
+    /// UT1-UTC is unwrapped across a leap second so the step is not smeared over the day.
+    ///
+    pub fn at(&self, jd_utc:f64) -> Option<EopRecord> {
+        let mjd:f64 = jd_utc - MJD0;
+        let i = self.records.iter().position(|r| r.mjd > mjd)?;
+        if i == 0 {
+            return None;
+        }
+        let (a, b) = (self.records[i - 1], self.records[i]);
+        let t:f64 = (mjd - a.mjd) / (b.mjd - a.mjd);
+        let step:f64 = (b.dut1 - a.dut1).round();
+        let dut1 = a.dut1 + t * (b.dut1 - step - a.dut1);
+
+        Some(EopRecord {mjd: mjd, xp: a.xp + t * (b.xp - a.xp), yp: a.yp + t * (b.yp - a.yp), dut1: dut1})
+    }
+
+    /// Construct the epoch at a UTC Julian date with TAI-UTC in seconds, or None outside the table.
+    ///
+    pub fn epoch(&self, jd_utc:f64, tai_utc:f64) -> Option<Epoch> {
+        let r = self.at(jd_utc)?;
+        Some(Epoch::new(jd_utc, tai_utc, r.dut1, r.xp, r.yp))
+    }
+}
+
+/// Parse Earth orientation parameters in the IERS finals format, e.g. finals2000A.all, or as
+/// whitespace separated columns of MJD, x pole, y pole in arc-seconds and UT1-UTC in seconds.
+///
+/// Finals lines without polar motion or UT1-UTC values, beyond the predictions, are skipped.
+///
+/// References:
+/// -----------
+/// IERS Rapid Service/Prediction Center. Format of finals2000A.all.
+/// https://maia.usno.navy.mil/ser7/readme.finals2000A
+///
+pub fn parse(text:&str) -> io::Result<Eop> {
+    let mut records:Vec<EopRecord> = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let field = |a:usize, b:usize| line.get(a..b).and_then(|v| v.trim().parse::<f64>().ok());
+        let finals = match (field(7, 15), field(18, 27), field(37, 46), field(58, 68)) {
+            (Some(mjd), Some(xp), Some(yp), Some(dut1)) => Some(EopRecord {mjd: mjd, xp: xp, yp: yp, dut1: dut1}),
+            _ => None
+        };
+        let record = match finals {
+            Some(record) => record,
+            None if line.len() >= 58 => continue,
+            None => {
+                let values:Vec<f64> = trimmed.split_whitespace().map(|v| v.parse::<f64>())
+                    .collect::<Result<Vec<f64>, _>>()
+                    .map_err(|_| invalid(&format!("could not parse EOP line {}", trimmed)))?;
+                if values.len() < 4 {
+                    return Err(invalid(&format!("EOP line has fewer than four columns {}", trimmed)));
+                }
+                EopRecord {mjd: values[0], xp: values[1], yp: values[2], dut1: values[3]}
+            }
+        };
+        records.push(record);
+    }
+    if records.len() < 2 {
+        return Err(invalid("EOP file has fewer than two records"));
+    }
+    records.sort_by(|a, b| a.mjd.partial_cmp(&b.mjd).unwrap());
+    Ok(Eop {records: records})
+}
+
+/// Read Earth orientation parameters from a file.
+///
+pub fn read(path:&str) -> io::Result<Eop> {
+    let bytes = grid::contents(path)?;
+    parse(&String::from_utf8_lossy(&bytes))
+}
+
+/// Calculate Greenwich mean sidereal time, in radians, by the IAU-82 model.
+///
+/// References:
+/// -----------
+/// Vallado, D. A. (2013). Fundamentals of Astrodynamics and Applications, 4th edition, eq. 3-45.
+///
+pub fn gmst(jd_ut1:f64) -> f64 {
+    let t:f64 = (jd_ut1 - J2000) / 36525.0;
+    let seconds:f64 = 67310.54841 + (876600.0 * 3600.0 + 8640184.812866) * t + 0.093104 * t * t - 6.2e-6 * t * t * t;
+
+    (seconds / 240.0).to_radians().rem_euclid(2.0 * PI)
+}
+
+/// Calculate the Earth rotation angle, in radians, of the IAU 2000 definition of UT1.
+///
+pub fn era(jd_ut1:f64) -> f64 {
+    let d:f64 = jd_ut1 - J2000;
+    (2.0 * PI * (0.7790572732640 + 0.00273781191135448 * d + d.fract())).rem_euclid(2.0 * PI)
+}
+
+/// Calculate the IAU-76 precession from the J2000 mean equator and equinox to the mean of date.
+///
+/// References:
+/// -----------
+/// Lieske, J. H. et al. (1977). Expressions for the precession quantities based upon the IAU
+/// (1976) system of astronomical constants. Astronomy and Astrophysics 58, 1-16.
+///
+pub fn precession(jd_tt:f64) -> Matrix3 {
+    let t:f64 = (jd_tt - J2000) / 36525.0;
+    let zeta:f64 = (2306.2181 * t + 0.30188 * t * t + 0.017998 * t * t * t) * ARCSEC;
+    let theta:f64 = (2004.3109 * t - 0.42665 * t * t - 0.041833 * t * t * t) * ARCSEC;
+    let z:f64 = (2306.2181 * t + 1.09468 * t * t + 0.018203 * t * t * t) * ARCSEC;
+
+    matrix::mul(&matrix::rot3(-z), &matrix::mul(&matrix::rot2(theta), &matrix::rot3(-zeta)))
+}
+
+/// Multipliers of l, l', F, D and Ω, and coefficients of Δψ and Δε with their rates per century,
+/// in units of 0.1 mas, for the 106 terms of the IAU 1980 nutation.
+///
+const NUTATION:[([f64; 5], f64, f64, f64, f64); 106] = [
+    ([0.0, 0.0, 0.0, 0.0, 1.0], -171996.0, -174.2, 92025.0, 8.9),
+    ([0.0, 0.0, 0.0, 0.0, 2.0], 2062.0, 0.2, -895.0, 0.5),
+    ([-2.0, 0.0, 2.0, 0.0, 1.0], 46.0, 0.0, -24.0, 0.0),
+    ([2.0, 0.0, -2.0, 0.0, 0.0], 11.0, 0.0, 0.0, 0.0),
+    ([-2.0, 0.0, 2.0, 0.0, 2.0], -3.0, 0.0, 1.0, 0.0),
+    ([1.0, -1.0, 0.0, -1.0, 0.0], -3.0, 0.0, 0.0, 0.0),
+    ([0.0, -2.0, 2.0, -2.0, 1.0], -2.0, 0.0, 1.0, 0.0),
+    ([2.0, 0.0, -2.0, 0.0, 1.0], 1.0, 0.0, 0.0, 0.0),
+    ([0.0, 0.0, 2.0, -2.0, 2.0], -13187.0, -1.6, 5736.0, -3.1),
+    ([0.0, 1.0, 0.0, 0.0, 0.0], 1426.0, -3.4, 54.0, -0.1),
+    ([0.0, 1.0, 2.0, -2.0, 2.0], -517.0, 1.2, 224.0, -0.6),
+    ([0.0, -1.0, 2.0, -2.0, 2.0], 217.0, -0.5, -95.0, 0.3),
+    ([0.0, 0.0, 2.0, -2.0, 1.0], 129.0, 0.1, -70.0, 0.0),
+    ([2.0, 0.0, 0.0, -2.0, 0.0], 48.0, 0.0, 1.0, 0.0),
+    ([0.0, 0.0, 2.0, -2.0, 0.0], -22.0, 0.0, 0.0, 0.0),
+    ([0.0, 2.0, 0.0, 0.0, 0.0], 17.0, -0.1, 0.0, 0.0),
+    ([0.0, 1.0, 0.0, 0.0, 1.0], -15.0, 0.0, 9.0, 0.0),
+    ([0.0, 2.0, 2.0, -2.0, 2.0], -16.0, 0.1, 7.0, 0.0),
+    ([0.0, -1.0, 0.0, 0.0, 1.0], -12.0, 0.0, 6.0, 0.0),
+    ([-2.0, 0.0, 0.0, 2.0, 1.0], -6.0, 0.0, 3.0, 0.0),
+    ([0.0, -1.0, 2.0, -2.0, 1.0], -5.0, 0.0, 3.0, 0.0),
+    ([2.0, 0.0, 0.0, -2.0, 1.0], 4.0, 0.0, -2.0, 0.0),
+    ([0.0, 1.0, 2.0, -2.0, 1.0], 4.0, 0.0, -2.0, 0.0),
+    ([1.0, 0.0, 0.0, -1.0, 0.0], -4.0, 0.0, 0.0, 0.0),
+    ([2.0, 1.0, 0.0, -2.0, 0.0], 1.0, 0.0, 0.0, 0.0),
+    ([0.0, 0.0, -2.0, 2.0, 1.0], 1.0, 0.0, 0.0, 0.0),
+    ([0.0, 1.0, -2.0, 2.0, 0.0], -1.0, 0.0, 0.0, 0.0),
+    ([0.0, 1.0, 0.0, 0.0, 2.0], 1.0, 0.0, 0.0, 0.0),
+    ([-1.0, 0.0, 0.0, 1.0, 1.0], 1.0, 0.0, 0.0, 0.0),
+    ([0.0, 1.0, 2.0, -2.0, 0.0], -1.0, 0.0, 0.0, 0.0),
+    ([0.0, 0.0, 2.0, 0.0, 2.0], -2274.0, -0.2, 977.0, -0.5),
+    ([1.0, 0.0, 0.0, 0.0, 0.0], 712.0, 0.1, -7.0, 0.0),
+    ([0.0, 0.0, 2.0, 0.0, 1.0], -386.0, -0.4, 200.0, 0.0),
+    ([1.0, 0.0, 2.0, 0.0, 2.0], -301.0, 0.0, 129.0, -0.1),
+    ([1.0, 0.0, 0.0, -2.0, 0.0], -158.0, 0.0, -1.0, 0.0),
+    ([-1.0, 0.0, 2.0, 0.0, 2.0], 123.0, 0.0, -53.0, 0.0),
+    ([0.0, 0.0, 0.0, 2.0, 0.0], 63.0, 0.0, -2.0, 0.0),
+    ([1.0, 0.0, 0.0, 0.0, 1.0], 63.0, 0.1, -33.0, 0.0),
+    ([-1.0, 0.0, 0.0, 0.0, 1.0], -58.0, -0.1, 32.0, 0.0),
+    ([-1.0, 0.0, 2.0, 2.0, 2.0], -59.0, 0.0, 26.0, 0.0),
+    ([1.0, 0.0, 2.0, 0.0, 1.0], -51.0, 0.0, 27.0, 0.0),
+    ([0.0, 0.0, 2.0, 2.0, 2.0], -38.0, 0.0, 16.0, 0.0),
+    ([2.0, 0.0, 0.0, 0.0, 0.0], 29.0, 0.0, -1.0, 0.0),
+    ([1.0, 0.0, 2.0, -2.0, 2.0], 29.0, 0.0, -12.0, 0.0),
+    ([2.0, 0.0, 2.0, 0.0, 2.0], -31.0, 0.0, 13.0, 0.0),
+    ([0.0, 0.0, 2.0, 0.0, 0.0], 26.0, 0.0, -1.0, 0.0),
+    ([-1.0, 0.0, 2.0, 0.0, 1.0], 21.0, 0.0, -10.0, 0.0),
+    ([-1.0, 0.0, 0.0, 2.0, 1.0], 16.0, 0.0, -8.0, 0.0),
+    ([1.0, 0.0, 0.0, -2.0, 1.0], -13.0, 0.0, 7.0, 0.0),
+    ([-1.0, 0.0, 2.0, 2.0, 1.0], -10.0, 0.0, 5.0, 0.0),
+    ([1.0, 1.0, 0.0, -2.0, 0.0], -7.0, 0.0, 0.0, 0.0),
+    ([0.0, 1.0, 2.0, 0.0, 2.0], 7.0, 0.0, -3.0, 0.0),
+    ([0.0, -1.0, 2.0, 0.0, 2.0], -7.0, 0.0, 3.0, 0.0),
+    ([1.0, 0.0, 2.0, 2.0, 2.0], -8.0, 0.0, 3.0, 0.0),
+    ([1.0, 0.0, 0.0, 2.0, 0.0], 6.0, 0.0, 0.0, 0.0),
+    ([2.0, 0.0, 2.0, -2.0, 2.0], 6.0, 0.0, -3.0, 0.0),
+    ([0.0, 0.0, 0.0, 2.0, 1.0], -6.0, 0.0, 3.0, 0.0),
+    ([0.0, 0.0, 2.0, 2.0, 1.0], -7.0, 0.0, 3.0, 0.0),
+    ([1.0, 0.0, 2.0, -2.0, 1.0], 6.0, 0.0, -3.0, 0.0),
+    ([0.0, 0.0, 0.0, -2.0, 1.0], -5.0, 0.0, 3.0, 0.0),
+    ([1.0, -1.0, 0.0, 0.0, 0.0], 5.0, 0.0, 0.0, 0.0),
+    ([2.0, 0.0, 2.0, 0.0, 1.0], -5.0, 0.0, 3.0, 0.0),
+    ([0.0, 1.0, 0.0, -2.0, 0.0], -4.0, 0.0, 0.0, 0.0),
+    ([1.0, 0.0, -2.0, 0.0, 0.0], 4.0, 0.0, 0.0, 0.0),
+    ([0.0, 0.0, 0.0, 1.0, 0.0], -4.0, 0.0, 0.0, 0.0),
+    ([1.0, 1.0, 0.0, 0.0, 0.0], -3.0, 0.0, 0.0, 0.0),
+    ([1.0, 0.0, 2.0, 0.0, 0.0], 3.0, 0.0, 0.0, 0.0),
+    ([1.0, -1.0, 2.0, 0.0, 2.0], -3.0, 0.0, 1.0, 0.0),
+    ([-1.0, -1.0, 2.0, 2.0, 2.0], -3.0, 0.0, 1.0, 0.0),
+    ([-2.0, 0.0, 0.0, 0.0, 1.0], -2.0, 0.0, 1.0, 0.0),
+    ([3.0, 0.0, 2.0, 0.0, 2.0], -3.0, 0.0, 1.0, 0.0),
+    ([0.0, -1.0, 2.0, 2.0, 2.0], -3.0, 0.0, 1.0, 0.0),
+    ([1.0, 1.0, 2.0, 0.0, 2.0], 2.0, 0.0, -1.0, 0.0),
+    ([-1.0, 0.0, 2.0, -2.0, 1.0], -2.0, 0.0, 1.0, 0.0),
+    ([2.0, 0.0, 0.0, 0.0, 1.0], 2.0, 0.0, -1.0, 0.0),
+    ([1.0, 0.0, 0.0, 0.0, 2.0], -2.0, 0.0, 1.0, 0.0),
+    ([3.0, 0.0, 0.0, 0.0, 0.0], 2.0, 0.0, 0.0, 0.0),
+    ([0.0, 0.0, 2.0, 1.0, 2.0], 2.0, 0.0, -1.0, 0.0),
+    ([-1.0, 0.0, 0.0, 0.0, 2.0], 1.0, 0.0, -1.0, 0.0),
+    ([1.0, 0.0, 0.0, -4.0, 0.0], -1.0, 0.0, 0.0, 0.0),
+    ([-2.0, 0.0, 2.0, 2.0, 2.0], 1.0, 0.0, -1.0, 0.0),
+    ([-1.0, 0.0, 2.0, 4.0, 2.0], -2.0, 0.0, 1.0, 0.0),
+    ([2.0, 0.0, 0.0, -4.0, 0.0], -1.0, 0.0, 0.0, 0.0),
+    ([1.0, 1.0, 2.0, -2.0, 2.0], 1.0, 0.0, -1.0, 0.0),
+    ([1.0, 0.0, 2.0, 2.0, 1.0], -1.0, 0.0, 1.0, 0.0),
+    ([-2.0, 0.0, 2.0, 4.0, 2.0], -1.0, 0.0, 1.0, 0.0),
+    ([-1.0, 0.0, 4.0, 0.0, 2.0], 1.0, 0.0, 0.0, 0.0),
+    ([1.0, -1.0, 0.0, -2.0, 0.0], 1.0, 0.0, 0.0, 0.0),
+    ([2.0, 0.0, 2.0, -2.0, 1.0], 1.0, 0.0, -1.0, 0.0),
+    ([2.0, 0.0, 2.0, 2.0, 2.0], -1.0, 0.0, 0.0, 0.0),
+    ([1.0, 0.0, 0.0, 2.0, 1.0], -1.0, 0.0, 0.0, 0.0),
+    ([0.0, 0.0, 4.0, -2.0, 2.0], 1.0, 0.0, 0.0, 0.0),
+    ([3.0, 0.0, 2.0, -2.0, 2.0], 1.0, 0.0, 0.0, 0.0),
+    ([1.0, 0.0, 2.0, -2.0, 0.0], -1.0, 0.0, 0.0, 0.0),
+    ([0.0, 1.0, 2.0, 0.0, 1.0], 1.0, 0.0, 0.0, 0.0),
+    ([-1.0, -1.0, 0.0, 2.0, 1.0], 1.0, 0.0, 0.0, 0.0),
+    ([0.0, 0.0, -2.0, 0.0, 1.0], -1.0, 0.0, 0.0, 0.0),
+    ([0.0, 0.0, 2.0, -1.0, 2.0], -1.0, 0.0, 0.0, 0.0),
+    ([0.0, 1.0, 0.0, 2.0, 0.0], -1.0, 0.0, 0.0, 0.0),
+    ([1.0, 0.0, -2.0, -2.0, 0.0], -1.0, 0.0, 0.0, 0.0),
+    ([0.0, -1.0, 2.0, 0.0, 1.0], -1.0, 0.0, 0.0, 0.0),
+    ([1.0, 1.0, 0.0, -2.0, 1.0], -1.0, 0.0, 0.0, 0.0),
+    ([1.0, 0.0, -2.0, 2.0, 0.0], -1.0, 0.0, 0.0, 0.0),
+    ([2.0, 0.0, 0.0, 2.0, 0.0], 1.0, 0.0, 0.0, 0.0),
+    ([0.0, 0.0, 2.0, 4.0, 2.0], -1.0, 0.0, 0.0, 0.0),
+    ([0.0, 1.0, 0.0, 1.0, 0.0], 1.0, 0.0, 0.0, 0.0)
+];
+
+/// Calculate the fundamental arguments l, l', F, D and Ω of the IAU 1980 theory, in radians.
+///
+fn arguments(t:f64) -> [f64; 5] {
+    let angle = |a:f64, b:f64, c:f64, d:f64, turns:f64| {
+        (((a + (b + (c + d * t) * t) * t) * ARCSEC) + (turns * t).fract() * 2.0 * PI).rem_euclid(2.0 * PI)
+    };
+    [angle(485866.733, 715922.633, 31.310, 0.064, 1325.0),
+     angle(1287099.804, 1292581.224, -0.577, -0.012, 99.0),
+     angle(335778.877, 295263.137, -13.257, 0.011, 1342.0),
+     angle(1072261.307, 1105601.328, -6.891, 0.019, 1236.0),
+     angle(450160.280, -482890.539, 7.455, 0.008, -5.0)]
+}
+
+/// Calculate the nutation in longitude Δψ, the nutation in obliquity Δε and the mean obliquity of
+/// the ecliptic ε, in radians, by the IAU 1980 theory.
+///
+/// References:
+/// -----------
+/// Seidelmann, P. K. (1982). 1980 IAU theory of nutation: the final report of the IAU working
+/// group on nutation. Celestial Mechanics 27, 79-106.
+///
+pub fn nutation(jd_tt:f64) -> (f64, f64, f64) {
+    let t:f64 = (jd_tt - J2000) / 36525.0;
+    let args = arguments(t);
+    let (mut dpsi, mut deps) = (0.0, 0.0);
+    for &(k, s, st, c, ct) in NUTATION.iter() {
+        let a:f64 = k[0] * args[0] + k[1] * args[1] + k[2] * args[2] + k[3] * args[3] + k[4] * args[4];
+        dpsi += (s + st * t) * a.sin();
+        deps += (c + ct * t) * a.cos();
+    }
+    let eps:f64 = 84381.448 - 46.8150 * t - 0.00059 * t * t + 0.001813 * t * t * t;
+
+    (dpsi * 1.0e-4 * ARCSEC, deps * 1.0e-4 * ARCSEC, eps * ARCSEC)
+}
+
+/// Calculate the rotation from the mean equator and equinox of date to the true equator and
+/// equinox of date.
+///
+pub fn nutation_matrix(jd_tt:f64) -> Matrix3 {
+    let (dpsi, deps, eps) = nutation(jd_tt);
+    matrix::mul(&matrix::rot1(-(eps + deps)), &matrix::mul(&matrix::rot3(-dpsi), &matrix::rot1(eps)))
+}
+
+/// Calculate the equation of the equinoxes, in radians, the difference of apparent from mean
+/// sidereal time, including the IAU 1994 terms in the lunar node.
+///
+pub fn equation_of_equinoxes(jd_tt:f64) -> f64 {
+    let t:f64 = (jd_tt - J2000) / 36525.0;
+    let (dpsi, _deps, eps) = nutation(jd_tt);
+    let node:f64 = arguments(t)[4];
+
+    dpsi * eps.cos() + (0.00264 * node.sin() + 0.000063 * (2.0 * node).sin()) * ARCSEC
+}
+
+/// Calculate the frame bias from the GCRF to the J2000 mean equator and equinox.
+///
+/// References:
+/// -----------
+/// Petit, G. and Luzum, B. (2010). IERS Conventions (2010). IERS Technical Note 36, eq. 5.21.
+///
+pub fn frame_bias() -> Matrix3 {
+    let (xi0, eta0, da0) = (-0.0166170 * ARCSEC, -0.0068192 * ARCSEC, -0.01460 * ARCSEC);
+    matrix::mul(&matrix::rot1(-eta0), &matrix::mul(&matrix::rot2(xi0), &matrix::rot3(da0)))
+}
+
+/// Calculate the polar motion rotation from the pseudo Earth fixed frame to the ITRF.
+///
+fn polar_motion(epoch:&Epoch) -> Matrix3 {
+    matrix::mul(&matrix::rot1(-epoch.yp), &matrix::rot2(-epoch.xp))
+}
+
+/// Calculate the rotation from the GCRF to ECEF by the IAU-76/FK5 reduction: frame bias,
+/// precession, nutation, apparent sidereal time and polar motion.
+///
+/// The celestial pole offsets of the epoch correct the nutation and already carry the offset of
+/// the GCRF pole from the J2000 mean pole, so the frame bias only stands in for them when they are
+/// not given.
+///
+/// References:
+/// -----------
+/// Vallado, D. A. (2013). Fundamentals of Astrodynamics and Applications, 4th edition, sec. 3.7.3.
+///
+pub fn gcrf_matrix(epoch:&Epoch) -> Matrix3 {
+    let (dpsi, deps, eps) = nutation(epoch.jd_tt);
+    let (dpsi, deps) = (dpsi + epoch.ddpsi, deps + epoch.ddeps);
+    let nutation = matrix::mul(&matrix::rot1(-(eps + deps)), &matrix::mul(&matrix::rot3(-dpsi), &matrix::rot1(eps)));
+    let bias = if epoch.ddpsi == 0.0 && epoch.ddeps == 0.0 { frame_bias() } else { matrix::IDENTITY };
+    let celestial = matrix::mul(&nutation, &matrix::mul(&precession(epoch.jd_tt), &bias));
+    let gast:f64 = gmst(epoch.jd_ut1) + equation_of_equinoxes(epoch.jd_tt) + epoch.ddpsi * eps.cos();
+
+    matrix::mul(&polar_motion(epoch), &matrix::mul(&matrix::rot3(gast), &celestial))
+}
+
+/// Calculate the rotation from the true equator, mean equinox frame used by SGP4 to ECEF.
+///
+/// References:
+/// -----------
+/// Vallado, D. A., Crawford, P., Hujsak, R. and Kelso, T. S. (2006). Revisiting Spacetrack
+/// Report #3. AIAA 2006-6753, appendix C.
+///
+pub fn teme_matrix(epoch:&Epoch) -> Matrix3 {
+    matrix::mul(&polar_motion(epoch), &matrix::rot3(gmst(epoch.jd_ut1)))
+}
+
+/// Apply a rotation to a position.
+///
+fn rotate(m:&Matrix3, x:f64, y:f64, z:f64) -> (f64, f64, f64) {
+    let v = matrix::mul_vec(m, [x, y, z]);
+    (v[0], v[1], v[2])
+}
+
+/// Convert a GCRF position to ECEF at an epoch.
+///
+pub fn gcrf2ecef(x:f64, y:f64, z:f64, epoch:&Epoch) -> (f64, f64, f64) {
+    rotate(&gcrf_matrix(epoch), x, y, z)
+}
+
+/// Convert an ECEF position to the GCRF at an epoch.
+///
+pub fn ecef2gcrf(x:f64, y:f64, z:f64, epoch:&Epoch) -> (f64, f64, f64) {
+    rotate(&matrix::transpose(&gcrf_matrix(epoch)), x, y, z)
+}
+
+/// Convert a TEME position to ECEF at an epoch.
+///
+pub fn teme2ecef(x:f64, y:f64, z:f64, epoch:&Epoch) -> (f64, f64, f64) {
+    rotate(&teme_matrix(epoch), x, y, z)
+}
+
+/// Convert an ECEF position to TEME at an epoch.
+///
+pub fn ecef2teme(x:f64, y:f64, z:f64, epoch:&Epoch) -> (f64, f64, f64) {
+    rotate(&matrix::transpose(&teme_matrix(epoch)), x, y, z)
+}
+
+#[test]
+fn test_sidereal() {
+    // sidereal time and the Earth rotation angle agree to well under a second of time at J2000
+    assert!((gmst(J2000).to_degrees() - 280.46061837).abs() < 1.0e-6);
+    assert!((era(J2000) - gmst(J2000)).abs() < 1.0e-4);
+}
+
+#[test]
+fn test_eci() {
+    // Vallado (2013), example 3-15
+    let jd = time::julian_date(2004, 4, 6, 7, 51, 28.386009);
+    let epoch = Epoch::new(jd, 32.0, -0.4399619, -0.140682, 0.333309);
+    let itrf = (-1033479.3830, 7901295.2754, 6380356.5958);
+
+    let (x, y, z) = ecef2teme(itrf.0, itrf.1, itrf.2, &epoch);
+    assert!((x - 5094180.16210).abs() < 1.0e-2 && (y - 6127644.65950).abs() < 1.0e-2 && (z - 6380344.53270).abs() < 1.0e-2);
+    let (x, y, z) = teme2ecef(x, y, z, &epoch);
+    assert!((x - itrf.0).abs() < 1.0e-6 && (y - itrf.1).abs() < 1.0e-6 && (z - itrf.2).abs() < 1.0e-6);
+
+    // without celestial pole offsets the frame bias brings the J2000 result within a meter
+    let (x, y, z) = ecef2gcrf(itrf.0, itrf.1, itrf.2, &epoch);
+    assert!((x - 5102508.958).abs() < 1.0 && (y - 6123011.401).abs() < 1.0 && (z - 6378136.928).abs() < 1.0);
+
+    let epoch = epoch.with_offsets(-0.052195, -0.003875);
+    let (x, y, z) = ecef2gcrf(itrf.0, itrf.1, itrf.2, &epoch);
+    assert!((x - 5102508.958).abs() < 1.0e-2 && (y - 6123011.401).abs() < 1.0e-2 && (z - 6378136.928).abs() < 1.0e-2);
+    let (x, y, z) = gcrf2ecef(x, y, z, &epoch);
+    assert!((x - itrf.0).abs() < 1.0e-6 && (y - itrf.1).abs() < 1.0e-6 && (z - itrf.2).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_eop() {
+    let text = "# mjd xp yp dut1\n58849 0.076 0.282 -0.1772\n58850 0.074 0.283 0.8210\n";
+    let eop = parse(text).unwrap();
+    // UT1-UTC steps by a leap second between the records
+    let r = eop.at(58849.5 + MJD0).unwrap();
+    assert!((r.xp - 0.075).abs() < 1.0e-12 && (r.dut1 + 0.1781).abs() < 1.0e-9);
+    assert!(eop.at(58851.0 + MJD0).is_none());
+
+    let finals = "92 1 1 48622.00 I  0.182987 0.000672  0.168775 0.000345  I-0.1251659 0.0000207  1.8335 0.0201  I    -0.086    0.202     0.130    0.165   .182400   .167900  -.1253000   -16.024    -1.900  \n\
+                  92 1 2 48623.00 I  0.185130 0.000665  0.167527 0.000353  I-0.1273232 0.0000174  2.5299 0.0127  I    -0.059    0.202     0.053    0.165   .184900   .166600  -.1275000   -16.108    -2.000  \n\
+                  92 1 3 48624.00                                                                                                                                                           \n";
+    let eop = parse(finals).unwrap();
+    assert_eq!(eop.records.len(), 2);
+    assert!((eop.records[1].yp - 0.167527).abs() < 1.0e-12 && (eop.records[1].dut1 + 0.1273232).abs() < 1.0e-12);
+}
@@ -16,14 +16,28 @@ pub struct Ellipsoid {
     pub asq : f64,
     pub bsq : f64,
     pub eccsq : f64,
-    pub ecc : f64
+    pub ecc : f64,
+
+    /// geocentric gravitational constant (m^3/s^2)
+    pub gm : f64,
+    /// angular velocity (rad/s)
+    pub omega : f64
 }
 
-/// Construct an Ellipsoid from parameters a (equatorial axis) and f (inverse flattening).
-///
 impl Ellipsoid {
 
+    /// Construct an Ellipsoid from parameters a (equatorial axis) and f (inverse flattening).
+    ///
+    /// The ellipsoid has no normal gravity field: its gravitational constant and angular velocity
+    /// are zero. Use with_gravity for a level ellipsoid.
+    ///
     pub fn new(a:f64, invf:f64) -> Ellipsoid {
+        Ellipsoid::with_gravity(a, invf, 0.0, 0.0)
+    }
+
+    /// Construct a level ellipsoid from a, inverse flattening, GM (m^3/s^2) and angular velocity (rad/s).
+    ///
+    pub fn with_gravity(a:f64, invf:f64, gm:f64, omega:f64) -> Ellipsoid {
         let f:f64 = invf.recip();
         let b:f64 = a*(1f64-f);
         let e1:f64 = ((a*a - b*b)/(a*a)).sqrt();
@@ -34,7 +48,13 @@ impl Ellipsoid {
         let eccsq = 1.0 - bsq/asq;
         let ecc = eccsq.sqrt();
 
-        Ellipsoid {a: a, f: f, b: b, e1: e1, e2: e2, asq: asq, bsq: bsq, eccsq: eccsq, ecc: ecc}
+        Ellipsoid {a: a, f: f, b: b, e1: e1, e2: e2, asq: asq, bsq: bsq, eccsq: eccsq, ecc: ecc, gm: gm, omega: omega}
+    }
+
+    /// Whether the ellipsoid is a level ellipsoid with a normal gravity field.
+    ///
+    pub fn has_gravity(self) -> bool {
+        self.gm > 0.0
     }
 
     /// Calculate ellipsoid radii (geocentric, normal to ellipsoid, and meridional) at a latitude, phi, given in radians.
@@ -69,3 +89,25 @@ impl Ellipsoid {
     (x*x + y*y + z*z).sqrt() - r
     }    
 }
+
+/// Look up a reference ellipsoid by name, e.g. WGS84, GRS80 or Clarke1866.
+///
+/// WGS84, GRS80, WGS72 and GRS67 are level ellipsoids with their defining gravitational constant
+/// and angular velocity; the others have no normal gravity field.
+///
+pub fn named(name:&str) -> Option<Ellipsoid> {
+    match name {
+        "WGS84" => Some(Ellipsoid::with_gravity(6378137.000, 298.257223563, 3.986004418e14, 7.292115e-5)),
+        "GRS80" => Some(Ellipsoid::with_gravity(6378137.000, 298.257222101, 3.986005e14, 7.292115e-5)),
+        "WGS72" => Some(Ellipsoid::with_gravity(6378135.000, 298.26, 3.986008e14, 7.292115147e-5)),
+        "GRS67" => Some(Ellipsoid::with_gravity(6378160.000, 298.247167427, 3.98603e14, 7.2921151467e-5)),
+        "ANS" => Some(Ellipsoid::new(6378160.000, 298.25)),
+        "Clarke1866" => Some(Ellipsoid::new(6378206.400, 294.978698214)),
+        "Clarke1880" => Some(Ellipsoid::new(6378249.145, 293.465)),
+        "Airy1830" => Some(Ellipsoid::new(6377563.396, 299.3249646)),
+        "Bessel1841" => Some(Ellipsoid::new(6377397.155, 299.1528128)),
+        "Intl1924" => Some(Ellipsoid::new(6378388.000, 297.0)),
+        "Krassovsky1940" => Some(Ellipsoid::new(6378245.000, 298.3)),
+        _ => None
+    }
+}
@@ -0,0 +1,298 @@
+use std::f64::consts::FRAC_PI_2;
+
+use ellipsoid;
+use projection;
+use projection::Projection;
+
+/// Albers Equal Area conic projection parameters.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, pp. 98-103.
+///
+#[derive(Copy, Clone)]
+pub struct AlbersEqualArea {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// central meridian (radians)
+    pub lambda0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64,
+
+    pub n : f64,
+    pub c : f64,
+    pub rho0 : f64
+}
+
+impl AlbersEqualArea {
+
+    /// Construct a projection with standard parallels phi1 and phi2 and the false origin at phi0.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi0:f64, phi1:f64, phi2:f64, x0:f64, y0:f64) -> AlbersEqualArea {
+        let m1:f64 = projection::msfn(phi1, ellps.ecc);
+        let m2:f64 = projection::msfn(phi2, ellps.ecc);
+        let q0:f64 = projection::qsfn(phi0, ellps.ecc);
+        let q1:f64 = projection::qsfn(phi1, ellps.ecc);
+        let q2:f64 = projection::qsfn(phi2, ellps.ecc);
+
+        let n:f64 = if (phi1 - phi2).abs() < 1.0e-10 {
+            phi1.sin()
+        } else {
+            (m1 * m1 - m2 * m2) / (q2 - q1)
+        };
+        let c:f64 = m1 * m1 + n * q1;
+        let rho0:f64 = ellps.a * (c - n * q0).sqrt() / n;
+
+        AlbersEqualArea {ellps: ellps, lambda0: lambda0, x0: x0, y0: y0, n: n, c: c, rho0: rho0}
+    }
+}
+
+impl Projection for AlbersEqualArea {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let q:f64 = projection::qsfn(phi, self.ellps.ecc);
+        let rho:f64 = self.ellps.a * (self.c - self.n * q).max(0.0).sqrt() / self.n;
+        let theta:f64 = self.n * (lambda - self.lambda0);
+
+        (self.x0 + rho * theta.sin(), self.y0 + self.rho0 - rho * theta.cos())
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let dx:f64 = x - self.x0;
+        let dy:f64 = self.rho0 - (y - self.y0);
+        let sign:f64 = self.n.signum();
+
+        let rho:f64 = (dx * dx + dy * dy).sqrt();
+        let theta:f64 = (sign * dx).atan2(sign * dy);
+        let q:f64 = (self.c - rho * rho * self.n * self.n / self.ellps.asq) / self.n;
+
+        (self.lambda0 + theta / self.n, projection::phi_from_q(q, self.ellps.ecc))
+    }
+}
+
+/// Lambert Azimuthal Equal Area projection parameters, in the oblique or polar aspect.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, pp. 182-190.
+///
+#[derive(Copy, Clone)]
+pub struct LambertAzimuthalEqualArea {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// longitude of the projection center (radians)
+    pub lambda0 : f64,
+    /// latitude of the projection center (radians)
+    pub phi0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64,
+
+    pub qp : f64,
+    pub rq : f64,
+    pub beta0 : f64,
+    pub d : f64
+}
+
+impl LambertAzimuthalEqualArea {
+
+    /// Construct a projection centered on lambda0, phi0.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi0:f64, x0:f64, y0:f64) -> LambertAzimuthalEqualArea {
+        let qp:f64 = projection::qsfn(FRAC_PI_2, ellps.ecc);
+        let rq:f64 = ellps.a * (qp / 2.0).sqrt();
+        let beta0:f64 = projection::authalic(phi0, ellps.ecc);
+        let d:f64 = if is_polar(phi0) {
+            1.0
+        } else {
+            ellps.a * projection::msfn(phi0, ellps.ecc) / (rq * beta0.cos())
+        };
+
+        LambertAzimuthalEqualArea {ellps: ellps, lambda0: lambda0, phi0: phi0, x0: x0, y0: y0, qp: qp, rq: rq, beta0: beta0, d: d}
+    }
+}
+
+fn is_polar(phi0:f64) -> bool {
+    (phi0.abs() - FRAC_PI_2).abs() < 1.0e-10
+}
+
+impl Projection for LambertAzimuthalEqualArea {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let q:f64 = projection::qsfn(phi, self.ellps.ecc);
+        let dlambda:f64 = lambda - self.lambda0;
+
+        if is_polar(self.phi0) {
+            let pole:f64 = self.phi0.signum();
+            let rho:f64 = self.ellps.a * (self.qp - pole * q).max(0.0).sqrt();
+            return (self.x0 + rho * dlambda.sin(), self.y0 - pole * rho * dlambda.cos());
+        }
+
+        let beta:f64 = (q / self.qp).max(-1.0).min(1.0).asin();
+        let cosz:f64 = self.beta0.sin() * beta.sin() + self.beta0.cos() * beta.cos() * dlambda.cos();
+        let b:f64 = self.rq * (2.0 / (1.0 + cosz)).sqrt();
+
+        let x:f64 = b * self.d * beta.cos() * dlambda.sin();
+        let y:f64 = (b / self.d) * (self.beta0.cos() * beta.sin() - self.beta0.sin() * beta.cos() * dlambda.cos());
+
+        (self.x0 + x, self.y0 + y)
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let dx:f64 = x - self.x0;
+        let dy:f64 = y - self.y0;
+
+        if is_polar(self.phi0) {
+            let pole:f64 = self.phi0.signum();
+            let rho:f64 = (dx * dx + dy * dy).sqrt();
+            let q:f64 = pole * (self.qp - rho * rho / self.ellps.asq);
+            let lambda:f64 = self.lambda0 + dx.atan2(-pole * dy);
+            return (lambda, projection::phi_from_q(q, self.ellps.ecc));
+        }
+
+        let rho:f64 = ((dx / self.d).powi(2) + (self.d * dy).powi(2)).sqrt();
+        if rho < 1.0e-10 {
+            return (self.lambda0, self.phi0);
+        }
+        let ce:f64 = 2.0 * (rho / (2.0 * self.rq)).min(1.0).asin();
+        let beta:f64 = (ce.cos() * self.beta0.sin() + self.d * dy * ce.sin() * self.beta0.cos() / rho).asin();
+        let lambda:f64 = self.lambda0 + (dx * ce.sin()).atan2(
+            self.d * rho * self.beta0.cos() * ce.cos() - self.d * self.d * dy * self.beta0.sin() * ce.sin());
+
+        (lambda, projection::phi_from_q(self.qp * beta.sin(), self.ellps.ecc))
+    }
+}
+
+/// Lambert Cylindrical Equal Area projection parameters, in the normal aspect.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, pp. 76-85.
+///
+#[derive(Copy, Clone)]
+pub struct CylindricalEqualArea {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// central meridian (radians)
+    pub lambda0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64,
+
+    pub k0 : f64
+}
+
+impl CylindricalEqualArea {
+
+    /// Construct a projection true to scale along the standard parallels at +/- phi1.
+    ///
+    pub fn new(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi1:f64, x0:f64, y0:f64) -> CylindricalEqualArea {
+        let k0:f64 = projection::msfn(phi1, ellps.ecc);
+
+        CylindricalEqualArea {ellps: ellps, lambda0: lambda0, x0: x0, y0: y0, k0: k0}
+    }
+}
+
+impl Projection for CylindricalEqualArea {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let q:f64 = projection::qsfn(phi, self.ellps.ecc);
+        let x:f64 = self.ellps.a * self.k0 * (lambda - self.lambda0);
+        let y:f64 = self.ellps.a * q / (2.0 * self.k0);
+
+        (self.x0 + x, self.y0 + y)
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let q:f64 = 2.0 * (y - self.y0) * self.k0 / self.ellps.a;
+        let lambda:f64 = self.lambda0 + (x - self.x0) / (self.ellps.a * self.k0);
+
+        (lambda, projection::phi_from_q(q, self.ellps.ecc))
+    }
+}
+
+#[test]
+fn test_albers() {
+    // Snyder (1987) numerical example, p. 292
+    let expected = (1885472.7, 1535925.0);
+    let tolerance:f64 = 0.1;
+
+    let clarke1866 = ellipsoid::Ellipsoid::new(6378206.400, 294.978698214);
+    let proj = AlbersEqualArea::new(clarke1866, -96f64.to_radians(), 23f64.to_radians(),
+        29.5f64.to_radians(), 45.5f64.to_radians(), 0.0, 0.0);
+
+    let (x, y) = proj.forward(-75f64.to_radians(), 35f64.to_radians());
+    println!("x {0} y {1}", x, y);
+    assert!((x - expected.0).abs() < tolerance);
+    assert!((y - expected.1).abs() < tolerance);
+
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() + 75.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - 35.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_laea() {
+    // EPSG Guidance Note 7-2 example, ETRS89 / LAEA Europe
+    let expected = (3962799.45, 2999718.85);
+    let tolerance:f64 = 0.01;
+
+    let grs80 = ellipsoid::Ellipsoid::new(6378137.000, 298.257222101);
+    let proj = LambertAzimuthalEqualArea::new(grs80, 10f64.to_radians(), 52f64.to_radians(), 4321000.0, 3210000.0);
+
+    let (x, y) = proj.forward(5f64.to_radians(), 50f64.to_radians());
+    println!("x {0} y {1}", x, y);
+    assert!((x - expected.0).abs() < tolerance);
+    assert!((y - expected.1).abs() < tolerance);
+
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() - 5.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - 50.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_laea_polar() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let proj = LambertAzimuthalEqualArea::new(wgs84, 0.0, 90f64.to_radians(), 0.0, 0.0);
+
+    let (x, y) = proj.forward(45f64.to_radians(), 60f64.to_radians());
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() - 45.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - 60.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_cea() {
+    // the whole map has the area of the ellipsoid
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let proj = CylindricalEqualArea::new(wgs84, 0.0, 30f64.to_radians(), 0.0, 0.0);
+
+    let (x, y) = proj.forward(180f64.to_radians(), 90f64.to_radians());
+    let rq:f64 = wgs84.a * (projection::qsfn(FRAC_PI_2, wgs84.ecc) / 2.0).sqrt();
+    let area:f64 = 4.0 * x * y;
+    assert!((area / (4.0 * ::std::f64::consts::PI * rq * rq) - 1.0).abs() < 1.0e-12);
+
+    let (x, y) = proj.forward(-100f64.to_radians(), 42f64.to_radians());
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() + 100.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - 42.0).abs() < 1.0e-9);
+}
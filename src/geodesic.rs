@@ -0,0 +1,170 @@
+use std::f64::consts::PI;
+
+use ellipsoid;
+
+/// Solve the inverse geodesic problem by the iterative method of Vincenty.
+///
+/// Returns the distance s12 in meters and the forward azimuths at both points in radians,
+/// measured clockwise from north. Longitudes and latitudes are given in radians. None is returned
+/// when the iteration fails to converge, as it may for nearly antipodal points.
+///
+/// References:
+/// -----------
+/// Vincenty, T. (1975). Direct and inverse solutions of geodesics on the
+/// ellipsoid with application of nested equations. Survey Review 23(176):88-93.
+///
+pub fn inverse(ellps:ellipsoid::Ellipsoid, lambda1:f64, phi1:f64, lambda2:f64, phi2:f64) -> Option<(f64, f64, f64)> {
+    let f:f64 = ellps.f;
+    let l:f64 = lambda2 - lambda1;
+    let u1:f64 = ((1.0 - f) * phi1.tan()).atan();
+    let u2:f64 = ((1.0 - f) * phi2.tan()).atan();
+    let (su1, cu1) = (u1.sin(), u1.cos());
+    let (su2, cu2) = (u2.sin(), u2.cos());
+
+    let mut lambda:f64 = l;
+    let mut sigma:f64 = 0.0;
+    let mut ssig:f64 = 0.0;
+    let mut csig:f64 = 1.0;
+    let mut cos2alpha:f64 = 1.0;
+    let mut cos2sigm:f64 = 0.0;
+    let mut converged = false;
+
+    for _ in 0..200 {
+        let (sl, cl) = (lambda.sin(), lambda.cos());
+        ssig = ((cu2 * sl).powi(2) + (cu1 * su2 - su1 * cu2 * cl).powi(2)).sqrt();
+        if ssig == 0.0 {
+            // coincident points
+            return Some((0.0, 0.0, 0.0));
+        }
+        csig = su1 * su2 + cu1 * cu2 * cl;
+        sigma = ssig.atan2(csig);
+        let salpha:f64 = cu1 * cu2 * sl / ssig;
+        cos2alpha = 1.0 - salpha * salpha;
+        // equatorial lines have cos2alpha of zero
+        cos2sigm = if cos2alpha != 0.0 { csig - 2.0 * su1 * su2 / cos2alpha } else { 0.0 };
+        let c:f64 = f / 16.0 * cos2alpha * (4.0 + f * (4.0 - 3.0 * cos2alpha));
+        let last:f64 = lambda;
+        lambda = l + (1.0 - c) * f * salpha
+            * (sigma + c * ssig * (cos2sigm + c * csig * (-1.0 + 2.0 * cos2sigm * cos2sigm)));
+        if (lambda - last).abs() < 1.0e-12 {
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return None;
+    }
+
+    let usq:f64 = cos2alpha * (ellps.asq - ellps.bsq) / ellps.bsq;
+    let ca:f64 = 1.0 + usq / 16384.0 * (4096.0 + usq * (-768.0 + usq * (320.0 - 175.0 * usq)));
+    let cb:f64 = usq / 1024.0 * (256.0 + usq * (-128.0 + usq * (74.0 - 47.0 * usq)));
+    let dsigma:f64 = cb * ssig * (cos2sigm + cb / 4.0 * (csig * (-1.0 + 2.0 * cos2sigm * cos2sigm)
+        - cb / 6.0 * cos2sigm * (-3.0 + 4.0 * ssig * ssig) * (-3.0 + 4.0 * cos2sigm * cos2sigm)));
+    let s12:f64 = ellps.b * ca * (sigma - dsigma);
+
+    let (sl, cl) = (lambda.sin(), lambda.cos());
+    let azi1:f64 = (cu2 * sl).atan2(cu1 * su2 - su1 * cu2 * cl);
+    let azi2:f64 = (cu1 * sl).atan2(-su1 * cu2 + cu1 * su2 * cl);
+
+    Some((s12, azi1, azi2))
+}
+
+/// Solve the direct geodesic problem by the method of Vincenty.
+///
+/// Returns the longitude, latitude and forward azimuth, in radians, reached by travelling
+/// s12 meters from lambda1, phi1 on azimuth azi1.
+///
+/// References:
+/// -----------
+/// Vincenty, T. (1975). Direct and inverse solutions of geodesics on the
+/// ellipsoid with application of nested equations. Survey Review 23(176):88-93.
+///
+pub fn direct(ellps:ellipsoid::Ellipsoid, lambda1:f64, phi1:f64, azi1:f64, s12:f64) -> (f64, f64, f64) {
+    let f:f64 = ellps.f;
+    let (sa1, ca1) = (azi1.sin(), azi1.cos());
+    let tu1:f64 = (1.0 - f) * phi1.tan();
+    let cu1:f64 = 1.0 / (1.0 + tu1 * tu1).sqrt();
+    let su1:f64 = tu1 * cu1;
+    let sigma1:f64 = tu1.atan2(ca1);
+    let salpha:f64 = cu1 * sa1;
+    let cos2alpha:f64 = 1.0 - salpha * salpha;
+
+    let usq:f64 = cos2alpha * (ellps.asq - ellps.bsq) / ellps.bsq;
+    let ca:f64 = 1.0 + usq / 16384.0 * (4096.0 + usq * (-768.0 + usq * (320.0 - 175.0 * usq)));
+    let cb:f64 = usq / 1024.0 * (256.0 + usq * (-128.0 + usq * (74.0 - 47.0 * usq)));
+
+    let mut sigma:f64 = s12 / (ellps.b * ca);
+    let mut cos2sigm:f64 = (2.0 * sigma1 + sigma).cos();
+    for _ in 0..200 {
+        cos2sigm = (2.0 * sigma1 + sigma).cos();
+        let ssig:f64 = sigma.sin();
+        let dsigma:f64 = cb * ssig * (cos2sigm + cb / 4.0 * (sigma.cos() * (-1.0 + 2.0 * cos2sigm * cos2sigm)
+            - cb / 6.0 * cos2sigm * (-3.0 + 4.0 * ssig * ssig) * (-3.0 + 4.0 * cos2sigm * cos2sigm)));
+        let last:f64 = sigma;
+        sigma = s12 / (ellps.b * ca) + dsigma;
+        if (sigma - last).abs() < 1.0e-12 {
+            break;
+        }
+    }
+
+    let (ssig, csig) = (sigma.sin(), sigma.cos());
+    let tmp:f64 = su1 * ssig - cu1 * csig * ca1;
+    let phi2:f64 = (su1 * csig + cu1 * ssig * ca1).atan2((1.0 - f) * (salpha * salpha + tmp * tmp).sqrt());
+    let lambda:f64 = (ssig * sa1).atan2(cu1 * csig - su1 * ssig * ca1);
+    let c:f64 = f / 16.0 * cos2alpha * (4.0 + f * (4.0 - 3.0 * cos2alpha));
+    let l:f64 = lambda - (1.0 - c) * f * salpha
+        * (sigma + c * ssig * (cos2sigm + c * csig * (-1.0 + 2.0 * cos2sigm * cos2sigm)));
+    let azi2:f64 = salpha.atan2(-tmp);
+
+    // normalise longitude to the range -pi..pi
+    let lambda2:f64 = (lambda1 + l + 3.0 * PI) % (2.0 * PI) - PI;
+
+    (lambda2, phi2, azi2)
+}
+
+#[test]
+fn test_vincenty_inverse() {
+    // Flinders Peak to Buninyong, Vincenty (1975)
+    let expected = (54972.271, 306.0 + 52.0/60.0 + 5.37/3600.0, 127.0 + 10.0/60.0 + 25.07/3600.0);
+    let grs80 = ellipsoid::Ellipsoid::new(6378137.000, 298.257222101);
+
+    let phi1:f64 = -(37.0 + 57.0/60.0 + 3.72030/3600.0);
+    let lambda1:f64 = 144.0 + 25.0/60.0 + 29.52440/3600.0;
+    let phi2:f64 = -(37.0 + 39.0/60.0 + 10.15610/3600.0);
+    let lambda2:f64 = 143.0 + 55.0/60.0 + 35.38390/3600.0;
+
+    let (s12, azi1, azi2) = inverse(grs80, lambda1.to_radians(), phi1.to_radians(), lambda2.to_radians(), phi2.to_radians()).unwrap();
+    println!("s12 {0} azi1 {1} azi2 {2}", s12, azi1.to_degrees(), azi2.to_degrees());
+    assert!((s12 - expected.0).abs() < 0.001);
+    assert!((azi1.to_degrees() + 360.0 - expected.1).abs() < 0.01/3600.0);
+    // the reverse azimuth differs from the forward azimuth by 180 degrees
+    assert!((azi2.to_degrees() + 180.0 - expected.2).abs() < 0.01/3600.0);
+}
+
+#[test]
+fn test_vincenty_direct() {
+    let grs80 = ellipsoid::Ellipsoid::new(6378137.000, 298.257222101);
+
+    let phi1:f64 = -(37.0 + 57.0/60.0 + 3.72030/3600.0);
+    let lambda1:f64 = 144.0 + 25.0/60.0 + 29.52440/3600.0;
+    let azi1:f64 = 306.0 + 52.0/60.0 + 5.37/3600.0;
+
+    let (lambda2, phi2, _azi2) = direct(grs80, lambda1.to_radians(), phi1.to_radians(), azi1.to_radians(), 54972.271);
+    println!("lambda2 {0} phi2 {1}", lambda2.to_degrees(), phi2.to_degrees());
+    assert!((phi2.to_degrees() + 37.0 + 39.0/60.0 + 10.15610/3600.0).abs() < 0.001/3600.0);
+    assert!((lambda2.to_degrees() - (143.0 + 55.0/60.0 + 35.38390/3600.0)).abs() < 0.001/3600.0);
+}
+
+#[test]
+fn test_antipodal() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    // nearly antipodal points that still converge are reached again by the direct solution
+    for &(lon, lat) in [(179.0f64, 0.5f64), (179.5, 1.0), (178.0, -2.0)].iter() {
+        let (s12, azi1, _azi2) = inverse(wgs84, 0.0, 0.0, lon.to_radians(), lat.to_radians()).unwrap();
+        assert!(s12 > 19.7e6 && s12 < 20.0e6);
+        let (lambda2, phi2, _azi2) = direct(wgs84, 0.0, 0.0, azi1, s12);
+        assert!((lambda2.to_degrees() - lon).abs() < 1.0e-9 && (phi2.to_degrees() - lat).abs() < 1.0e-9);
+    }
+    // Vincenty's iteration fails to converge between 0N 0E and 0.5N 179.7E
+    assert!(inverse(wgs84, 0.0, 0.0, 179.7f64.to_radians(), 0.5f64.to_radians()).is_none());
+}
@@ -0,0 +1,156 @@
+use std::io;
+use std::path::Path;
+
+use grid;
+use grid::{invalid, Raster};
+
+/// Geoid model given as a grid of undulations N, the height of the geoid above the ellipsoid.
+///
+/// Orthometric height H, above mean sea level, relates to ellipsoidal height h by h = H + N.
+///
+#[derive(Clone, Debug)]
+pub struct Geoid {
+    /// undulations (m)
+    pub raster : Raster,
+    /// whether to interpolate bicubically rather than bilinearly
+    pub cubic : bool
+}
+
+impl Geoid {
+
+    /// Interpolate the undulation, in meters, at a point given in radians, or None off the grid.
+    ///
+    pub fn undulation(&self, lambda:f64, phi:f64) -> Option<f64> {
+        let (lon, lat) = (lambda.to_degrees(), phi.to_degrees());
+        if self.cubic { self.raster.bicubic(0, lon, lat) } else { self.raster.bilinear(0, lon, lat) }
+    }
+
+    /// Convert an ellipsoidal height to an orthometric height.
+    ///
+    pub fn orthometric(&self, lambda:f64, phi:f64, h:f64) -> Option<f64> {
+        Some(h - self.undulation(lambda, phi)?)
+    }
+
+    /// Convert an orthometric height to an ellipsoidal height.
+    ///
+    pub fn ellipsoidal(&self, lambda:f64, phi:f64, height:f64) -> Option<f64> {
+        Some(height + self.undulation(lambda, phi)?)
+    }
+}
+
+/// Parse a geoid grid in the PGM format distributed with GeographicLib, e.g. egm96-5.pgm.
+///
+/// The binary PGM image holds 16 bit unsigned samples, most significant byte first, with rows
+/// from 90N to 90S and columns eastward from 0E, not repeating 360E. Header comments give the
+/// offset and scale converting samples to meters.
+///
+/// References:
+/// -----------
+/// Karney, C. F. F. GeographicLib, Geoid height. https://geographiclib.sourceforge.io/C++/doc/geoid.html
+///
+pub fn parse_pgm(bytes:&[u8]) -> io::Result<Raster> {
+    let (mut offset, mut scale) = (None, None);
+    let mut fields:Vec<String> = Vec::new();
+    let mut pos:usize = 0;
+
+    // magic number, width, height and maximum value, separated by white space and comments
+    while fields.len() < 4 {
+        while pos < bytes.len() && (bytes[pos] as char).is_whitespace() {
+            pos += 1;
+        }
+        if pos >= bytes.len() {
+            return Err(invalid("PGM header is truncated"));
+        }
+        let start = pos;
+        if bytes[pos] == b'#' {
+            while pos < bytes.len() && bytes[pos] != b'\n' {
+                pos += 1;
+            }
+            let comment = String::from_utf8_lossy(&bytes[start + 1..pos]).to_string();
+            let words:Vec<&str> = comment.split_whitespace().collect();
+            match words.as_slice() {
+                ["Offset", value] => offset = value.parse::<f64>().ok(),
+                ["Scale", value] => scale = value.parse::<f64>().ok(),
+                _ => ()
+            }
+            continue;
+        }
+        while pos < bytes.len() && !(bytes[pos] as char).is_whitespace() {
+            pos += 1;
+        }
+        fields.push(String::from_utf8_lossy(&bytes[start..pos]).to_string());
+    }
+    // a single white space character separates the header from the samples
+    pos += 1;
+
+    let number = |i:usize| fields[i].parse::<usize>().map_err(|_| invalid("PGM header is malformed"));
+    let (width, height, max) = (number(1)?, number(2)?, number(3)?);
+    if fields[0] != "P5" || max != 65535 || width < 2 || height < 2 {
+        return Err(invalid("geoid PGM must be a binary image of 16 bit samples"));
+    }
+    let (offset, scale) = match (offset, scale) {
+        (Some(offset), Some(scale)) => (offset, scale),
+        _ => return Err(invalid("geoid PGM has no Offset and Scale comments"))
+    };
+
+    let mut rdr = grid::Reader::new(bytes, true);
+    rdr.offset = pos;
+    let mut values = vec![0.0f32; width * height];
+    for row in 0..height {
+        for col in 0..width {
+            values[(height - 1 - row) * width + col] = (offset + scale * rdr.uint(2)? as f64) as f32;
+        }
+    }
+    Ok(Raster {west: 0.0, south: -90.0, dlon: 360.0 / width as f64, dlat: 180.0 / (height - 1) as f64,
+        rows: height, cols: width, bands: vec![values], nodata: None})
+}
+
+/// Read a geoid grid by file extension: .pgm GeographicLib, .gtx, or .bin NGS binary as for GEOID18.
+///
+pub fn read(path:&str, cubic:bool) -> io::Result<Geoid> {
+    let extension = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let raster = match extension.as_str() {
+        "pgm" => parse_pgm(&grid::contents(path)?)?,
+        "gtx" => grid::read_gtx(path)?,
+        "bin" => grid::read_ngs(path)?,
+        _ => return Err(invalid(&format!("unknown geoid format {}", path)))
+    };
+    Ok(Geoid {raster: raster, cubic: cubic})
+}
+
+#[test]
+fn test_geoid_pgm() {
+    // a 90 by 60 degree global grid holding N = lat / 10 + 2 cos(lon) in steps of 0.01 m
+    let (width, height) = (4usize, 4usize);
+    let mut bytes:Vec<u8> = Vec::new();
+    bytes.extend(b"P5\n# Geoid file in PGM format\n# Offset -100\n# Scale 0.01\n4 4\n65535\n");
+    for row in 0..height {
+        for col in 0..width {
+            let (lat, lon) = (90.0 - 60.0 * row as f64, 90.0 * col as f64);
+            let n:f64 = lat / 10.0 + 2.0 * lon.to_radians().cos();
+            let v = ((n + 100.0) / 0.01).round() as u16;
+            bytes.extend(&[(v >> 8) as u8, v as u8]);
+        }
+    }
+    let raster = parse_pgm(&bytes).unwrap();
+    assert!(raster.wraps());
+
+    let linear = Geoid {raster: raster.clone(), cubic: false};
+    let cubic = Geoid {raster: raster, cubic: true};
+    for geoid in [&linear, &cubic].iter() {
+        // nodes are reproduced exactly, including those reached across the antimeridian
+        let n = geoid.undulation((-90.0f64).to_radians(), 30.0f64.to_radians()).unwrap();
+        assert!((n - 3.0).abs() < 1.0e-6);
+        let h = geoid.ellipsoidal(0.0, 30.0f64.to_radians(), 10.0).unwrap();
+        assert!((h - 15.0).abs() < 1.0e-6);
+        assert!((geoid.orthometric(0.0, 30.0f64.to_radians(), h).unwrap() - 10.0).abs() < 1.0e-9);
+    }
+
+    // between nodes the cubic follows the cosine more closely
+    let (lambda, phi) = (45.0f64.to_radians(), 0.0f64.to_radians());
+    let exact:f64 = 2.0 * lambda.cos();
+    let e1 = (linear.undulation(lambda, phi).unwrap() - exact).abs();
+    let e2 = (cubic.undulation(lambda, phi).unwrap() - exact).abs();
+    println!("bilinear error {0} bicubic error {1}", e1, e2);
+    assert!(e2 < e1);
+}
@@ -0,0 +1,689 @@
+use std::io;
+use std::path::Path;
+
+use grid::{contents, invalid, Raster, Reader};
+
+/// TIFF field of the first image directory, with its values widened to f64.
+///
+struct Field {
+    tag : u16,
+    values : Vec<f64>
+}
+
+/// Read the fields of the image directory at an offset.
+///
+fn directory(bytes:&[u8], big_endian:bool, offset:usize) -> io::Result<Vec<Field>> {
+    let mut rdr = Reader::new(bytes, big_endian);
+    rdr.offset = offset;
+    let count = rdr.uint(2)? as usize;
+
+    let mut fields = Vec::with_capacity(count);
+    for _ in 0..count {
+        let tag = rdr.uint(2)? as u16;
+        let kind = rdr.uint(2)?;
+        let n = rdr.uint(4)? as usize;
+        let size:usize = match kind {
+            1 | 2 | 6 | 7 => 1,
+            3 | 8 => 2,
+            4 | 9 | 11 => 4,
+            5 | 10 | 12 => 8,
+            _ => return Err(invalid("unknown TIFF field type"))
+        };
+        // values of up to four bytes are held in place of the offset
+        let mut values = Reader::new(bytes, big_endian);
+        values.offset = if n * size <= 4 { rdr.offset } else { rdr.uint(4)? as usize };
+        if n * size <= 4 {
+            rdr.take(4)?;
+        }
+        let mut v = Vec::with_capacity(n);
+        for _ in 0..n {
+            v.push(match kind {
+                11 => values.f32()? as f64,
+                12 => values.f64()?,
+                5 => { let num = values.uint(4)? as f64; num / values.uint(4)? as f64 },
+                _ => values.uint(size)? as f64
+            });
+        }
+        fields.push(Field {tag: tag, values: v});
+    }
+    Ok(fields)
+}
+
+/// Reader of a DEFLATE bit stream, least significant bit first.
+///
+struct Bits<'a> {
+    bytes : &'a [u8],
+    bit : usize
+}
+
+impl<'a> Bits<'a> {
+
+    fn bits(&mut self, n:usize) -> io::Result<usize> {
+        let mut v:usize = 0;
+        for i in 0..n {
+            let byte = *self.bytes.get(self.bit / 8).ok_or_else(|| invalid("compressed TIFF data is truncated"))?;
+            v |= (((byte >> (self.bit % 8)) & 1) as usize) << i;
+            self.bit += 1;
+        }
+        Ok(v)
+    }
+}
+
+/// Canonical Huffman code given by the number of codes of each length and the symbols in code order.
+///
+struct Huffman {
+    counts : [usize; 16],
+    symbols : Vec<usize>
+}
+
+impl Huffman {
+
+    fn new(lengths:&[usize]) -> Huffman {
+        let mut counts = [0usize; 16];
+        for &l in lengths.iter() {
+            counts[l] += 1;
+        }
+        counts[0] = 0;
+        let mut symbols = Vec::with_capacity(lengths.len());
+        for l in 1..16 {
+            for (s, &length) in lengths.iter().enumerate() {
+                if length == l {
+                    symbols.push(s);
+                }
+            }
+        }
+        Huffman {counts: counts, symbols: symbols}
+    }
+
+    fn decode(&self, bits:&mut Bits) -> io::Result<usize> {
+        let (mut code, mut first, mut index) = (0usize, 0usize, 0usize);
+        for l in 1..16 {
+            code |= bits.bits(1)?;
+            let count = self.counts[l];
+            if code < first + count {
+                return Ok(self.symbols[index + code - first]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(invalid("invalid Huffman code in compressed TIFF data"))
+    }
+}
+
+/// Base lengths and distances of the DEFLATE length and distance symbols, with their extra bits.
+///
+const LENGTH_BASE:[usize; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59,
+    67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA:[usize; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE:[usize; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769,
+    1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DISTANCE_EXTRA:[usize; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10,
+    11, 11, 12, 12, 13, 13];
+
+/// Decompress a zlib stream, as used by TIFF DEFLATE compression.
+///
+/// References:
+/// -----------
+/// Deutsch, P. (1996). DEFLATE Compressed Data Format Specification version 1.3, RFC 1951.
+///
+/// Deutsch, P. & Gailly, J-L. (1996). ZLIB Compressed Data Format Specification version 3.3, RFC 1950.
+///
+pub fn inflate(bytes:&[u8]) -> io::Result<Vec<u8>> {
+    if bytes.len() < 2 || bytes[0] & 0x0f != 8 || ((bytes[0] as u16) << 8 | bytes[1] as u16) % 31 != 0 {
+        return Err(invalid("compressed TIFF data is not a zlib stream"));
+    }
+    let mut bits = Bits {bytes: &bytes[2..], bit: 0};
+    let mut out:Vec<u8> = Vec::new();
+    loop {
+        let last = bits.bits(1)? == 1;
+        match bits.bits(2)? {
+            0 => {
+                // stored block, from the next byte boundary
+                bits.bit = (bits.bit + 7) / 8 * 8;
+                let len = bits.bits(16)?;
+                if bits.bits(16)? != !len & 0xffff {
+                    return Err(invalid("corrupt stored block in compressed TIFF data"));
+                }
+                let start = bits.bit / 8;
+                let block = bits.bytes.get(start..start + len).ok_or_else(|| invalid("compressed TIFF data is truncated"))?;
+                out.extend_from_slice(block);
+                bits.bit += 8 * len;
+            },
+            1 => {
+                let mut lengths = [8usize; 288];
+                for l in lengths[144..256].iter_mut() {
+                    *l = 9;
+                }
+                for l in lengths[256..280].iter_mut() {
+                    *l = 7;
+                }
+                codes(&mut bits, &mut out, &Huffman::new(&lengths), &Huffman::new(&[5; 30]))?;
+            },
+            2 => {
+                let (nlen, ndist, ncode) = (bits.bits(5)? + 257, bits.bits(5)? + 1, bits.bits(4)? + 4);
+                let order = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+                let mut code_lengths = [0usize; 19];
+                for &i in order[..ncode].iter() {
+                    code_lengths[i] = bits.bits(3)?;
+                }
+                let lencode = Huffman::new(&code_lengths);
+                let mut lengths:Vec<usize> = Vec::with_capacity(nlen + ndist);
+                while lengths.len() < nlen + ndist {
+                    let symbol = lencode.decode(&mut bits)?;
+                    let (value, repeat) = match symbol {
+                        0..=15 => (symbol, 1),
+                        16 => (*lengths.last().ok_or_else(|| invalid("corrupt code lengths in compressed TIFF data"))?, 3 + bits.bits(2)?),
+                        17 => (0, 3 + bits.bits(3)?),
+                        _ => (0, 11 + bits.bits(7)?)
+                    };
+                    lengths.extend(::std::iter::repeat(value).take(repeat));
+                }
+                if lengths.len() > nlen + ndist {
+                    return Err(invalid("corrupt code lengths in compressed TIFF data"));
+                }
+                codes(&mut bits, &mut out, &Huffman::new(&lengths[..nlen]), &Huffman::new(&lengths[nlen..]))?;
+            },
+            _ => return Err(invalid("invalid block type in compressed TIFF data"))
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+/// Decode the literals and back references of a Huffman coded DEFLATE block.
+///
+fn codes(bits:&mut Bits, out:&mut Vec<u8>, lencode:&Huffman, distcode:&Huffman) -> io::Result<()> {
+    loop {
+        let symbol = lencode.decode(bits)?;
+        if symbol < 256 {
+            out.push(symbol as u8);
+        } else if symbol == 256 {
+            return Ok(());
+        } else {
+            let i = symbol - 257;
+            if i >= 29 {
+                return Err(invalid("invalid length in compressed TIFF data"));
+            }
+            let length = LENGTH_BASE[i] + bits.bits(LENGTH_EXTRA[i])?;
+            let d = distcode.decode(bits)?;
+            if d >= 30 {
+                return Err(invalid("invalid distance in compressed TIFF data"));
+            }
+            let distance = DISTANCE_BASE[d] + bits.bits(DISTANCE_EXTRA[d])?;
+            if distance > out.len() {
+                return Err(invalid("distance too far back in compressed TIFF data"));
+            }
+            // copies may overlap the bytes they produce
+            let start = out.len() - distance;
+            for k in 0..length {
+                let b = out[start + k];
+                out.push(b);
+            }
+        }
+    }
+}
+
+/// Decompress TIFF LZW data: codes most significant bit first, starting at 9 bits and widening
+/// one code early, with 256 clearing the table and 257 ending the data.
+///
+/// References:
+/// -----------
+/// Adobe (1992). TIFF Revision 6.0, section 13.
+///
+pub fn lzw(bytes:&[u8]) -> io::Result<Vec<u8>> {
+    let fresh = || -> Vec<Vec<u8>> {
+        let mut table:Vec<Vec<u8>> = (0..256).map(|b| vec![b as u8]).collect();
+        table.push(Vec::new());
+        table.push(Vec::new());
+        table
+    };
+    let mut table = fresh();
+    let mut width:usize = 9;
+    let mut bit:usize = 0;
+    let mut previous:Option<Vec<u8>> = None;
+    let mut out:Vec<u8> = Vec::new();
+    while bit + width <= 8 * bytes.len() {
+        let mut code:usize = 0;
+        for _ in 0..width {
+            code = (code << 1) | ((bytes[bit / 8] >> (7 - bit % 8)) & 1) as usize;
+            bit += 1;
+        }
+        if code == 257 {
+            break;
+        }
+        if code == 256 {
+            table = fresh();
+            width = 9;
+            previous = None;
+            continue;
+        }
+        // a code not yet in the table repeats the previous string and its first byte
+        let entry = match previous {
+            _ if code < 256 || (code > 257 && code < table.len()) => table[code].clone(),
+            Some(ref p) if code == table.len() => {
+                let mut e = p.clone();
+                e.push(p[0]);
+                e
+            },
+            _ => return Err(invalid("invalid LZW code in compressed TIFF data"))
+        };
+        out.extend_from_slice(&entry);
+        if let Some(mut p) = previous.take() {
+            p.push(entry[0]);
+            table.push(p);
+        }
+        previous = Some(entry);
+        if table.len() + 1 >= 1 << width && width < 12 {
+            width += 1;
+        }
+    }
+    Ok(out)
+}
+
+/// Undo a TIFF predictor over the rows of a decompressed tile of 32 bit samples in place.
+///
+/// Predictor 2 differences the samples of each row as integers; predictor 3 splits each row into
+/// planes of bytes, most significant first, and differences the bytes.
+///
+/// References:
+/// -----------
+/// Adobe (2005). Adobe Photoshop TIFF Technical Note 3, floating point predictor.
+///
+fn unpredict(data:&mut [u8], predictor:usize, row_samples:usize, stride:usize, big_endian:bool) -> io::Result<()> {
+    let row_bytes = 4 * row_samples;
+    if predictor == 1 || row_bytes == 0 {
+        return Ok(());
+    }
+    for row in data.chunks_mut(row_bytes) {
+        if row.len() < row_bytes {
+            break;
+        }
+        match predictor {
+            2 => {
+                let word = |b:&[u8]| if big_endian { u32::from_be_bytes([b[0], b[1], b[2], b[3]]) } else { u32::from_le_bytes([b[0], b[1], b[2], b[3]]) };
+                for i in stride..row_samples {
+                    let v = word(&row[4 * i..4 * i + 4]).wrapping_add(word(&row[4 * (i - stride)..4 * (i - stride) + 4]));
+                    let b = if big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+                    row[4 * i..4 * i + 4].copy_from_slice(&b);
+                }
+            },
+            3 => {
+                for i in stride..row_bytes {
+                    row[i] = row[i].wrapping_add(row[i - stride]);
+                }
+                let planes = row.to_vec();
+                for i in 0..row_samples {
+                    let b = [planes[i], planes[row_samples + i], planes[2 * row_samples + i], planes[3 * row_samples + i]];
+                    if big_endian {
+                        row[4 * i..4 * i + 4].copy_from_slice(&b);
+                    } else {
+                        row[4 * i..4 * i + 4].copy_from_slice(&[b[3], b[2], b[1], b[0]]);
+                    }
+                }
+            },
+            _ => return Err(invalid("unknown TIFF predictor"))
+        }
+    }
+    Ok(())
+}
+
+/// Description of a band of a GeoTIFF grid, from its GDAL metadata.
+///
+#[derive(Clone, Debug, Default)]
+pub struct Band {
+    /// what the band holds, e.g. latitude_offset or geoid_undulation
+    pub description : String,
+    /// unit of the values, e.g. arc-second or metre
+    pub unit : String,
+    /// whether positive values are to the west
+    pub positive_west : bool
+}
+
+/// Value of an attribute of an XML element.
+///
+fn attribute(element:&str, name:&str) -> Option<String> {
+    let start = element.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let end = element[start..].find('"')? + start;
+    Some(element[start..end].to_string())
+}
+
+/// Describe the bands from the GDAL_METADATA XML, whose items name a band by sample, e.g.
+/// `<Item name="DESCRIPTION" sample="1" role="description">longitude_offset</Item>`.
+///
+fn describe(xml:&str, samples:usize) -> Vec<Band> {
+    let mut bands = vec![Band::default(); samples];
+    for item in xml.split("<Item").skip(1) {
+        let (open, rest) = match item.find('>') {
+            Some(i) => (&item[..i], &item[i + 1..]),
+            None => continue
+        };
+        let value = rest.split("</Item>").next().unwrap_or("").trim();
+        let sample = match attribute(open, "sample").and_then(|s| s.parse::<usize>().ok()) {
+            Some(s) if s < samples => s,
+            _ => continue
+        };
+        let name = attribute(open, "name").unwrap_or_default().to_lowercase();
+        let role = attribute(open, "role").unwrap_or_default();
+        if role == "description" || name == "description" {
+            bands[sample].description = value.to_string();
+        } else if role == "unittype" || name == "unittype" {
+            bands[sample].unit = value.to_string();
+        } else if name == "positive_value" {
+            bands[sample].positive_west = value.eq_ignore_ascii_case("west");
+        }
+    }
+    bands
+}
+
+/// Parse a GeoTIFF grid of the kind distributed for PROJ.
+///
+/// Only the first image is read. Samples must be 32 bit floats, in strips or tiles, interleaved or
+/// in separate planes, and uncompressed or compressed by DEFLATE or LZW with any predictor; each
+/// sample becomes a band of the raster, described by the GDAL metadata where present. The
+/// georeference is taken from the model tie point and pixel scale, allowing for pixel is area or
+/// point.
+///
+/// References:
+/// -----------
+/// Adobe (1992). TIFF Revision 6.0.
+///
+/// OGC (2019). GeoTIFF Standard 1.1, OGC 19-008r4.
+///
+/// PROJ. Geodetic TIFF grids (GTG). https://github.com/OSGeo/PROJ-data/blob/master/grid_tools/README.md
+///
+pub fn parse_grid(bytes:&[u8]) -> io::Result<(Raster, Vec<Band>)> {
+    if bytes.len() < 8 {
+        return Err(invalid("TIFF file is truncated"));
+    }
+    let big_endian = match &bytes[0..2] {
+        b"II" => false,
+        b"MM" => true,
+        _ => return Err(invalid("not a TIFF file"))
+    };
+    let mut rdr = Reader::new(bytes, big_endian);
+    rdr.take(2)?;
+    if rdr.uint(2)? != 42 {
+        return Err(invalid("not a classic TIFF file"));
+    }
+    let fields = directory(bytes, big_endian, rdr.uint(4)? as usize)?;
+    let field = |tag:u16| fields.iter().find(|f| f.tag == tag).map(|f| &f.values);
+    let one = |tag:u16, default:f64| field(tag).and_then(|v| v.first().cloned()).unwrap_or(default);
+
+    let width = one(256, 0.0) as usize;
+    let height = one(257, 0.0) as usize;
+    let samples = one(277, 1.0) as usize;
+    let planar = one(284, 1.0) as usize;
+    let compression = one(259, 1.0) as usize;
+    let predictor = one(317, 1.0) as usize;
+    if compression != 1 && compression != 5 && compression != 8 && compression != 32946 {
+        return Err(invalid(&format!("TIFF compression {} is not supported", compression)));
+    }
+    if predictor < 1 || predictor > 3 {
+        return Err(invalid(&format!("TIFF predictor {} is not supported", predictor)));
+    }
+    if one(258, 0.0) != 32.0 || one(339, 1.0) != 3.0 {
+        return Err(invalid("TIFF grid samples must be 32 bit floats"));
+    }
+    if width < 2 || height < 2 {
+        return Err(invalid("TIFF grid is too small"));
+    }
+
+    // strips are tiles spanning the image width
+    let (tile_w, tile_h, offsets, counts) = match field(324) {
+        Some(offsets) => (one(322, 0.0) as usize, one(323, 0.0) as usize, offsets, field(325)),
+        None => match field(273) {
+            Some(offsets) => (width, (one(278, height as f64) as usize).min(height), offsets, field(279)),
+            None => return Err(invalid("TIFF grid has no image data"))
+        }
+    };
+    if tile_w == 0 || tile_h == 0 {
+        return Err(invalid("TIFF grid has empty tiles"));
+    }
+    let across = (width + tile_w - 1) / tile_w;
+    let down = (height + tile_h - 1) / tile_h;
+    let per_tile = if planar == 2 { 1 } else { samples };
+
+    let mut bands = vec![vec![0.0f32; width * height]; samples];
+    for (t, offset) in offsets.iter().enumerate() {
+        let plane = if planar == 2 { t / (across * down) } else { 0 };
+        let (tx, ty) = ((t % (across * down)) % across, (t % (across * down)) / across);
+        let offset = *offset as usize;
+        let size = match counts.and_then(|c| c.get(t)) {
+            Some(&count) => count as usize,
+            None if compression == 1 => 4 * tile_w * tile_h * per_tile,
+            None => return Err(invalid("compressed TIFF grid has no byte counts"))
+        };
+        let raw = bytes.get(offset..offset + size).ok_or_else(|| invalid("TIFF file is truncated"))?;
+        let mut data = match compression {
+            1 => raw.to_vec(),
+            5 => lzw(raw)?,
+            _ => inflate(raw)?
+        };
+        unpredict(&mut data, predictor, tile_w * per_tile, per_tile, big_endian)?;
+
+        let mut values = Reader::new(&data, big_endian);
+        for r in 0..tile_h {
+            // strips end at the last row and padding rows of tiles are never needed
+            let row = ty * tile_h + r;
+            if row >= height {
+                break;
+            }
+            for c in 0..tile_w {
+                let col = tx * tile_w + c;
+                for s in 0..per_tile {
+                    let v = values.f32()?;
+                    if col < width && plane + s < samples {
+                        // TIFF rows run from north to south
+                        bands[plane + s][(height - 1 - row) * width + col] = v;
+                    }
+                }
+            }
+        }
+    }
+
+    let scale = field(33550).ok_or_else(|| invalid("GeoTIFF grid has no pixel scale"))?;
+    let tie = field(33922).ok_or_else(|| invalid("GeoTIFF grid has no tie point"))?;
+    if scale.len() < 2 || tie.len() < 6 {
+        return Err(invalid("GeoTIFF grid has a malformed georeference"));
+    }
+    // GTRasterTypeGeoKey 1025 is 1 for pixel is area, where the tie point is a pixel corner
+    let keys = field(34735).cloned().unwrap_or_default();
+    let area = keys.chunks(4).skip(1).find(|k| k.len() == 4 && k[0] == 1025.0).map_or(true, |k| k[3] == 1.0);
+    let half = if area { 0.5 } else { 0.0 };
+    let (dlon, dlat) = (scale[0], scale[1]);
+    let west = tie[3] + (half - tie[0]) * dlon;
+    let north = tie[4] - (half - tie[1]) * dlat;
+
+    // GDAL_NODATA holds the value and GDAL_METADATA the band descriptions as text
+    let text = |tag:u16| field(tag).map(|v| v.iter().map(|c| *c as u8 as char).filter(|c| *c != '\0').collect::<String>());
+    let nodata = text(42113).and_then(|t| t.trim().parse::<f32>().ok());
+    let description = describe(&text(42112).unwrap_or_default(), samples);
+    Ok((Raster {west: west, south: north - (height - 1) as f64 * dlat, dlon: dlon, dlat: dlat,
+        rows: height, cols: width, bands: bands, nodata: nodata}, description))
+}
+
+/// Parse a GeoTIFF grid, without its band descriptions.
+///
+pub fn parse(bytes:&[u8]) -> io::Result<Raster> {
+    Ok(parse_grid(bytes)?.0)
+}
+
+/// Read a GeoTIFF grid and its band descriptions.
+///
+pub fn read_grid<P: AsRef<Path>>(path:P) -> io::Result<(Raster, Vec<Band>)> {
+    parse_grid(&contents(path)?)
+}
+
+/// Read a GeoTIFF grid.
+///
+pub fn read<P: AsRef<Path>>(path:P) -> io::Result<Raster> {
+    parse(&contents(path)?)
+}
+
+#[test]
+fn test_geotiff() {
+    // a little endian two band grid in one strip, pixel is point, tied at 50N 10E with 0.5 degree spacing
+    let (width, height) = (3u32, 2u32);
+    let mut bytes:Vec<u8> = Vec::new();
+    bytes.extend(b"II");
+    bytes.extend(&42u16.to_le_bytes());
+    bytes.extend(&8u32.to_le_bytes());
+
+    let entries:Vec<(u16, u16, u32, u32)> = vec![
+        (256, 3, 1, width), (257, 3, 1, height), (258, 3, 1, 32), (259, 3, 1, 1), (273, 4, 1, 0),
+        (277, 3, 1, 2), (278, 3, 1, height), (284, 3, 1, 1), (339, 3, 1, 3),
+        (33550, 12, 3, 0), (33922, 12, 6, 0), (34735, 3, 8, 0), (42113, 2, 4, 0)];
+    let data_offset:u32 = 8 + 2 + 12 * entries.len() as u32 + 4;
+    let scale_offset:u32 = data_offset + 4 * width * height * 2;
+    let tie_offset:u32 = scale_offset + 24;
+    let keys_offset:u32 = tie_offset + 48;
+    bytes.extend(&(entries.len() as u16).to_le_bytes());
+    for &(tag, kind, n, value) in entries.iter() {
+        let value = match tag {
+            273 => data_offset,
+            33550 => scale_offset,
+            33922 => tie_offset,
+            34735 => keys_offset,
+            42113 => u32::from_le_bytes(*b"-99\0"),
+            _ => value
+        };
+        bytes.extend(&tag.to_le_bytes());
+        bytes.extend(&kind.to_le_bytes());
+        bytes.extend(&n.to_le_bytes());
+        bytes.extend(&value.to_le_bytes());
+    }
+    bytes.extend(&0u32.to_le_bytes());
+    // the northern row first, each node holding its row and column in the two bands
+    for row in 0..height {
+        for col in 0..width {
+            let v = if row == 0 && col == 2 { -99.0f32 } else { (10 * (height - 1 - row) + col) as f32 };
+            bytes.extend(&v.to_bits().to_le_bytes());
+            bytes.extend(&(-v).to_bits().to_le_bytes());
+        }
+    }
+    for v in [0.5f64, 0.5, 0.0, 0.0, 0.0, 0.0, 10.0, 50.0, 0.0].iter() {
+        bytes.extend(&v.to_bits().to_le_bytes());
+    }
+    for v in [1u16, 1, 0, 1, 1025, 0, 1, 2].iter() {
+        bytes.extend(&v.to_le_bytes());
+    }
+
+    let raster = parse(&bytes).unwrap();
+    assert_eq!(raster.bands.len(), 2);
+    assert!((raster.west - 10.0).abs() < 1.0e-12 && (raster.south - 49.5).abs() < 1.0e-12);
+    assert!((raster.bilinear(0, 10.25, 49.75).unwrap() - 5.5).abs() < 1.0e-6);
+    assert!((raster.bilinear(1, 10.25, 49.75).unwrap() + 5.5).abs() < 1.0e-6);
+    assert!(raster.bilinear(0, 10.75, 49.75).is_none());
+}
+
+/// Build a little endian GeoTIFF in memory from fields of long values and strips of data, tied
+/// at 50N 10W with 0.5 degree spacing, pixel is area.
+///
+#[cfg(test)]
+pub fn synthetic_tiff(fields:&[(u16, Vec<u32>)], strips:&[&[u8]], metadata:&str) -> Vec<u8> {
+    let mut entries:Vec<(u16, u16, u32, Vec<u8>)> = fields.iter()
+        .map(|&(tag, ref v)| (tag, 4, v.len() as u32, v.iter().flat_map(|x| x.to_le_bytes().to_vec()).collect()))
+        .collect();
+    let doubles = |v:&[f64]| v.iter().flat_map(|x| x.to_bits().to_le_bytes().to_vec()).collect::<Vec<u8>>();
+    entries.push((33550, 12, 3, doubles(&[0.5, 0.5, 0.0])));
+    entries.push((33922, 12, 6, doubles(&[0.0, 0.0, 0.0, -10.0, 50.0, 0.0])));
+    if !metadata.is_empty() {
+        entries.push((42112, 2, metadata.len() as u32 + 1, metadata.bytes().chain(Some(0)).collect()));
+    }
+    // strips follow the directory and the values too long to hold in place follow the strips
+    let start:u32 = 8 + 2 + 12 * (entries.len() as u32 + 2) + 4;
+    let mut offsets = Vec::new();
+    let mut data:Vec<u8> = Vec::new();
+    for strip in strips.iter() {
+        offsets.extend(&(start + data.len() as u32).to_le_bytes());
+        data.extend(strip.iter());
+    }
+    let counts:Vec<u8> = strips.iter().flat_map(|s| (s.len() as u32).to_le_bytes().to_vec()).collect();
+    entries.push((273, 4, strips.len() as u32, offsets));
+    entries.push((279, 4, strips.len() as u32, counts));
+
+    let mut bytes:Vec<u8> = Vec::new();
+    bytes.extend(b"II");
+    bytes.extend(&42u16.to_le_bytes());
+    bytes.extend(&8u32.to_le_bytes());
+    bytes.extend(&(entries.len() as u16).to_le_bytes());
+    for &(tag, kind, n, ref values) in entries.iter() {
+        bytes.extend(&tag.to_le_bytes());
+        bytes.extend(&kind.to_le_bytes());
+        bytes.extend(&n.to_le_bytes());
+        if values.len() <= 4 {
+            let mut inline = values.clone();
+            inline.resize(4, 0);
+            bytes.extend(inline);
+        } else {
+            bytes.extend(&(start + data.len() as u32).to_le_bytes());
+            data.extend(values.iter());
+        }
+    }
+    bytes.extend(&0u32.to_le_bytes());
+    bytes.extend(data);
+    bytes
+}
+
+#[test]
+fn test_inflate() {
+    // a dynamic Huffman block from zlib
+    let dynamic = [120u8, 218, 5, 193, 129, 13, 0, 48, 8, 2, 176, 219, 32, 176, 137, 252, 255, 143, 45, 230, 175,
+        64, 119, 43, 148, 99, 71, 212, 72, 175, 9, 14, 126, 238, 8, 50];
+    assert_eq!(inflate(&dynamic).unwrap(), b"AHGJDABEKJKDAKBHEEIDBDHDDFKIIA".to_vec());
+    assert!(inflate(&dynamic[..20]).is_err());
+    assert!(inflate(&[0u8, 0, 0]).is_err());
+}
+
+#[test]
+fn test_compressed() {
+    // a 4 by 3 grid of longitude offsets, positive west, and latitude offsets in arc-seconds
+    let lon = |row:usize, col:usize| -((10 * row + col) as f32) - 0.25;
+    let lat = |row:usize, col:usize| (100 + 10 * row + col) as f32;
+    let check = |raster:&Raster, lon_band:usize, lat_band:usize| {
+        assert_eq!((raster.rows, raster.cols), (3, 4));
+        assert!((raster.west + 9.75).abs() < 1.0e-12 && (raster.south - 48.75).abs() < 1.0e-12);
+        for row in 0..3 {
+            for col in 0..4 {
+                assert_eq!(raster.bands[lon_band][row * 4 + col], lon(row, col));
+                assert_eq!(raster.bands[lat_band][row * 4 + col], lat(row, col));
+            }
+        }
+    };
+    let metadata = "<GDALMetadata>\n  <Item name=\"DESCRIPTION\" sample=\"0\" role=\"description\">longitude_offset</Item>\n  \
+        <Item name=\"positive_value\" sample=\"0\">west</Item>\n  <Item name=\"UNITTYPE\" sample=\"0\" role=\"unittype\">arc-second</Item>\n  \
+        <Item name=\"DESCRIPTION\" sample=\"1\" role=\"description\">latitude_offset</Item>\n  \
+        <Item name=\"UNITTYPE\" sample=\"1\" role=\"unittype\">arc-second</Item>\n</GDALMetadata>";
+
+    // interleaved DEFLATE strips of two rows and one with the floating point predictor, as made by
+    // GDAL for PROJ-data, the first in a fixed Huffman block and the second in a stored block
+    let strip0 = [120u8, 218, 59, 232, 196, 0, 6, 15, 215, 113, 48, 129, 160, 27, 23, 3, 10, 56, 8, 149, 79, 158, 37,
+        192, 4, 130, 107, 228, 80, 229, 1, 244, 80, 6, 1];
+    let strip1 = [120u8, 1, 1, 32, 0, 223, 255, 190, 66, 1, 0, 1, 0, 0, 0, 192, 134, 32, 2, 112, 2, 64, 2, 176, 50,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 92, 130, 4, 1];
+    let fields = vec![(256, vec![4]), (257, vec![3]), (258, vec![32, 32]), (259, vec![8]), (277, vec![2]),
+        (278, vec![2]), (284, vec![1]), (317, vec![3]), (339, vec![3, 3])];
+    let (raster, bands) = parse_grid(&synthetic_tiff(&fields, &[&strip0, &strip1], metadata)).unwrap();
+    check(&raster, 0, 1);
+    assert_eq!(bands.len(), 2);
+    assert_eq!(bands[0].description, "longitude_offset");
+    assert!(bands[0].positive_west && !bands[1].positive_west);
+    assert_eq!(bands[1].description, "latitude_offset");
+    assert_eq!(bands[1].unit, "arc-second");
+
+    // the same grid in LZW planes of latitude then longitude, with the horizontal predictor
+    let lat_plane = [128u8, 0, 0, 15, 2, 20, 8, 5, 2, 0, 65, 224, 208, 134, 228, 22, 19, 8, 133, 67, 224, 76, 136,
+        116, 70, 44, 0, 128, 128];
+    let lon_plane = [128u8, 0, 0, 10, 38, 12, 8, 17, 2, 0, 65, 224, 208, 129, 36, 20, 0, 16, 132, 68, 32, 81, 32, 2,
+        1, 125, 2, 16, 0, 96, 71, 8, 65, 0, 1, 1];
+    let fields = vec![(256, vec![4]), (257, vec![3]), (258, vec![32, 32]), (259, vec![5]), (277, vec![2]),
+        (278, vec![3]), (284, vec![2]), (317, vec![2]), (339, vec![3, 3])];
+    let (raster, bands) = parse_grid(&synthetic_tiff(&fields, &[&lat_plane, &lon_plane], "")).unwrap();
+    check(&raster, 1, 0);
+    assert!(bands.iter().all(|b| b.description.is_empty()));
+
+    let fields = vec![(256, vec![4]), (257, vec![3]), (258, vec![32]), (259, vec![7]), (339, vec![3])];
+    assert!(parse(&synthetic_tiff(&fields, &[&strip0], "")).is_err());
+}
@@ -0,0 +1,211 @@
+use convert;
+use ellipsoid;
+
+/// Standard gravity used to scale geopotential to geopotential height (m/s^2).
+///
+pub const G0:f64 = 9.80665;
+
+/// Calculate q = ((1 + 3/x²) atan x - 3/x)/2 and q' = 3 (1 + 1/x²)(1 - atan(x)/x) - 1 for x = E/u.
+///
+/// The closed forms lose most of their digits to cancellation for small x, so they are summed as
+/// series in x², which converge quickly for the Earth's eccentricity.
+///
+fn q_functions(x:f64) -> (f64, f64) {
+    let (mut q, mut qp) = (0.0, 0.0);
+    let mut power:f64 = x * x;
+    for k in 1..20 {
+        let kf = k as f64;
+        let sign:f64 = if k % 2 == 1 { 1.0 } else { -1.0 };
+        let d:f64 = (2.0 * kf + 1.0) * (2.0 * kf + 3.0);
+        q += sign * 2.0 * kf * power * x / d;
+        qp += sign * 6.0 * power / d;
+        power *= x * x;
+    }
+    (q, qp)
+}
+
+/// Calculate m = ω²a²b/GM and the constants q0 and q0' of the level ellipsoid.
+///
+/// The normal field functions need an ellipsoid constructed with_gravity; for one without, m
+/// and everything derived from it are not finite.
+///
+fn constants(ellps:ellipsoid::Ellipsoid) -> (f64, f64, f64) {
+    let m:f64 = ellps.omega * ellps.omega * ellps.asq * ellps.b / ellps.gm;
+    let (q0, q0p) = q_functions(ellps.e2);
+
+    (m, q0, q0p)
+}
+
+/// Calculate normal gravity at the equator and at the poles, in m/s², of a level ellipsoid.
+///
+/// References:
+/// -----------
+/// Hofmann-Wellenhof, B. and Moritz, H. (2006). Physical Geodesy, 2nd edition,
+/// eqs. 2-141 and 2-142.
+///
+pub fn equatorial_polar(ellps:ellipsoid::Ellipsoid) -> (f64, f64) {
+    let (m, q0, q0p) = constants(ellps);
+    let ep:f64 = ellps.e2;
+    let gamma_e:f64 = ellps.gm / (ellps.a * ellps.b) * (1.0 - m - m * ep * q0p / (6.0 * q0));
+    let gamma_p:f64 = ellps.gm / ellps.asq * (1.0 + m * ep * q0p / (3.0 * q0));
+
+    (gamma_e, gamma_p)
+}
+
+/// Calculate normal gravity, in m/s², on the ellipsoid at geodetic latitude phi by Somigliana's formula.
+///
+/// References:
+/// -----------
+/// NIMA (2000). Department of Defense World Geodetic System 1984. Technical Report
+/// 8350.2, 3rd edition, eq. 4-1.
+///
+pub fn normal_gravity(ellps:ellipsoid::Ellipsoid, phi:f64) -> f64 {
+    let (gamma_e, gamma_p) = equatorial_polar(ellps);
+    let (s2, c2) = (phi.sin().powi(2), phi.cos().powi(2));
+
+    (ellps.a * gamma_e * c2 + ellps.b * gamma_p * s2) / (ellps.asq * c2 + ellps.bsq * s2).sqrt()
+}
+
+/// Calculate normal gravity, in m/s², at geodetic latitude phi and height h above the ellipsoid.
+///
+/// Uses the second order expansion in height, good to about a micro-Gal through the atmosphere
+/// where aircraft fly.
+///
+/// References:
+/// -----------
+/// NIMA (2000). Department of Defense World Geodetic System 1984. Technical Report
+/// 8350.2, 3rd edition, eq. 4-3.
+///
+pub fn normal_gravity_at(ellps:ellipsoid::Ellipsoid, phi:f64, h:f64) -> f64 {
+    let (m, _q0, _q0p) = constants(ellps);
+    let f:f64 = ellps.f;
+
+    normal_gravity(ellps, phi) * (1.0 - 2.0 / ellps.a * (1.0 + f + m - 2.0 * f * phi.sin().powi(2)) * h
+        + 3.0 * h * h / ellps.asq)
+}
+
+/// Calculate the normal potential, in m²/s², on the surface of the level ellipsoid.
+///
+pub fn surface_potential(ellps:ellipsoid::Ellipsoid) -> f64 {
+    let e:f64 = (ellps.asq - ellps.bsq).sqrt();
+    ellps.gm / e * ellps.e2.atan() + ellps.omega * ellps.omega * ellps.asq / 3.0
+}
+
+/// Calculate the normal gravity potential, gravitational plus centrifugal, in m²/s², at geodetic
+/// latitude phi and height h above the ellipsoid.
+///
+/// The potential is evaluated in closed form in ellipsoidal harmonic coordinates and is exact at
+/// any height.
+///
+/// References:
+/// -----------
+/// Hofmann-Wellenhof, B. and Moritz, H. (2006). Physical Geodesy, 2nd edition,
+/// eqs. 1-153, 2-126.
+///
+pub fn normal_potential(ellps:ellipsoid::Ellipsoid, phi:f64, h:f64) -> f64 {
+    let (_m, q0, _q0p) = constants(ellps);
+    let (x, y, z) = convert::lla2ecef(0.0, phi, h, ellps);
+    let p:f64 = (x * x + y * y).sqrt();
+    let esq:f64 = ellps.asq - ellps.bsq;
+    let e:f64 = esq.sqrt();
+
+    // ellipsoidal harmonic coordinates: semi-minor axis u of the confocal ellipsoid and reduced latitude beta
+    let d:f64 = p * p + z * z - esq;
+    let usq:f64 = 0.5 * d * (1.0 + (1.0 + 4.0 * esq * z * z / (d * d)).sqrt());
+    let u:f64 = usq.sqrt();
+    let beta:f64 = (z * (usq + esq).sqrt()).atan2(u * p);
+    let (q, _qp) = q_functions(e / u);
+    let w2:f64 = ellps.omega * ellps.omega;
+
+    ellps.gm / e * (e / u).atan() + 0.5 * w2 * ellps.asq * q / q0 * (beta.sin().powi(2) - 1.0 / 3.0)
+        + 0.5 * w2 * (usq + esq) * beta.cos().powi(2)
+}
+
+/// Convert geometric height above the ellipsoid to geopotential height, in geopotential meters.
+///
+/// Geopotential height is the normal potential difference from the ellipsoid divided by standard
+/// gravity, as used for pressure levels in meteorology and aviation.
+///
+pub fn geopotential_height(ellps:ellipsoid::Ellipsoid, phi:f64, h:f64) -> f64 {
+    (surface_potential(ellps) - normal_potential(ellps, phi, h)) / G0
+}
+
+/// Convert geopotential height to geometric height above the ellipsoid, in meters.
+///
+/// Solved by Newton's method, with the derivative of geopotential height being γ/G0.
+///
+pub fn geometric_height(ellps:ellipsoid::Ellipsoid, phi:f64, z:f64) -> f64 {
+    let mut h:f64 = z;
+    for _ in 0..10 {
+        let dh = (geopotential_height(ellps, phi, h) - z) * G0 / normal_gravity_at(ellps, phi, h);
+        h -= dh;
+        if dh.abs() < 1.0e-9 {
+            break;
+        }
+    }
+    h
+}
+
+/// Calculate the fully normalized zonal coefficient of degree n of the normal potential.
+///
+/// Odd degrees vanish by symmetry.
+///
+/// References:
+/// -----------
+/// Hofmann-Wellenhof, B. and Moritz, H. (2006). Physical Geodesy, 2nd edition,
+/// eqs. 2-92 and 2-165.
+///
+pub fn zonal(ellps:ellipsoid::Ellipsoid, n:usize) -> f64 {
+    if n == 0 || n % 2 == 1 {
+        return 0.0;
+    }
+    let (m, q0, _q0p) = constants(ellps);
+    let esq:f64 = ellps.eccsq;
+    let j2:f64 = esq / 3.0 * (1.0 - 2.0 / 15.0 * m * ellps.e2 / q0);
+    let k = (n / 2) as f64;
+    let sign:f64 = if (n / 2) % 2 == 1 { 1.0 } else { -1.0 };
+    let j:f64 = sign * 3.0 * esq.powi(n as i32 / 2) / ((2.0 * k + 1.0) * (2.0 * k + 3.0)) * (1.0 - k + 5.0 * k * j2 / esq);
+
+    -j / ((2 * n + 1) as f64).sqrt()
+}
+
+#[test]
+fn test_normal_gravity() {
+    // WGS84 defining and derived constants, NIMA TR8350.2 tables 3.4 and 3.5
+    let wgs84 = ellipsoid::named("WGS84").unwrap();
+    assert!((-zonal(wgs84, 2) * 5.0f64.sqrt() - 1.082629821e-3).abs() < 1.0e-11);
+    let (gamma_e, gamma_p) = equatorial_polar(wgs84);
+    assert!((gamma_e - 9.7803253359).abs() < 1.0e-9);
+    assert!((gamma_p - 9.8321849378).abs() < 1.0e-9);
+    assert!((normal_gravity(wgs84, 45.0f64.to_radians()) - 9.8061977694).abs() < 1.0e-9);
+    assert!((surface_potential(wgs84) - 62636851.7146).abs() < 1.0e-3);
+
+    // GRS80, Moritz (2000)
+    let grs80 = ellipsoid::named("GRS80").unwrap();
+    let (gamma_e, gamma_p) = equatorial_polar(grs80);
+    assert!((gamma_e - 9.7803267715).abs() < 1.0e-9);
+    assert!((gamma_p - 9.8321863685).abs() < 1.0e-9);
+    assert!((surface_potential(grs80) - 62636860.850).abs() < 1.0e-3);
+
+    // the potential is constant on the ellipsoid and its vertical gradient is normal gravity
+    for &lat in [0.0f64, 30.0, 60.0, 90.0].iter() {
+        let phi = lat.to_radians();
+        assert!((normal_potential(wgs84, phi, 0.0) - surface_potential(wgs84)).abs() < 1.0e-6);
+        for &h in [0.0f64, 10000.0].iter() {
+            let gradient = (normal_potential(wgs84, phi, h - 0.5) - normal_potential(wgs84, phi, h + 0.5)) / 1.0;
+            assert!((gradient - normal_gravity_at(wgs84, phi, h)).abs() < 1.0e-6);
+        }
+    }
+}
+
+#[test]
+fn test_geopotential_height() {
+    let wgs84 = ellipsoid::named("WGS84").unwrap();
+    let phi:f64 = 45.0f64.to_radians();
+    // near 45 degrees gravity is close to standard, so the two heights differ only slowly
+    let z = geopotential_height(wgs84, phi, 11000.0);
+    println!("geopotential height {}", z);
+    assert!(z > 10970.0 && z < 11000.0);
+    assert!((geometric_height(wgs84, phi, z) - 11000.0).abs() < 1.0e-6);
+    assert!(geopotential_height(wgs84, phi, 0.0).abs() < 1.0e-6);
+}
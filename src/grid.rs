@@ -0,0 +1,571 @@
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+
+use geotiff;
+use ntv2;
+
+/// Geodetic shift interpolated from a grid, applied forward from the source datum to the target.
+///
+/// Longitude lambda and latitude phi are in radians, height h in meters.
+///
+pub trait GridShift {
+
+    /// Calculate the shift (dlambda, dphi, dh) at a point of the source datum, or None outside the grid.
+    ///
+    fn shift(&self, lambda:f64, phi:f64) -> Option<(f64, f64, f64)>;
+
+    /// Shift geodetic coordinates from the source datum to the target datum.
+    ///
+    fn forward(&self, lambda:f64, phi:f64, h:f64) -> Option<(f64, f64, f64)> {
+        let (dlambda, dphi, dh) = self.shift(lambda, phi)?;
+        Some((lambda + dlambda, phi + dphi, h + dh))
+    }
+
+    /// Shift geodetic coordinates from the target datum back to the source datum.
+    ///
+    /// The shift is given at source positions, so the inverse is found by fixed point iteration
+    /// until it agrees with the target to about a micrometer.
+    ///
+    fn inverse(&self, lambda:f64, phi:f64, h:f64) -> Option<(f64, f64, f64)> {
+        let (mut l, mut p) = (lambda, phi);
+        let mut dh:f64 = 0.0;
+        for _ in 0..10 {
+            let (dlambda, dphi, dh2) = self.shift(l, p)?;
+            let (l2, p2) = (lambda - dlambda, phi - dphi);
+            let done = (l2 - l).abs() < 1.0e-13 && (p2 - p).abs() < 1.0e-13;
+            l = l2;
+            p = p2;
+            dh = dh2;
+            if done {
+                break;
+            }
+        }
+        Some((l, p, h - dh))
+    }
+}
+
+/// Regular latitude and longitude grid of one or more bands of values.
+///
+/// Nodes run west to east along each row and rows run south to north. Longitudes wrap, so a
+/// global grid may start at either 0 or -180 degrees.
+///
+#[derive(Clone, Debug)]
+pub struct Raster {
+    /// longitude of the western column (degrees)
+    pub west : f64,
+    /// latitude of the southern row (degrees)
+    pub south : f64,
+    /// longitude spacing (degrees)
+    pub dlon : f64,
+    /// latitude spacing (degrees)
+    pub dlat : f64,
+    /// number of rows
+    pub rows : usize,
+    /// number of columns
+    pub cols : usize,
+    /// node values of each band
+    pub bands : Vec<Vec<f32>>,
+    /// value marking nodes without data
+    pub nodata : Option<f32>
+}
+
+impl Raster {
+
+    /// Test whether the columns span the whole globe without repeating the first column at the end.
+    ///
+    pub fn wraps(&self) -> bool {
+        (self.cols as f64 * self.dlon - 360.0).abs() < 1.0e-9
+    }
+
+    /// Locate a point, in degrees, as fractional column and row indices, or None outside the grid.
+    ///
+    pub fn index(&self, lon:f64, lat:f64) -> Option<(f64, f64)> {
+        let x:f64 = (lon - self.west).rem_euclid(360.0) / self.dlon;
+        let y:f64 = (lat - self.south) / self.dlat;
+        let w = if self.wraps() { self.cols as f64 } else { (self.cols - 1) as f64 };
+        let h = (self.rows - 1) as f64;
+        // a point just west of the first column lies in the last cell
+        let x = if (x - 360.0 / self.dlon).abs() < 1.0e-9 { 0.0 } else { x };
+        if x < -1.0e-9 || x > w + 1.0e-9 || y < -1.0e-9 || y > h + 1.0e-9 {
+            return None;
+        }
+        Some((x.max(0.0).min(w), y.max(0.0).min(h)))
+    }
+
+    /// Get the value of a band at a node, or None where there is no data.
+    ///
+    /// Column indices wrap around a global grid and are clamped to the edges of a regional one, as
+    /// are row indices.
+    ///
+    pub fn node(&self, band:usize, i:isize, j:isize) -> Option<f64> {
+        let (cols, rows) = (self.cols as isize, self.rows as isize);
+        let i = if self.wraps() { i.rem_euclid(cols) } else { i.max(0).min(cols - 1) };
+        let j = j.max(0).min(rows - 1);
+        let v = self.bands[band][(j * cols + i) as usize];
+        match self.nodata {
+            Some(nodata) if v == nodata || v.is_nan() => None,
+            _ => Some(v as f64)
+        }
+    }
+
+    /// Find the cell containing a point, in degrees, as the index of its south west node and the
+    /// fractional position within it.
+    ///
+    fn cell(&self, lon:f64, lat:f64) -> Option<(isize, isize, f64, f64)> {
+        let (x, y) = self.index(lon, lat)?;
+        let last = if self.wraps() { self.cols - 1 } else { self.cols - 2 };
+        let i:usize = (x.floor() as usize).min(last);
+        let j:usize = (y.floor() as usize).min(self.rows - 2);
+
+        Some((i as isize, j as isize, x - i as f64, y - j as f64))
+    }
+
+    /// Interpolate a band bilinearly between the four nodes surrounding a point, in degrees.
+    ///
+    pub fn bilinear(&self, band:usize, lon:f64, lat:f64) -> Option<f64> {
+        let (i, j, dx, dy) = self.cell(lon, lat)?;
+
+        Some(self.node(band, i, j)? * (1.0 - dx) * (1.0 - dy) + self.node(band, i + 1, j)? * dx * (1.0 - dy)
+            + self.node(band, i, j + 1)? * (1.0 - dx) * dy + self.node(band, i + 1, j + 1)? * dx * dy)
+    }
+
+    /// Interpolate a band by bicubic convolution over the sixteen nodes surrounding a point, in degrees.
+    ///
+    /// The interpolant passes through the nodes with a continuous gradient, which avoids the
+    /// creases bilinear interpolation leaves along grid lines.
+    ///
+    /// References:
+    /// -----------
+    /// Keys, R. (1981). Cubic convolution interpolation for digital image processing.
+    /// IEEE Trans Acoust Speech Signal Process 29(6):1153-1160.
+    ///
+    pub fn bicubic(&self, band:usize, lon:f64, lat:f64) -> Option<f64> {
+        let (i, j, dx, dy) = self.cell(lon, lat)?;
+        let weights = |t:f64| [
+            ((-0.5 * t + 1.0) * t - 0.5) * t,
+            (1.5 * t - 2.5) * t * t + 1.0,
+            ((-1.5 * t + 2.0) * t + 0.5) * t,
+            (0.5 * t - 0.5) * t * t
+        ];
+        let (wx, wy) = (weights(dx), weights(dy));
+
+        let mut sum:f64 = 0.0;
+        for n in 0..4 {
+            for m in 0..4 {
+                sum += wx[m] * wy[n] * self.node(band, i + m as isize - 1, j + n as isize - 1)?;
+            }
+        }
+        Some(sum)
+    }
+}
+
+/// Grid of latitude, longitude and height shifts, any of which may be absent.
+///
+#[derive(Clone, Debug)]
+pub struct ShiftGrid {
+    /// interpolated values
+    pub raster : Raster,
+    /// band of latitude shifts (arc-seconds)
+    pub lat_band : Option<usize>,
+    /// band of longitude shifts, positive east (arc-seconds)
+    pub lon_band : Option<usize>,
+    /// band of height shifts (m)
+    pub h_band : Option<usize>
+}
+
+impl GridShift for ShiftGrid {
+
+    fn shift(&self, lambda:f64, phi:f64) -> Option<(f64, f64, f64)> {
+        let arcsec:f64 = (1.0f64 / 3600.0).to_radians();
+        let (lon, lat) = (lambda.to_degrees(), phi.to_degrees());
+        let band = |band:Option<usize>| match band {
+            Some(band) => self.raster.bilinear(band, lon, lat),
+            None => Some(0.0)
+        };
+
+        Some((band(self.lon_band)? * arcsec, band(self.lat_band)? * arcsec, band(self.h_band)?))
+    }
+}
+
+/// Sequence of grid shifts applied one after another, e.g. NADCON5 grids through several datums.
+///
+pub struct Chain {
+    /// grid shifts in the order of the forward transformation
+    pub steps : Vec<Box<dyn GridShift>>
+}
+
+impl Chain {
+
+    /// Apply each shift in turn from the first source datum to the last target datum.
+    ///
+    pub fn forward(&self, lambda:f64, phi:f64, h:f64) -> Option<(f64, f64, f64)> {
+        self.steps.iter().fold(Some((lambda, phi, h)), |p, step| p.and_then(|(l, p, h)| step.forward(l, p, h)))
+    }
+
+    /// Undo each shift in reverse order, from the last target datum back to the first source datum.
+    ///
+    pub fn inverse(&self, lambda:f64, phi:f64, h:f64) -> Option<(f64, f64, f64)> {
+        self.steps.iter().rev().fold(Some((lambda, phi, h)), |p, step| p.and_then(|(l, p, h)| step.inverse(l, p, h)))
+    }
+}
+
+impl GridShift for ntv2::Grid {
+
+    fn shift(&self, lambda:f64, phi:f64) -> Option<(f64, f64, f64)> {
+        let arcsec:f64 = (1.0f64 / 3600.0).to_radians();
+        let (lat, long) = (phi / arcsec, -lambda / arcsec);
+        let grid = self.subgrid(lat, long)?;
+        let (dlat, dlong) = grid.interpolate(lat, long);
+
+        Some((-dlong * arcsec, dlat * arcsec, 0.0))
+    }
+}
+
+/// Cursor over binary grid file contents in either byte order.
+///
+pub struct Reader<'a> {
+    /// file contents
+    pub bytes : &'a [u8],
+    /// position of the next value
+    pub offset : usize,
+    /// whether values are stored most significant byte first
+    pub big_endian : bool
+}
+
+impl<'a> Reader<'a> {
+
+    /// Construct a reader positioned at the start of the bytes.
+    ///
+    pub fn new(bytes:&'a [u8], big_endian:bool) -> Reader<'a> {
+        Reader {bytes: bytes, offset: 0, big_endian: big_endian}
+    }
+
+    /// Take the next n bytes.
+    ///
+    pub fn take(&mut self, n:usize) -> io::Result<&'a [u8]> {
+        if self.offset + n > self.bytes.len() {
+            return Err(invalid("grid file is truncated"));
+        }
+        let value = &self.bytes[self.offset..self.offset + n];
+        self.offset += n;
+        Ok(value)
+    }
+
+    /// Read an unsigned integer of n bytes.
+    ///
+    pub fn uint(&mut self, n:usize) -> io::Result<u64> {
+        let b = self.take(n)?;
+        let fold = |v:u64, b:&u8| (v << 8) | *b as u64;
+        Ok(if self.big_endian { b.iter().fold(0, fold) } else { b.iter().rev().fold(0, fold) })
+    }
+
+    /// Read a 4 byte signed integer.
+    ///
+    pub fn i32(&mut self) -> io::Result<i32> {
+        Ok(self.uint(4)? as u32 as i32)
+    }
+
+    /// Read a 4 byte float.
+    ///
+    pub fn f32(&mut self) -> io::Result<f32> {
+        Ok(f32::from_bits(self.uint(4)? as u32))
+    }
+
+    /// Read an 8 byte float.
+    ///
+    pub fn f64(&mut self) -> io::Result<f64> {
+        Ok(f64::from_bits(self.uint(8)?))
+    }
+}
+
+/// Construct the error returned for malformed grid files.
+///
+pub fn invalid(message:&str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Read the whole contents of a file.
+///
+pub fn contents<P: AsRef<Path>>(path:P) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Parse a grid in the NGS binary format used by NADCON5 .b and GEOID .bin files.
+///
+/// The 44 byte header holds the southern latitude, western longitude and spacings in degrees as
+/// 8 byte floats, then the numbers of rows and columns and a kind flag of 1 as 4 byte integers.
+/// Rows of 4 byte floats follow from south to north. Files may be in either byte order and may
+/// carry Fortran sequential record markers around the header and each row.
+///
+/// References:
+/// -----------
+/// Smith, D. A. (2017). NADCON5 technical documentation. NOAA Technical
+/// Memorandum NOS NGS 63.
+///
+pub fn parse_ngs(bytes:&[u8]) -> io::Result<Raster> {
+    if bytes.len() < 44 {
+        return Err(invalid("NGS grid is truncated"));
+    }
+    let marked = (bytes[0] == 44 && bytes[1..4] == [0, 0, 0]) || (bytes[0..3] == [0, 0, 0] && bytes[3] == 44);
+    let (start, big_endian) = if marked { (4, bytes[0] == 0) } else { (0, bytes[43] == 1) };
+
+    let mut rdr = Reader::new(bytes, big_endian);
+    rdr.take(start)?;
+    let south = rdr.f64()?;
+    let west = rdr.f64()?;
+    let dlat = rdr.f64()?;
+    let dlon = rdr.f64()?;
+    let rows = rdr.i32()?;
+    let cols = rdr.i32()?;
+    let kind = rdr.i32()?;
+    if kind != 1 || rows < 2 || cols < 2 {
+        return Err(invalid("unsupported NGS grid header"));
+    }
+    let (rows, cols) = (rows as usize, cols as usize);
+
+    let mut values = Vec::with_capacity(rows * cols);
+    for _ in 0..rows {
+        rdr.take(2 * start)?;
+        for _ in 0..cols {
+            values.push(rdr.f32()?);
+        }
+    }
+    Ok(Raster {west: west, south: south, dlon: dlon, dlat: dlat, rows: rows, cols: cols, bands: vec![values], nodata: None})
+}
+
+/// Read a grid in the NGS binary format.
+///
+pub fn read_ngs<P: AsRef<Path>>(path:P) -> io::Result<Raster> {
+    parse_ngs(&contents(path)?)
+}
+
+/// Read a set of NADCON5 grids given the path of the latitude shift file.
+///
+/// The longitude and ellipsoid height shift files are found by replacing .lat. in the file name
+/// with .lon. and .eht.; the height file is optional.
+///
+pub fn read_nadcon5(lat_path:&str) -> io::Result<ShiftGrid> {
+    if !lat_path.contains(".lat.") {
+        return Err(invalid("NADCON5 latitude grid name must contain .lat."));
+    }
+    let mut raster = read_ngs(lat_path)?;
+    let lon = read_ngs(lat_path.replace(".lat.", ".lon."))?;
+    if lon.rows != raster.rows || lon.cols != raster.cols {
+        return Err(invalid("NADCON5 latitude and longitude grids differ in size"));
+    }
+    raster.bands.push(lon.bands[0].clone());
+
+    let eht_path = lat_path.replace(".lat.", ".eht.");
+    let h_band = if Path::new(&eht_path).exists() {
+        raster.bands.push(read_ngs(eht_path)?.bands[0].clone());
+        Some(2)
+    } else {
+        None
+    };
+    Ok(ShiftGrid {raster: raster, lat_band: Some(0), lon_band: Some(1), h_band: h_band})
+}
+
+/// Parse a vertical shift grid in the GTX format.
+///
+/// A big endian header holds the southern latitude, western longitude and spacings in degrees
+/// as 8 byte floats and the numbers of rows and columns as 4 byte integers, followed by rows of
+/// 4 byte floats from south to north. Nodes without data hold -88.8888.
+///
+pub fn parse_gtx(bytes:&[u8]) -> io::Result<Raster> {
+    let mut rdr = Reader::new(bytes, true);
+    let south = rdr.f64()?;
+    let west = rdr.f64()?;
+    let dlat = rdr.f64()?;
+    let dlon = rdr.f64()?;
+    let rows = rdr.i32()?;
+    let cols = rdr.i32()?;
+    if rows < 2 || cols < 2 {
+        return Err(invalid("unsupported GTX grid header"));
+    }
+    let (rows, cols) = (rows as usize, cols as usize);
+
+    let mut values = Vec::with_capacity(rows * cols);
+    for _ in 0..rows * cols {
+        values.push(rdr.f32()?);
+    }
+    Ok(Raster {west: west, south: south, dlon: dlon, dlat: dlat, rows: rows, cols: cols, bands: vec![values], nodata: Some(-88.8888)})
+}
+
+/// Read a vertical shift grid in the GTX format.
+///
+pub fn read_gtx<P: AsRef<Path>>(path:P) -> io::Result<Raster> {
+    parse_gtx(&contents(path)?)
+}
+
+/// Shift grid of the bands of a GeoTIFF grid chosen by their descriptions.
+///
+/// Latitude and longitude offsets are converted to arc-seconds positive east, from degrees or
+/// positive west where the metadata says so. Without descriptions the first two bands are taken
+/// as latitude and longitude offsets and a single band as vertical offsets.
+///
+pub fn tiff_shift(mut raster:Raster, bands:&[geotiff::Band]) -> ShiftGrid {
+    let find = |names:&[&str]| bands.iter().position(|b| names.contains(&b.description.as_str()));
+    let mut lat_band = find(&["latitude_offset"]);
+    let mut lon_band = find(&["longitude_offset"]);
+    let mut h_band = find(&["geoid_undulation", "vertical_offset", "ellipsoidal_height_offset"]);
+    if lat_band.is_none() && lon_band.is_none() && h_band.is_none() {
+        if raster.bands.len() >= 2 {
+            lat_band = Some(0);
+            lon_band = Some(1);
+        } else {
+            h_band = Some(0);
+        }
+    }
+    for (band, west) in [(lat_band, false), (lon_band, true)].iter() {
+        if let Some(b) = *band {
+            let mut scale:f32 = if bands.get(b).map_or(false, |d| d.unit.starts_with("degree")) { 3600.0 } else { 1.0 };
+            if *west && bands.get(b).map_or(false, |d| d.positive_west) {
+                scale = -scale;
+            }
+            let nodata = raster.nodata;
+            for v in raster.bands[b].iter_mut().filter(|v| Some(**v) != nodata) {
+                *v *= scale;
+            }
+        }
+    }
+    ShiftGrid {raster: raster, lat_band: lat_band, lon_band: lon_band, h_band: h_band}
+}
+
+/// Load a grid shift by file extension: .gsb NTv2, .b NADCON5, .gtx vertical and .tif GeoTIFF.
+///
+/// GeoTIFF grid bands are chosen by their GDAL metadata descriptions, as for PROJ-data grids.
+///
+pub fn load(path:&str) -> io::Result<Box<dyn GridShift>> {
+    let extension = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    match extension.as_str() {
+        "gsb" => Ok(Box::new(ntv2::read(path)?)),
+        "b" => Ok(Box::new(read_nadcon5(path)?)),
+        "gtx" => Ok(Box::new(ShiftGrid {raster: read_gtx(path)?, lat_band: None, lon_band: None, h_band: Some(0)})),
+        "tif" | "tiff" => {
+            let (raster, bands) = geotiff::read_grid(path)?;
+            Ok(Box::new(tiff_shift(raster, &bands)))
+        },
+        _ => Err(invalid(&format!("unknown grid format {}", path)))
+    }
+}
+
+/// Build a big endian GTX file in memory with values given by a function of the node position.
+///
+#[cfg(test)]
+pub fn synthetic_gtx(south:f64, west:f64, dlat:f64, dlon:f64, rows:usize, cols:usize, f:&dyn Fn(f64, f64) -> f32) -> Vec<u8> {
+    let mut bytes:Vec<u8> = Vec::new();
+    for v in [south, west, dlat, dlon].iter() {
+        bytes.extend(&v.to_bits().to_be_bytes());
+    }
+    bytes.extend(&(rows as i32).to_be_bytes());
+    bytes.extend(&(cols as i32).to_be_bytes());
+    for j in 0..rows {
+        for i in 0..cols {
+            bytes.extend(&f(south + j as f64 * dlat, west + i as f64 * dlon).to_bits().to_be_bytes());
+        }
+    }
+    bytes
+}
+
+#[test]
+fn test_gtx() {
+    let bytes = synthetic_gtx(40.0, -80.0, 0.25, 0.5, 9, 9, &|lat, lon| if lat > 41.5 { -88.8888 } else { (lat - lon) as f32 });
+    let grid = ShiftGrid {raster: parse_gtx(&bytes).unwrap(), lat_band: None, lon_band: None, h_band: Some(0)};
+
+    let (lambda, phi) = ((-78.3f64).to_radians(), 40.6f64.to_radians());
+    let (lambda2, phi2, h2) = grid.forward(lambda, phi, 10.0).unwrap();
+    assert!(lambda2 == lambda && phi2 == phi);
+    assert!((h2 - 10.0 - (40.6 + 78.3)).abs() < 1.0e-4);
+    let (_l, _p, h3) = grid.inverse(lambda2, phi2, h2).unwrap();
+    assert!((h3 - 10.0).abs() < 1.0e-9);
+
+    // nodes without data and points off the grid give no shift
+    assert!(grid.shift(lambda, 41.6f64.to_radians()).is_none());
+    assert!(grid.shift(lambda, 39.0f64.to_radians()).is_none());
+}
+
+#[test]
+fn test_ngs() {
+    // a NADCON5 style grid with Fortran record markers, and the same without
+    let (rows, cols) = (3usize, 4usize);
+    let mut marked:Vec<u8> = Vec::new();
+    let mut plain:Vec<u8> = Vec::new();
+    let mut header:Vec<u8> = Vec::new();
+    for v in [30.0f64, 250.0, 1.0, 2.0].iter() {
+        header.extend(&v.to_bits().to_be_bytes());
+    }
+    for v in [rows as i32, cols as i32, 1].iter() {
+        header.extend(&v.to_be_bytes());
+    }
+    marked.extend(&44i32.to_be_bytes());
+    marked.extend(&header);
+    marked.extend(&44i32.to_be_bytes());
+    plain.extend(&header);
+    for j in 0..rows {
+        marked.extend(&(4 * cols as i32).to_be_bytes());
+        for i in 0..cols {
+            let v = (j * 10 + i) as f32;
+            marked.extend(&v.to_bits().to_be_bytes());
+            plain.extend(&v.to_bits().to_be_bytes());
+        }
+        marked.extend(&(4 * cols as i32).to_be_bytes());
+    }
+
+    for bytes in [marked, plain].iter() {
+        let raster = parse_ngs(bytes).unwrap();
+        assert_eq!((raster.rows, raster.cols), (rows, cols));
+        // longitudes east of 180 wrap to the western hemisphere
+        let v = raster.bilinear(0, -107.0, 31.5).unwrap();
+        assert!((v - 16.5).abs() < 1.0e-9);
+    }
+}
+
+#[test]
+fn test_chain() {
+    // an NTv2 horizontal shift followed by a vertical shift, undone in reverse
+    let bytes = ntv2::synthetic(&[("ALL", "NONE", 144000.0, 147600.0, 288000.0, 291600.0, 900.0)],
+                                &|lat, long| ((lat - 144000.0) as f32 * 1.0e-3, 3.0 + (long - 288000.0) as f32 * 1.0e-3));
+    let vertical = synthetic_gtx(40.0, -81.0, 0.5, 0.5, 3, 3, &|lat, _lon| lat as f32);
+    let chain = Chain {steps: vec![
+        Box::new(ntv2::parse(&bytes).unwrap()),
+        Box::new(ShiftGrid {raster: parse_gtx(&vertical).unwrap(), lat_band: None, lon_band: None, h_band: Some(0)})
+    ]};
+
+    let (lambda, phi) = ((-80.5f64).to_radians(), 40.5f64.to_radians());
+    let (lambda2, phi2, h2) = chain.forward(lambda, phi, 100.0).unwrap();
+    assert!(lambda2 < lambda && phi2 > phi);
+    assert!((h2 - 140.5).abs() < 1.0e-3);
+
+    let (lambda3, phi3, h3) = chain.inverse(lambda2, phi2, h2).unwrap();
+    assert!((lambda3 - lambda).abs() < 1.0e-12 && (phi3 - phi).abs() < 1.0e-12);
+    assert!((h3 - 100.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_tiff_shift() {
+    // longitude offsets in degrees positive west ahead of latitude offsets in arc-seconds, 2 by 2
+    let mut strip:Vec<u8> = Vec::new();
+    for _ in 0..4 {
+        strip.extend(&(-1.0f32 / 3600.0).to_bits().to_le_bytes());
+        strip.extend(&2.0f32.to_bits().to_le_bytes());
+    }
+    let fields = vec![(256, vec![2]), (257, vec![2]), (258, vec![32, 32]), (259, vec![1]), (277, vec![2]), (339, vec![3, 3])];
+    let metadata = "<GDALMetadata><Item name=\"DESCRIPTION\" sample=\"0\" role=\"description\">longitude_offset</Item>\
+        <Item name=\"UNITTYPE\" sample=\"0\" role=\"unittype\">degree</Item><Item name=\"positive_value\" sample=\"0\">west</Item>\
+        <Item name=\"DESCRIPTION\" sample=\"1\" role=\"description\">latitude_offset</Item></GDALMetadata>";
+    let (raster, bands) = geotiff::parse_grid(&geotiff::synthetic_tiff(&fields, &[&strip], metadata)).unwrap();
+    let grid = tiff_shift(raster, &bands);
+    assert_eq!((grid.lat_band, grid.lon_band, grid.h_band), (Some(1), Some(0), None));
+
+    let arcsec = (1.0f64 / 3600.0).to_radians();
+    let (dlambda, dphi, dh) = grid.shift((-9.5f64).to_radians(), 49.5f64.to_radians()).unwrap();
+    assert!((dlambda / arcsec - 1.0).abs() < 1.0e-4 && (dphi / arcsec - 2.0).abs() < 1.0e-9 && dh == 0.0);
+
+    // undescribed bands are taken as latitude then longitude offsets
+    let grid = tiff_shift(geotiff::parse(&geotiff::synthetic_tiff(&fields, &[&strip], "")).unwrap(), &[]);
+    assert_eq!((grid.lat_band, grid.lon_band, grid.h_band), (Some(0), Some(1), None));
+}
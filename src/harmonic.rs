@@ -0,0 +1,342 @@
+use std::io;
+
+use convert;
+use ellipsoid;
+use gravity;
+use grid;
+use grid::invalid;
+
+/// Scale applied to the sectoral Legendre functions so high degree terms neither overflow nor
+/// underflow before the final multiplication.
+///
+const SCALE:f64 = 1.0e-280;
+
+/// Spherical harmonic model of the Earth's gravitational potential, such as EGM96 or EGM2008.
+///
+/// Coefficients are fully normalized and stored by degree n and order m at n(n+1)/2 + m.
+///
+#[derive(Clone, Debug)]
+pub struct Model {
+    /// geocentric gravitational constant of the model (m^3/s^2)
+    pub gm : f64,
+    /// reference radius of the model (m)
+    pub a : f64,
+    /// maximum degree
+    pub nmax : usize,
+    /// cosine coefficients
+    pub c : Vec<f64>,
+    /// sine coefficients
+    pub s : Vec<f64>,
+    /// zero degree term of the geoid above the reference ellipsoid (m), from the differences of
+    /// GM and of the geoid and ellipsoid potentials: -0.41 for EGM2008 and -0.53 for EGM96 on WGS84
+    pub n0 : f64
+}
+
+/// Gravity field functionals at a point.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Functionals {
+    /// disturbing potential (m^2/s^2)
+    pub potential : f64,
+    /// gravity anomaly (m/s^2)
+    pub anomaly : f64,
+    /// deflection of the vertical, north-south component (radians)
+    pub xi : f64,
+    /// deflection of the vertical, east-west component (radians)
+    pub eta : f64
+}
+
+/// Fill a column of scaled Legendre functions q(n) = P̄nm(t)/u^m for n = m to nmax.
+///
+/// The column starts from the sectoral value qmm and runs the standard forward recursion in degree.
+///
+fn column(q:&mut Vec<f64>, m:usize, nmax:usize, t:f64, qmm:f64) {
+    q.clear();
+    q.push(qmm);
+    if m < nmax {
+        q.push((2.0 * m as f64 + 3.0).sqrt() * t * qmm);
+    }
+    for n in m + 2..nmax + 1 {
+        let (nf, mf) = (n as f64, m as f64);
+        let a:f64 = ((2.0 * nf - 1.0) * (2.0 * nf + 1.0) / ((nf - mf) * (nf + mf))).sqrt();
+        let b:f64 = ((2.0 * nf + 1.0) * (nf + mf - 1.0) * (nf - mf - 1.0) / ((nf - mf) * (nf + mf) * (2.0 * nf - 3.0))).sqrt();
+        let v = a * t * q[n - m - 1] - b * q[n - m - 2];
+        q.push(v);
+    }
+}
+
+/// Calculate the scaled sectoral values qmm = P̄mm/u^m for m = 0 to nmax.
+///
+fn sectorals(nmax:usize) -> Vec<f64> {
+    let mut qmm = Vec::with_capacity(nmax + 1);
+    qmm.push(SCALE);
+    for m in 1..nmax + 1 {
+        let factor:f64 = if m == 1 { 3.0f64.sqrt() } else { ((2 * m + 1) as f64 / (2 * m) as f64).sqrt() };
+        let v = qmm[m - 1] * factor;
+        qmm.push(v);
+    }
+    qmm
+}
+
+/// Calculate fully normalized associated Legendre functions P̄nm(t), stored at n(n+1)/2 + m.
+///
+/// The recursion runs on values divided by u^m = (1 - t²)^(m/2) and scaled by 1e-280, so it stays
+/// stable beyond degree 2000 at every latitude; terms that underflow on the final multiplication
+/// are too small to matter.
+///
+/// References:
+/// -----------
+/// Holmes, S. A. and Featherstone, W. E. (2002). A unified approach to the Clenshaw
+/// summation and the recursive computation of very high degree and order normalised
+/// associated Legendre functions. J Geod 76:279-299.
+///
+pub fn legendre(nmax:usize, t:f64) -> Vec<f64> {
+    let u:f64 = (1.0 - t * t).sqrt();
+    let qmm = sectorals(nmax);
+    let mut p = vec![0.0; (nmax + 1) * (nmax + 2) / 2];
+    let mut q = Vec::with_capacity(nmax + 1);
+    for m in 0..nmax + 1 {
+        column(&mut q, m, nmax, t, qmm[m]);
+        let um:f64 = u.powi(m as i32) / SCALE;
+        for n in m..nmax + 1 {
+            p[n * (n + 1) / 2 + m] = q[n - m] * um;
+        }
+    }
+    p
+}
+
+impl Model {
+
+    /// Find the storage index of the coefficients of degree n and order m.
+    ///
+    pub fn index(n:usize, m:usize) -> usize {
+        n * (n + 1) / 2 + m
+    }
+
+    /// Calculate the disturbing potential and its derivatives at a point given in geodetic
+    /// longitude lambda and latitude phi, in radians, and height h above the level ellipsoid.
+    ///
+    /// The normal field is subtracted from the even zonal coefficients after rescaling it to the
+    /// model's GM and radius, and the degree 0 and 1 terms are omitted. Spherical harmonics are
+    /// summed over orders by Horner's scheme in u = cos φ̄, so no power u^m is ever formed.
+    ///
+    pub fn functionals(&self, ellps:ellipsoid::Ellipsoid, lambda:f64, phi:f64, h:f64) -> Functionals {
+        let (x, y, z) = convert::lla2ecef(lambda, phi, h, ellps);
+        let r:f64 = (x * x + y * y + z * z).sqrt();
+        let t:f64 = z / r;
+        let u:f64 = (x * x + y * y).sqrt() / r;
+        let rho:f64 = self.a / r;
+        let qmm = sectorals(self.nmax);
+
+        // ratio powers (a/r)^n and the normal zonal coefficients in the model's scale
+        let mut rho_n = Vec::with_capacity(self.nmax + 1);
+        let mut normal_c = Vec::with_capacity(self.nmax + 1);
+        for n in 0..self.nmax + 1 {
+            rho_n.push(rho.powi(n as i32));
+            normal_c.push(gravity::zonal(ellps, n) * ellps.gm / self.gm * (ellps.a / self.a).powi(n as i32));
+        }
+
+        let (mut sum_t, mut sum_g, mut sum_phi, mut sum_lambda) = (0.0, 0.0, 0.0, 0.0);
+        let mut q = Vec::with_capacity(self.nmax + 1);
+        for m in (0..self.nmax + 1).rev() {
+            column(&mut q, m, self.nmax, t, qmm[m]);
+            let (mut ct, mut st, mut cg, mut sg, mut cd, mut sd) = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+            for n in m.max(2)..self.nmax + 1 {
+                let k = Model::index(n, m);
+                let c:f64 = if m == 0 { self.c[k] - normal_c[n] } else { self.c[k] };
+                let s:f64 = self.s[k];
+                let qn = q[n - m];
+                let (nf, mf) = (n as f64, m as f64);
+                // u dP̄nm/dφ = -n t P̄nm + f P̄n-1,m
+                let f:f64 = if n > m { ((nf * nf - mf * mf) * (2.0 * nf + 1.0) / (2.0 * nf - 1.0)).sqrt() } else { 0.0 };
+                let dq = -nf * t * qn + if n > m { f * q[n - m - 1] } else { 0.0 };
+                ct += rho_n[n] * qn * c;
+                st += rho_n[n] * qn * s;
+                cg += (nf - 1.0) * rho_n[n] * qn * c;
+                sg += (nf - 1.0) * rho_n[n] * qn * s;
+                cd += rho_n[n] * dq * c;
+                sd += rho_n[n] * dq * s;
+            }
+            let (sm, cm) = (m as f64 * lambda).sin_cos();
+            sum_t = sum_t * u + ct * cm + st * sm;
+            sum_g = sum_g * u + cg * cm + sg * sm;
+            sum_phi = sum_phi * u + cd * cm + sd * sm;
+            sum_lambda = sum_lambda * u + m as f64 * (st * cm - ct * sm);
+        }
+
+        let gm_r:f64 = self.gm / r / SCALE;
+        let gamma:f64 = gravity::normal_gravity(ellps, phi);
+        let dt_dphi:f64 = gm_r * sum_phi / u;
+        let dt_dlambda:f64 = gm_r * sum_lambda;
+
+        Functionals {
+            potential: gm_r * sum_t,
+            anomaly: gm_r / r * sum_g,
+            xi: -dt_dphi / (gamma * r),
+            eta: -dt_dlambda / (gamma * r * u)
+        }
+    }
+
+    /// Calculate the height anomaly, in meters, at geodetic longitude lambda and latitude phi by
+    /// Bruns' formula ζ = T/γ on the ellipsoid, with the zero degree term.
+    ///
+    pub fn height_anomaly(&self, ellps:ellipsoid::Ellipsoid, lambda:f64, phi:f64) -> f64 {
+        self.n0 + self.functionals(ellps, lambda, phi, 0.0).potential / gravity::normal_gravity(ellps, phi)
+    }
+
+    /// Sum the surface spherical harmonics of the coefficients, without any radial factor, at
+    /// geodetic longitude lambda and latitude phi on the ellipsoid.
+    ///
+    pub fn synthesis(&self, ellps:ellipsoid::Ellipsoid, lambda:f64, phi:f64) -> f64 {
+        let (x, y, z) = convert::lla2ecef(lambda, phi, 0.0, ellps);
+        let p = legendre(self.nmax, z / (x * x + y * y + z * z).sqrt());
+        let mut sum:f64 = 0.0;
+        for n in 0..self.nmax + 1 {
+            for m in 0..n + 1 {
+                let k = Model::index(n, m);
+                let (sm, cm) = (m as f64 * lambda).sin_cos();
+                sum += p[k] * (self.c[k] * cm + self.s[k] * sm);
+            }
+        }
+        sum
+    }
+
+    /// Calculate the geoid undulation, in meters, at geodetic longitude lambda and latitude phi.
+    ///
+    /// The undulation is the height anomaly plus the correction from height anomaly to geoid
+    /// undulation, which over land depends on the topography and is synthesized from its own
+    /// coefficients, e.g. Zeta-to-N_to2160_egm2008. Without them the height anomaly is returned,
+    /// which is the undulation over the oceans.
+    ///
+    /// References:
+    /// -----------
+    /// Pavlis, N. K., Holmes, S. A., Kenyon, S. C. and Factor, J. K. (2012). The
+    /// development and evaluation of the Earth Gravitational Model 2008 (EGM2008).
+    /// J Geophys Res 117:B04406, sec. 5.
+    ///
+    pub fn undulation(&self, ellps:ellipsoid::Ellipsoid, lambda:f64, phi:f64, correction:Option<&Model>) -> f64 {
+        let c:f64 = correction.map_or(0.0, |model| model.synthesis(ellps, lambda, phi));
+        self.height_anomaly(ellps, lambda, phi) + c
+    }
+}
+
+/// Parse a coefficient file truncated at degree nmax.
+///
+/// Both the ICGEM format, with gfc data lines after a header giving earth_gravity_constant and
+/// radius, and the plain n m C S columns of the NGA EGM files are read; Fortran D exponents are
+/// accepted. Plain files take the EGM96 and EGM2008 constants GM = 3986004.415e8 m^3/s^2 and
+/// a = 6378136.3 m. The zero degree term is that of EGM2008 on WGS84.
+///
+/// References:
+/// -----------
+/// Pavlis, N. K., Holmes, S. A., Kenyon, S. C. and Factor, J. K. (2012). The
+/// development and evaluation of the Earth Gravitational Model 2008 (EGM2008).
+/// J Geophys Res 117:B04406.
+///
+/// Barthelmes, F. and Förste, C. (2011). The ICGEM-format. GFZ Potsdam.
+///
+/// NGA. EGM2008 - WGS 84 Version. https://earth-info.nga.mil/index.php?dir=wgs84&action=wgs84
+///
+pub fn parse(text:&str, nmax:usize) -> io::Result<Model> {
+    let size = Model::index(nmax, nmax) + 1;
+    let mut model = Model {gm: 3986004.415e8, a: 6378136.3, nmax: nmax, c: vec![0.0; size], s: vec![0.0; size],
+        n0: -0.41};
+    let number = |v:&str| v.replace('D', "E").replace('d', "e").parse::<f64>()
+        .map_err(|_| invalid(&format!("could not parse {} in coefficient file", v)));
+
+    let mut header = text.contains("end_of_head");
+    for line in text.lines() {
+        let words:Vec<&str> = line.split_whitespace().collect();
+        if header {
+            match words.as_slice() {
+                ["earth_gravity_constant", value, ..] => model.gm = number(value)?,
+                ["radius", value, ..] => model.a = number(value)?,
+                ["end_of_head", ..] => header = false,
+                _ => ()
+            }
+            continue;
+        }
+        let words = if words.first() == Some(&"gfc") { &words[1..] } else { &words[..] };
+        if words.len() < 4 {
+            continue;
+        }
+        let (n, m) = (number(words[0])? as usize, number(words[1])? as usize);
+        if n <= nmax && m <= n {
+            let k = Model::index(n, m);
+            model.c[k] = number(words[2])?;
+            model.s[k] = number(words[3])?;
+        }
+    }
+    Ok(model)
+}
+
+/// Read a coefficient file, e.g. EGM2008_to2190_TideFree or an ICGEM .gfc file, to degree nmax.
+///
+pub fn read(path:&str, nmax:usize) -> io::Result<Model> {
+    let bytes = grid::contents(path)?;
+    parse(&String::from_utf8_lossy(&bytes), nmax)
+}
+
+#[test]
+fn test_legendre() {
+    // the functions of each degree satisfy sum over m of P̄nm² = 2n + 1 at any latitude
+    let nmax:usize = 2190;
+    for &lat in [0.0f64, 30.0, 89.9].iter() {
+        let p = legendre(nmax, lat.to_radians().sin());
+        for &n in [2usize, 360, 2190].iter() {
+            let sum:f64 = (0..n + 1).map(|m| p[Model::index(n, m)].powi(2)).sum();
+            assert!((sum / (2 * n + 1) as f64 - 1.0).abs() < 1.0e-9);
+        }
+    }
+    // P̄20 = sqrt(5) (3t² - 1) / 2
+    let t:f64 = 0.3;
+    let p = legendre(2, t);
+    assert!((p[3] - 5.0f64.sqrt() * (3.0 * t * t - 1.0) / 2.0).abs() < 1.0e-14);
+}
+
+#[test]
+fn test_functionals() {
+    let wgs84 = ellipsoid::named("WGS84").unwrap();
+
+    // a model of the normal field alone has no disturbing potential
+    let nmax:usize = 8;
+    let mut text = String::from("radius 6378137.0\nearth_gravity_constant 3.986004418D+14\nend_of_head\n");
+    for n in (2..nmax + 1).step_by(2) {
+        text.push_str(&format!("gfc {} 0 {:e} 0.0 0.0 0.0\n", n, gravity::zonal(wgs84, n)));
+    }
+    let model = parse(&text, nmax).unwrap();
+    let f = model.functionals(wgs84, 0.3, 0.7, 100.0);
+    assert!(f.potential.abs() < 1.0e-6 && f.anomaly.abs() < 1.0e-12);
+
+    // a single sectoral term: check the anomaly and deflections against the potential
+    let mut model = model;
+    model.c[Model::index(3, 3)] = 1.0e-6;
+    model.s[Model::index(3, 3)] = 0.5e-6;
+    let (lambda, phi, h) = (0.4, 0.6, 0.0);
+    let f = model.functionals(wgs84, lambda, phi, h);
+    let (x, y, z) = convert::lla2ecef(lambda, phi, h, wgs84);
+    let r:f64 = (x * x + y * y + z * z).sqrt();
+    let gamma = gravity::normal_gravity(wgs84, phi);
+    // T = GM/r (a/r)^3 P̄33 (C cos 3λ + S sin 3λ) with P̄33 = sqrt(35/8) u³
+    let u:f64 = (x * x + y * y).sqrt() / r;
+    let expected:f64 = model.gm / r * (model.a / r).powi(3) * (35.0f64 / 8.0).sqrt() * u.powi(3)
+        * (1.0e-6 * (3.0 * lambda).cos() + 0.5e-6 * (3.0 * lambda).sin());
+    assert!((f.potential / expected - 1.0).abs() < 1.0e-12);
+    assert!((f.anomaly - 2.0 * f.potential / r).abs() < 1.0e-12);
+    let dt_dlambda:f64 = model.gm / r * (model.a / r).powi(3) * (35.0f64 / 8.0).sqrt() * u.powi(3)
+        * 3.0 * (-1.0e-6 * (3.0 * lambda).sin() + 0.5e-6 * (3.0 * lambda).cos());
+    assert!((f.eta + dt_dlambda / (gamma * r * u)).abs() < 1.0e-15);
+    // dT/dφ̄ = -3 T tan φ̄ for a sectoral term
+    let expected_xi:f64 = 3.0 * f.potential * (z / (x * x + y * y).sqrt()) / (gamma * r);
+    assert!((f.xi - expected_xi).abs() < 1.0e-15);
+
+    let n = model.undulation(wgs84, lambda, phi, None);
+    assert!((n - expected / gamma + 0.41).abs() < 1.0e-6);
+    assert_eq!(n, model.height_anomaly(wgs84, lambda, phi));
+
+    // the correction to the geoid is a plain surface synthesis: C̄10 P̄10 + C̄11 P̄11 cos λ
+    let correction = parse("1 0 0.5 0.0\n1 1 -0.2 0.3\n", 1).unwrap();
+    let t:f64 = z / r;
+    let c:f64 = 3.0f64.sqrt() * (0.5 * t + u * (-0.2 * lambda.cos() + 0.3 * lambda.sin()));
+    assert!((model.undulation(wgs84, lambda, phi, Some(&correction)) - n - c).abs() < 1.0e-12);
+}
@@ -0,0 +1,134 @@
+use matrix;
+
+/// Sign convention for the rotation parameters of a Helmert transformation.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Convention {
+    /// rotations turn the position vector, as used by EPSG method 1033 and IERS
+    PositionVector,
+    /// rotations turn the coordinate frame, as used by EPSG method 1032
+    CoordinateFrame
+}
+
+impl Convention {
+
+    /// Look up a convention by name, e.g. position-vector or coordinate-frame.
+    ///
+    pub fn named(name:&str) -> Option<Convention> {
+        match name {
+            "position-vector" | "pv" => Some(Convention::PositionVector),
+            "coordinate-frame" | "cf" => Some(Convention::CoordinateFrame),
+            _ => None
+        }
+    }
+}
+
+/// Seven parameter Helmert similarity transformation between ECEF frames.
+///
+/// References:
+/// -----------
+/// IOGP (2019). Geomatics Guidance Note 7-2, Helmert 7-parameter transformations,
+/// EPSG methods 1032 and 1033.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Helmert {
+    /// translation along x (m)
+    pub tx : f64,
+    /// translation along y (m)
+    pub ty : f64,
+    /// translation along z (m)
+    pub tz : f64,
+    /// rotation about x (arc-seconds)
+    pub rx : f64,
+    /// rotation about y (arc-seconds)
+    pub ry : f64,
+    /// rotation about z (arc-seconds)
+    pub rz : f64,
+    /// scale difference (ppm)
+    pub ds : f64,
+    /// rotation sign convention
+    pub convention : Convention
+}
+
+impl Helmert {
+
+    /// Construct a transformation from translations in meters, rotations in arc-seconds and scale
+    /// difference in parts per million.
+    ///
+    pub fn new(tx:f64, ty:f64, tz:f64, rx:f64, ry:f64, rz:f64, ds:f64, convention:Convention) -> Helmert {
+        Helmert {tx: tx, ty: ty, tz: tz, rx: rx, ry: ry, rz: rz, ds: ds, convention: convention}
+    }
+
+    /// Construct a translation only transformation.
+    ///
+    pub fn translation(tx:f64, ty:f64, tz:f64) -> Helmert {
+        Helmert::new(tx, ty, tz, 0.0, 0.0, 0.0, 0.0, Convention::PositionVector)
+    }
+
+    /// Calculate the combined scale and small angle rotation matrix applied to position vectors.
+    ///
+    pub fn matrix(&self) -> matrix::Matrix3 {
+        let arcsec:f64 = (1.0f64 / 3600.0).to_radians();
+        let (rx, ry, rz) = match self.convention {
+            Convention::PositionVector => (self.rx * arcsec, self.ry * arcsec, self.rz * arcsec),
+            Convention::CoordinateFrame => (-self.rx * arcsec, -self.ry * arcsec, -self.rz * arcsec)
+        };
+        let m:f64 = 1.0 + self.ds * 1.0e-6;
+
+        [[m, -m * rz, m * ry],
+         [m * rz, m, -m * rx],
+         [-m * ry, m * rx, m]]
+    }
+
+    /// Transform ECEF coordinates from the source frame to the target frame.
+    ///
+    pub fn transform(&self, x:f64, y:f64, z:f64) -> (f64, f64, f64) {
+        let v = matrix::mul_vec(&self.matrix(), [x, y, z]);
+
+        (v[0] + self.tx, v[1] + self.ty, v[2] + self.tz)
+    }
+
+    /// Transform ECEF coordinates from the target frame back to the source frame.
+    ///
+    /// The scale and rotation matrix is inverted exactly rather than by reversing the sign of the
+    /// parameters, so a round trip through transform and inverse recovers the input.
+    ///
+    pub fn inverse(&self, x:f64, y:f64, z:f64) -> (f64, f64, f64) {
+        let inv = matrix::inverse(&self.matrix()).expect("Helmert matrix is singular");
+        let v = matrix::mul_vec(&inv, [x - self.tx, y - self.ty, z - self.tz]);
+
+        (v[0], v[1], v[2])
+    }
+}
+
+#[test]
+fn test_helmert_position_vector() {
+    // EPSG Guidance Note 7-2 example, WGS 72 to WGS 84
+    let expected = (3657660.78, 255778.43, 5201387.75);
+    let tolerance:f64 = 0.01;
+
+    let helmert = Helmert::new(0.0, 0.0, 4.5, 0.0, 0.0, 0.554, 0.219, Convention::PositionVector);
+    let (x, y, z) = helmert.transform(3657660.66, 255768.55, 5201382.11);
+    println!("x {0} y {1} z {2}", x, y, z);
+    assert!((x - expected.0).abs() < tolerance);
+    assert!((y - expected.1).abs() < tolerance);
+    assert!((z - expected.2).abs() < tolerance);
+
+    let (x, y, z) = helmert.inverse(x, y, z);
+    assert!((x - 3657660.66).abs() < 1.0e-6);
+    assert!((y - 255768.55).abs() < 1.0e-6);
+    assert!((z - 5201382.11).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_helmert_coordinate_frame() {
+    // EPSG Guidance Note 7-2 example, the same transformation with coordinate frame rotation
+    let expected = (3657660.78, 255778.43, 5201387.75);
+    let tolerance:f64 = 0.01;
+
+    let helmert = Helmert::new(0.0, 0.0, 4.5, 0.0, 0.0, -0.554, 0.219, Convention::CoordinateFrame);
+    let (x, y, z) = helmert.transform(3657660.66, 255768.55, 5201382.11);
+    assert!((x - expected.0).abs() < tolerance);
+    assert!((y - expected.1).abs() < tolerance);
+    assert!((z - expected.2).abs() < tolerance);
+}
@@ -0,0 +1,222 @@
+use helmert;
+
+/// Fourteen parameter time dependent Helmert transformation between terrestrial reference frames.
+///
+/// Parameters follow the IERS conventions: translations in millimeters, scale difference in parts
+/// per billion and position vector rotations in milliarc-seconds, each with a rate per year, valid
+/// at a reference epoch in decimal years.
+///
+/// References:
+/// -----------
+/// Petit, G. and Luzum, B. (eds.) (2010). IERS Conventions (2010). IERS
+/// Technical Note 36, eq. 4.13.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct TimeHelmert {
+    /// source frame
+    pub from : &'static str,
+    /// target frame
+    pub to : &'static str,
+    /// translations (mm)
+    pub t : [f64; 3],
+    /// scale difference (ppb)
+    pub d : f64,
+    /// rotations (mas)
+    pub r : [f64; 3],
+    /// translation rates (mm/yr)
+    pub t_rate : [f64; 3],
+    /// scale difference rate (ppb/yr)
+    pub d_rate : f64,
+    /// rotation rates (mas/yr)
+    pub r_rate : [f64; 3],
+    /// reference epoch (decimal year)
+    pub epoch : f64
+}
+
+impl TimeHelmert {
+
+    /// Evaluate the seven parameter transformation valid at an epoch, in decimal years.
+    ///
+    pub fn at(&self, epoch:f64) -> helmert::Helmert {
+        let dt:f64 = epoch - self.epoch;
+        let t = |i:usize| (self.t[i] + self.t_rate[i] * dt) * 1.0e-3;
+        let r = |i:usize| (self.r[i] + self.r_rate[i] * dt) * 1.0e-3;
+        let d:f64 = (self.d + self.d_rate * dt) * 1.0e-3;
+
+        helmert::Helmert::new(t(0), t(1), t(2), r(0), r(1), r(2), d, helmert::Convention::PositionVector)
+    }
+
+    /// Transform ECEF coordinates observed at an epoch, in decimal years, from the source frame to
+    /// the target frame.
+    ///
+    pub fn transform(&self, x:f64, y:f64, z:f64, epoch:f64) -> (f64, f64, f64) {
+        self.at(epoch).transform(x, y, z)
+    }
+
+    /// Construct the same transformation with its parameters referred to another epoch.
+    ///
+    pub fn shifted(&self, epoch:f64) -> TimeHelmert {
+        let dt:f64 = epoch - self.epoch;
+        let add = |v:[f64; 3], rate:[f64; 3]| [v[0] + rate[0] * dt, v[1] + rate[1] * dt, v[2] + rate[2] * dt];
+
+        TimeHelmert {t: add(self.t, self.t_rate), d: self.d + self.d_rate * dt, r: add(self.r, self.r_rate),
+            epoch: epoch, ..*self}
+    }
+
+    /// Compose this transformation with a following one from its target frame, by referring both
+    /// to this epoch and adding their parameters. Products of the parameters, below a micrometer
+    /// at the Earth's surface, are neglected as in the published ITRF parameters.
+    ///
+    pub fn then(&self, next:&TimeHelmert) -> TimeHelmert {
+        let next = next.shifted(self.epoch);
+        let add = |u:[f64; 3], v:[f64; 3]| [u[0] + v[0], u[1] + v[1], u[2] + v[2]];
+
+        TimeHelmert {from: self.from, to: next.to, t: add(self.t, next.t), d: self.d + next.d, r: add(self.r, next.r),
+            t_rate: add(self.t_rate, next.t_rate), d_rate: self.d_rate + next.d_rate, r_rate: add(self.r_rate, next.r_rate),
+            epoch: self.epoch}
+    }
+
+    /// Construct the reverse transformation by changing the sign of every parameter.
+    ///
+    pub fn reversed(&self) -> TimeHelmert {
+        let neg = |v:[f64; 3]| [-v[0], -v[1], -v[2]];
+
+        TimeHelmert {from: self.to, to: self.from, t: neg(self.t), d: -self.d, r: neg(self.r),
+            t_rate: neg(self.t_rate), d_rate: -self.d_rate, r_rate: neg(self.r_rate), epoch: self.epoch}
+    }
+}
+
+macro_rules! params {
+    ($from:expr, $to:expr, $t:expr, $d:expr, $r:expr, $t_rate:expr, $d_rate:expr, $r_rate:expr, $epoch:expr) => (
+        TimeHelmert { from: $from, to: $to, t: $t, d: $d, r: $r, t_rate: $t_rate, d_rate: $d_rate, r_rate: $r_rate, epoch: $epoch }
+    )
+}
+
+/// Published transformation parameters between ITRF realisations and to ETRF.
+///
+/// References:
+/// -----------
+/// IGN (2022). Transformation parameters from ITRF2020 to past ITRFs.
+/// https://itrf.ign.fr/docs/solutions/itrf2020/Transfo-ITRF2020_TRFs.txt
+///
+/// IGN (2016). Transformation parameters from ITRF2014 to past ITRFs.
+/// https://itrf.ign.fr/docs/solutions/itrf2014/Transfo-ITRF2014_ITRFs.txt
+///
+/// Altamimi, Z. (2018, 2023). EUREF Technical Notes 1: Relationship and
+/// transformation between the ITRF and ETRF.
+///
+pub static PARAMETERS: &'static [TimeHelmert] = &[
+    params!("ITRF2020", "ITRF2014", [-1.4, -0.9, 1.4], -0.42, [0.0, 0.0, 0.0], [0.0, -0.1, 0.2], 0.00, [0.0, 0.0, 0.0], 2015.0),
+    params!("ITRF2020", "ITRF2008", [0.2, 1.0, 3.3], -0.29, [0.0, 0.0, 0.0], [0.0, -0.1, 0.1], 0.03, [0.0, 0.0, 0.0], 2015.0),
+    params!("ITRF2020", "ITRF2005", [2.7, 0.1, -1.4], 0.65, [0.0, 0.0, 0.0], [0.3, -0.1, 0.1], 0.03, [0.0, 0.0, 0.0], 2015.0),
+    params!("ITRF2020", "ITRF2000", [-0.2, 0.8, -34.2], 2.25, [0.0, 0.0, 0.0], [0.1, 0.0, -1.7], 0.11, [0.0, 0.0, 0.0], 2015.0),
+    params!("ITRF2014", "ITRF2008", [1.6, 1.9, 2.4], -0.02, [0.0, 0.0, 0.0], [0.0, 0.0, -0.1], 0.03, [0.0, 0.0, 0.0], 2010.0),
+    params!("ITRF2014", "ITRF2005", [2.6, 1.0, -2.3], 0.92, [0.0, 0.0, 0.0], [0.3, 0.0, -0.1], 0.03, [0.0, 0.0, 0.0], 2010.0),
+    params!("ITRF2020", "ETRF2020", [0.0, 0.0, 0.0], 0.0, [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], 0.0, [0.086, 0.519, -0.753], 1989.0),
+    params!("ITRF2014", "ETRF2014", [0.0, 0.0, 0.0], 0.0, [0.0, 0.0, 0.0], [0.0, 0.0, 0.0], 0.0, [0.085, 0.531, -0.770], 1989.0),
+];
+
+/// Look up the published transformation between two frames in either direction.
+///
+fn published(from:&str, to:&str) -> Option<TimeHelmert> {
+    for params in PARAMETERS.iter() {
+        if params.from == from && params.to == to {
+            return Some(*params);
+        }
+        if params.from == to && params.to == from {
+            return Some(params.reversed());
+        }
+    }
+    None
+}
+
+/// Look up the transformation between two frames, e.g. ITRF2014 and ITRF2020, in either direction.
+///
+/// Frames without published parameters between them, e.g. ITRF2008 and ITRF2005, are related
+/// through a frame both have parameters to, ITRF2020 or else ITRF2014. A known frame is related to
+/// itself by the identity.
+///
+pub fn find(from:&str, to:&str) -> Option<TimeHelmert> {
+    if from == to {
+        let zero = [0.0, 0.0, 0.0];
+        return PARAMETERS.iter().filter_map(|p| if p.from == from { Some(p.from) } else if p.to == from { Some(p.to) } else { None })
+            .next().map(|frame| params!(frame, frame, zero, 0.0, zero, zero, 0.0, zero, 2000.0));
+    }
+    published(from, to).or_else(|| {
+        PARAMETERS.iter().filter_map(|p| if p.from == from { Some(p.to) } else if p.to == from { Some(p.from) } else { None })
+            .filter_map(|via| published(via, to).map(|second| published(from, via).unwrap().then(&second)))
+            .next()
+    })
+}
+
+#[test]
+fn test_itrf2020_to_itrf2014() {
+    let x:f64 = 4027893.6719;
+    let y:f64 = 307045.9064;
+    let z:f64 = 4919475.1704;
+
+    let params = find("ITRF2020", "ITRF2014").unwrap();
+    let (x2, y2, z2) = params.transform(x, y, z, 2020.0);
+    // translation at 2020.0 is (-1.4, -1.4, 2.4) mm and scale -0.42 ppb
+    let expected = (x - 1.4e-3 - 0.42e-9 * x, y - 1.4e-3 - 0.42e-9 * y, z + 2.4e-3 - 0.42e-9 * z);
+    assert!((x2 - expected.0).abs() < 1.0e-6);
+    assert!((y2 - expected.1).abs() < 1.0e-6);
+    assert!((z2 - expected.2).abs() < 1.0e-6);
+
+    let back = find("ITRF2014", "ITRF2020").unwrap();
+    let (x3, y3, z3) = back.transform(x2, y2, z2, 2020.0);
+    assert!((x3 - x).abs() < 1.0e-6 && (y3 - y).abs() < 1.0e-6 && (z3 - z).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_itrf2020_to_etrf2020() {
+    // the frames coincide at 1989.0 and drift apart with the Eurasian plate
+    let params = find("ITRF2020", "ETRF2020").unwrap();
+    let (x, y, z) = params.transform(4027893.6719, 307045.9064, 4919475.1704, 1989.0);
+    assert!((x - 4027893.6719).abs() < 1.0e-9);
+    assert!((y - 307045.9064).abs() < 1.0e-9);
+    assert!((z - 4919475.1704).abs() < 1.0e-9);
+
+    let (x, y, z) = params.transform(4027893.6719, 307045.9064, 4919475.1704, 2020.0);
+    let shift:f64 = ((x - 4027893.6719).powi(2) + (y - 307045.9064).powi(2) + (z - 4919475.1704).powi(2)).sqrt();
+    // about 2.5 cm per year in Europe
+    assert!(shift > 0.6 && shift < 0.9);
+}
+
+#[test]
+fn test_identity() {
+    let params = find("ITRF2014", "ITRF2014").unwrap();
+    assert_eq!((params.from, params.to), ("ITRF2014", "ITRF2014"));
+    let (x, y, z) = params.transform(4027893.6719, 307045.9064, 4919475.1704, 2024.5);
+    assert!((x - 4027893.6719).abs() < 1.0e-9 && (y - 307045.9064).abs() < 1.0e-9 && (z - 4919475.1704).abs() < 1.0e-9);
+    assert!(find("ETRF2020", "ETRF2020").is_some());
+    assert!(find("ITRF1900", "ITRF1900").is_none());
+}
+
+#[test]
+fn test_chained() {
+    // ITRF2014 to ITRF2000 through ITRF2020 against the parameters published with ITRF2014
+    let chained = find("ITRF2014", "ITRF2000").unwrap();
+    let published = params!("ITRF2014", "ITRF2000", [0.7, 1.2, -26.1], 2.12, [0.0, 0.0, 0.0], [0.1, 0.1, -1.9], 0.11, [0.0, 0.0, 0.0], 2010.0);
+    assert_eq!((chained.from, chained.to), ("ITRF2014", "ITRF2000"));
+    let (x, y, z) = (4027893.6719, 307045.9064, 4919475.1704);
+    for &epoch in [2000.0, 2010.0, 2024.5].iter() {
+        let (x1, y1, z1) = chained.transform(x, y, z, epoch);
+        let (x2, y2, z2) = published.transform(x, y, z, epoch);
+        assert!((x1 - x2).abs() < 1.0e-6 && (y1 - y2).abs() < 1.0e-6 && (z1 - z2).abs() < 1.0e-6);
+    }
+
+    // ITRF2008 to ITRF2005 against the parameters published with ITRF2008
+    let chained = find("ITRF2008", "ITRF2005").unwrap();
+    let published = params!("ITRF2008", "ITRF2005", [-2.0, -0.9, -4.7], 0.94, [0.0, 0.0, 0.0], [0.3, 0.0, 0.0], 0.0, [0.0, 0.0, 0.0], 2000.0);
+    for &epoch in [2000.0, 2015.0].iter() {
+        let (x1, y1, z1) = chained.transform(x, y, z, epoch);
+        let (x2, y2, z2) = published.transform(x, y, z, epoch);
+        assert!((x1 - x2).abs() < 1.0e-6 && (y1 - y2).abs() < 1.0e-6 && (z1 - z2).abs() < 1.0e-6);
+    }
+    let (x1, y1, z1) = find("ITRF2005", "ITRF2008").unwrap().transform(x, y, z, 2015.0);
+    let (x2, y2, z2) = chained.transform(x1, y1, z1, 2015.0);
+    assert!((x2 - x).abs() < 1.0e-6 && (y2 - y).abs() < 1.0e-6 && (z2 - z).abs() < 1.0e-6);
+
+    assert!(find("ITRF2014", "ITRF97").is_none());
+}
@@ -0,0 +1,150 @@
+use std::f64::consts::FRAC_PI_2;
+
+use ellipsoid;
+use projection;
+use projection::Projection;
+
+/// Lambert Conformal Conic projection parameters.
+///
+#[derive(Copy, Clone)]
+pub struct LambertConformalConic {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid,
+    /// central meridian (radians)
+    pub lambda0 : f64,
+    /// latitude of origin (radians)
+    pub phi0 : f64,
+    /// scale factor at the standard parallel
+    pub k0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64,
+
+    pub n : f64,
+    pub af : f64,
+    pub rho0 : f64
+}
+
+impl LambertConformalConic {
+
+    /// Construct a one standard parallel (1SP) projection with the natural origin at phi0.
+    ///
+    pub fn new_1sp(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi0:f64, k0:f64, x0:f64, y0:f64) -> LambertConformalConic {
+        let n:f64 = phi0.sin();
+        let m0:f64 = projection::msfn(phi0, ellps.ecc);
+        let t0:f64 = projection::tsfn(phi0, ellps.ecc);
+        let af:f64 = ellps.a * k0 * m0 / (n * t0.powf(n));
+        let rho0:f64 = af * t0.powf(n);
+
+        LambertConformalConic {ellps: ellps, lambda0: lambda0, phi0: phi0, k0: k0, x0: x0, y0: y0, n: n, af: af, rho0: rho0}
+    }
+
+    /// Construct a two standard parallel (2SP) projection with standard parallels phi1 and phi2
+    /// and the false origin at phi0.
+    ///
+    pub fn new_2sp(ellps:ellipsoid::Ellipsoid, lambda0:f64, phi0:f64, phi1:f64, phi2:f64, x0:f64, y0:f64) -> LambertConformalConic {
+        let m1:f64 = projection::msfn(phi1, ellps.ecc);
+        let m2:f64 = projection::msfn(phi2, ellps.ecc);
+        let t0:f64 = projection::tsfn(phi0, ellps.ecc);
+        let t1:f64 = projection::tsfn(phi1, ellps.ecc);
+        let t2:f64 = projection::tsfn(phi2, ellps.ecc);
+
+        // a tangent cone when the standard parallels coincide
+        let n:f64 = if (phi1 - phi2).abs() < 1.0e-10 {
+            phi1.sin()
+        } else {
+            (m1.ln() - m2.ln()) / (t1.ln() - t2.ln())
+        };
+        let af:f64 = ellps.a * m1 / (n * t1.powf(n));
+        let rho0:f64 = af * t0.powf(n);
+
+        LambertConformalConic {ellps: ellps, lambda0: lambda0, phi0: phi0, k0: 1.0, x0: x0, y0: y0, n: n, af: af, rho0: rho0}
+    }
+}
+
+impl Projection for LambertConformalConic {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    /// Project geodetic longitude lambda and latitude phi, given in radians, to easting and northing.
+    ///
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let rho:f64 = if (phi.abs() - FRAC_PI_2).abs() < 1.0e-10 {
+            if phi * self.n > 0.0 { 0.0 } else { f64::INFINITY }
+        } else {
+            self.af * projection::tsfn(phi, self.ellps.ecc).powf(self.n)
+        };
+        let theta:f64 = self.n * (lambda - self.lambda0);
+
+        let x:f64 = self.x0 + rho * theta.sin();
+        let y:f64 = self.y0 + self.rho0 - rho * theta.cos();
+
+        (x, y)
+    }
+
+    /// Recover geodetic longitude and latitude, in radians, from easting and northing.
+    ///
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        let dx:f64 = x - self.x0;
+        let dy:f64 = self.rho0 - (y - self.y0);
+        let sign:f64 = self.n.signum();
+
+        let rho:f64 = sign * (dx * dx + dy * dy).sqrt();
+        let theta:f64 = (sign * dx).atan2(sign * dy);
+
+        let phi:f64 = if rho.abs() < 1.0e-10 {
+            sign * FRAC_PI_2
+        } else {
+            let t:f64 = (rho / self.af).powf(1.0 / self.n);
+            projection::phi2(t, self.ellps.ecc)
+        };
+        let lambda:f64 = theta / self.n + self.lambda0;
+
+        (lambda, phi)
+    }
+}
+
+#[test]
+fn test_lcc_2sp() {
+    // Snyder (1987) numerical example, p. 296
+    let expected = (1894410.9, 1564649.5);
+    let tolerance:f64 = 0.1;
+
+    let clarke1866 = ellipsoid::Ellipsoid::new(6378206.400, 294.978698214);
+    let proj = LambertConformalConic::new_2sp(clarke1866, -96f64.to_radians(), 23f64.to_radians(),
+        33f64.to_radians(), 45f64.to_radians(), 0.0, 0.0);
+
+    let (x, y) = proj.forward(-75f64.to_radians(), 35f64.to_radians());
+    println!("x {0} y {1}", x, y);
+    assert!((x - expected.0).abs() < tolerance);
+    assert!((y - expected.1).abs() < tolerance);
+
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() + 75.0).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - 35.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_lcc_1sp() {
+    // EPSG Guidance Note 7-2 example, Jamaica National Grid
+    let expected = (255966.58, 142493.51);
+    let tolerance:f64 = 0.01;
+
+    let clarke1866 = ellipsoid::Ellipsoid::new(6378206.400, 294.978698214);
+    let proj = LambertConformalConic::new_1sp(clarke1866, -77f64.to_radians(), 18f64.to_radians(),
+        1.0, 250000.0, 150000.0);
+
+    let lon:f64 = -(76.0 + 56.0/60.0 + 37.26/3600.0);
+    let lat:f64 = 17.0 + 55.0/60.0 + 55.80/3600.0;
+    let (x, y) = proj.forward(lon.to_radians(), lat.to_radians());
+    println!("x {0} y {1}", x, y);
+    assert!((x - expected.0).abs() < tolerance);
+    assert!((y - expected.1).abs() < tolerance);
+
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() - lon).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - lat).abs() < 1.0e-9);
+}
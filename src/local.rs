@@ -0,0 +1,297 @@
+use std::f64::consts::PI;
+
+use convert;
+use ellipsoid;
+use matrix;
+use matrix::Matrix3;
+
+/// Calculate the rotation from ECEF to the local east, north, up frame at lambda, phi in radians.
+///
+/// The rows are the east, north and up unit vectors expressed in ECEF; the transpose rotates back.
+///
+pub fn rotation(lambda:f64, phi:f64) -> Matrix3 {
+    let (sl, cl) = (lambda.sin(), lambda.cos());
+    let (sp, cp) = (phi.sin(), phi.cos());
+
+    [[-sl, cl, 0.0],
+     [-sp * cl, -sp * sl, cp],
+     [cp * cl, cp * sl, sp]]
+}
+
+/// Convert an ECEF position to east, north, up coordinates relative to an origin at geodetic
+/// lambda0, phi0, h0.
+///
+pub fn ecef2enu(x:f64, y:f64, z:f64, lambda0:f64, phi0:f64, h0:f64, ellps:ellipsoid::Ellipsoid) -> (f64, f64, f64) {
+    let (x0, y0, z0) = convert::lla2ecef(lambda0, phi0, h0, ellps);
+    let v = matrix::mul_vec(&rotation(lambda0, phi0), [x - x0, y - y0, z - z0]);
+
+    (v[0], v[1], v[2])
+}
+
+/// Convert east, north, up coordinates relative to an origin at geodetic lambda0, phi0, h0 to ECEF.
+///
+pub fn enu2ecef(e:f64, n:f64, u:f64, lambda0:f64, phi0:f64, h0:f64, ellps:ellipsoid::Ellipsoid) -> (f64, f64, f64) {
+    let (x0, y0, z0) = convert::lla2ecef(lambda0, phi0, h0, ellps);
+    let v = matrix::mul_vec(&matrix::transpose(&rotation(lambda0, phi0)), [e, n, u]);
+
+    (x0 + v[0], y0 + v[1], z0 + v[2])
+}
+
+/// Calculate the azimuth, clockwise from north, and elevation, in radians, and the slant range, in
+/// meters, of an ECEF position seen from an observer at geodetic lambda0, phi0, h0.
+///
+pub fn look_angles(x:f64, y:f64, z:f64, lambda0:f64, phi0:f64, h0:f64, ellps:ellipsoid::Ellipsoid) -> (f64, f64, f64) {
+    let (e, n, u) = ecef2enu(x, y, z, lambda0, phi0, h0, ellps);
+    let horizontal:f64 = e.hypot(n);
+
+    (e.atan2(n).rem_euclid(2.0 * PI), u.atan2(horizontal), horizontal.hypot(u))
+}
+
+/// Rotate an ECEF velocity, in m/s, to east, north, up at lambda, phi.
+///
+/// The up component is the vertical rate, positive climbing.
+///
+pub fn velocity_ecef2enu(vx:f64, vy:f64, vz:f64, lambda:f64, phi:f64) -> (f64, f64, f64) {
+    let v = matrix::mul_vec(&rotation(lambda, phi), [vx, vy, vz]);
+    (v[0], v[1], v[2])
+}
+
+/// Rotate an east, north, up velocity, in m/s, at lambda, phi to ECEF.
+///
+pub fn velocity_enu2ecef(ve:f64, vn:f64, vu:f64, lambda:f64, phi:f64) -> (f64, f64, f64) {
+    let v = matrix::mul_vec(&matrix::transpose(&rotation(lambda, phi)), [ve, vn, vu]);
+    (v[0], v[1], v[2])
+}
+
+/// Rotate an ECEF velocity, in m/s, to north, east, down at lambda, phi.
+///
+pub fn velocity_ecef2ned(vx:f64, vy:f64, vz:f64, lambda:f64, phi:f64) -> (f64, f64, f64) {
+    let (ve, vn, vu) = velocity_ecef2enu(vx, vy, vz, lambda, phi);
+    (vn, ve, -vu)
+}
+
+/// Rotate a north, east, down velocity, in m/s, at lambda, phi to ECEF.
+///
+pub fn velocity_ned2ecef(vn:f64, ve:f64, vd:f64, lambda:f64, phi:f64) -> (f64, f64, f64) {
+    velocity_enu2ecef(ve, vn, -vd, lambda, phi)
+}
+
+/// Calculate the horizontal ground speed from east and north velocity components.
+///
+pub fn ground_speed(ve:f64, vn:f64) -> f64 {
+    ve.hypot(vn)
+}
+
+/// Calculate the course over ground, in radians clockwise from true north in [0, 2π), from east
+/// and north velocity components.
+///
+pub fn course(ve:f64, vn:f64) -> f64 {
+    ve.atan2(vn).rem_euclid(2.0 * PI)
+}
+
+/// Calculate the Jacobian of ECEF x, y, z with respect to geodetic lambda, phi, h.
+///
+/// Columns are the derivatives by lambda and phi, in meters per radian, and by h.
+///
+pub fn jacobian_lla2ecef(lambda:f64, phi:f64, h:f64, ellps:ellipsoid::Ellipsoid) -> Matrix3 {
+    let (sl, cl) = (lambda.sin(), lambda.cos());
+    let (sp, cp) = (phi.sin(), phi.cos());
+    let (_r, rn, rm) = ellps.radii(phi);
+
+    [[-(rn + h) * cp * sl, -(rm + h) * sp * cl, cp * cl],
+     [(rn + h) * cp * cl, -(rm + h) * sp * sl, cp * sl],
+     [0.0, (rm + h) * cp, sp]]
+}
+
+/// Calculate the Jacobian of geodetic lambda, phi, h with respect to ECEF x, y, z.
+///
+/// This is the exact inverse of jacobian_lla2ecef: each row is a local unit vector scaled by the
+/// radius of curvature in its direction.
+///
+pub fn jacobian_ecef2lla(lambda:f64, phi:f64, h:f64, ellps:ellipsoid::Ellipsoid) -> Matrix3 {
+    let (_r, rn, rm) = ellps.radii(phi);
+    let r = rotation(lambda, phi);
+    let scale = [1.0 / ((rn + h) * phi.cos()), 1.0 / (rm + h), 1.0];
+
+    [[r[0][0] * scale[0], r[0][1] * scale[0], r[0][2] * scale[0]],
+     [r[1][0] * scale[1], r[1][1] * scale[1], r[1][2] * scale[1]],
+     [r[2][0], r[2][1], r[2][2]]]
+}
+
+/// Propagate an ECEF covariance, in m², to the local east, north, up frame at lambda, phi.
+///
+pub fn covariance_ecef2enu(c:&Matrix3, lambda:f64, phi:f64) -> Matrix3 {
+    matrix::congruence(&rotation(lambda, phi), c)
+}
+
+/// Propagate a local east, north, up covariance, in m², at lambda, phi to ECEF.
+///
+pub fn covariance_enu2ecef(c:&Matrix3, lambda:f64, phi:f64) -> Matrix3 {
+    matrix::congruence(&matrix::transpose(&rotation(lambda, phi)), c)
+}
+
+/// Propagate an ECEF covariance, in m², to geodetic lambda, phi, h, in radians² and m².
+///
+pub fn covariance_ecef2lla(c:&Matrix3, lambda:f64, phi:f64, h:f64, ellps:ellipsoid::Ellipsoid) -> Matrix3 {
+    matrix::congruence(&jacobian_ecef2lla(lambda, phi, h, ellps), c)
+}
+
+/// Propagate a geodetic covariance of lambda, phi, h, in radians² and m², to ECEF.
+///
+pub fn covariance_lla2ecef(c:&Matrix3, lambda:f64, phi:f64, h:f64, ellps:ellipsoid::Ellipsoid) -> Matrix3 {
+    matrix::congruence(&jacobian_lla2ecef(lambda, phi, h, ellps), c)
+}
+
+/// Summary of a local east, north, up covariance.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Precision {
+    /// standard deviation east (m)
+    pub sigma_e : f64,
+    /// standard deviation north (m)
+    pub sigma_n : f64,
+    /// standard deviation up (m)
+    pub sigma_u : f64,
+    /// horizontal standard deviation, root sum of squares of east and north (m)
+    pub horizontal : f64,
+    /// semi-major axis of the one sigma horizontal error ellipse (m)
+    pub semi_major : f64,
+    /// semi-minor axis of the one sigma horizontal error ellipse (m)
+    pub semi_minor : f64,
+    /// azimuth of the semi-major axis, clockwise from north (radians)
+    pub azimuth : f64
+}
+
+/// Calculate standard deviations and the horizontal error ellipse from an east, north, up covariance.
+///
+/// The ellipse axes are the square roots of the eigenvalues of the east-north block.
+///
+/// References:
+/// -----------
+/// Mikhail, E. M. (1976). Observations and Least Squares. IEP, ch. 2.
+///
+pub fn precision(c:&Matrix3) -> Precision {
+    let (ee, nn, en) = (c[0][0], c[1][1], c[0][1]);
+    let mean:f64 = 0.5 * (ee + nn);
+    let radius:f64 = (0.25 * (ee - nn).powi(2) + en * en).sqrt();
+
+    Precision {
+        sigma_e: ee.sqrt(),
+        sigma_n: nn.sqrt(),
+        sigma_u: c[2][2].sqrt(),
+        horizontal: (ee + nn).sqrt(),
+        semi_major: (mean + radius).sqrt(),
+        semi_minor: (mean - radius).max(0.0).sqrt(),
+        azimuth: (0.5 * (2.0 * en).atan2(nn - ee)).rem_euclid(PI)
+    }
+}
+
+#[test]
+fn test_jacobians() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let (lambda, phi, h) = (0.3, 0.8, 1234.0);
+    let j = jacobian_lla2ecef(lambda, phi, h, wgs84);
+    let k = jacobian_ecef2lla(lambda, phi, h, wgs84);
+    let product = matrix::mul(&k, &j);
+    for i in 0..3 {
+        for n in 0..3 {
+            assert!((product[i][n] - matrix::IDENTITY[i][n]).abs() < 1.0e-12);
+        }
+    }
+
+    // against central differences of the conversion
+    let d:f64 = 1.0e-7;
+    for (col, step) in [(d, 0.0, 0.0), (0.0, d, 0.0), (0.0, 0.0, 1.0)].iter().enumerate() {
+        let p = convert::lla2ecef(lambda + step.0, phi + step.1, h + step.2, wgs84);
+        let m = convert::lla2ecef(lambda - step.0, phi - step.1, h - step.2, wgs84);
+        let scale:f64 = if col == 2 { 2.0 } else { 2.0 * d };
+        let diff = [(p.0 - m.0) / scale, (p.1 - m.1) / scale, (p.2 - m.2) / scale];
+        for i in 0..3 {
+            assert!((diff[i] - j[i][col]).abs() < 1.0e-3 * j[i][col].abs().max(1.0));
+        }
+    }
+
+    let (e, n, u) = ecef2enu(1.0e6, 2.0e6, 5.0e6, lambda, phi, h, wgs84);
+    let (x, y, z) = enu2ecef(e, n, u, lambda, phi, h, wgs84);
+    assert!((x - 1.0e6).abs() < 1.0e-6 && (y - 2.0e6).abs() < 1.0e-6 && (z - 5.0e6).abs() < 1.0e-6);
+
+    // a point 1 km east and 1 km up is at azimuth 90 and elevation 45 degrees
+    let (x, y, z) = enu2ecef(1000.0, 0.0, 1000.0, lambda, phi, h, wgs84);
+    let (az, el, range) = look_angles(x, y, z, lambda, phi, h, wgs84);
+    assert!((az.to_degrees() - 90.0).abs() < 1.0e-9 && (el.to_degrees() - 45.0).abs() < 1.0e-9);
+    assert!((range - 2.0f64.sqrt() * 1000.0).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_covariance() {
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let (lambda, phi, h) = (-1.3, 0.7, 250.0);
+
+    // an error ellipse of 3 m by 1 m with its major axis 30 degrees east of north, 2 m vertical
+    let az:f64 = 30.0f64.to_radians();
+    let (s, c) = (az.sin(), az.cos());
+    let (a2, b2) = (9.0, 1.0);
+    let enu:Matrix3 = [[a2 * s * s + b2 * c * c, (a2 - b2) * s * c, 0.0],
+                       [(a2 - b2) * s * c, a2 * c * c + b2 * s * s, 0.0],
+                       [0.0, 0.0, 4.0]];
+    let ecef = covariance_enu2ecef(&enu, lambda, phi);
+    let p = precision(&covariance_ecef2enu(&ecef, lambda, phi));
+    assert!((p.semi_major - 3.0).abs() < 1.0e-9 && (p.semi_minor - 1.0).abs() < 1.0e-9);
+    assert!((p.azimuth - az).abs() < 1.0e-9);
+    assert!((p.sigma_u - 2.0).abs() < 1.0e-9);
+    assert!((p.horizontal - 10.0f64.sqrt()).abs() < 1.0e-9);
+
+    // geodetic variances scale by the radii of curvature
+    let lla = covariance_ecef2lla(&ecef, lambda, phi, h, wgs84);
+    let (_r, rn, rm) = wgs84.radii(phi);
+    assert!((lla[0][0].sqrt() * (rn + h) * phi.cos() - p.sigma_e).abs() < 1.0e-9);
+    assert!((lla[1][1].sqrt() * (rm + h) - p.sigma_n).abs() < 1.0e-9);
+    let back = covariance_lla2ecef(&lla, lambda, phi, h, wgs84);
+    for i in 0..3 {
+        for n in 0..3 {
+            assert!((back[i][n] - ecef[i][n]).abs() < 1.0e-9);
+        }
+    }
+
+    // starting from an ECEF position at 150 E, where x is negative, as the covariance command does
+    let (lambda, phi) = (150.0f64.to_radians(), -35.0f64.to_radians());
+    let (x, y, z) = convert::lla2ecef(lambda, phi, h, wgs84);
+    let ecef = covariance_enu2ecef(&enu, lambda, phi);
+    let (lon, lat, _hae) = convert::ecef2lla(x, y, z, wgs84, "olson");
+    let p = precision(&covariance_ecef2enu(&ecef, lon.to_radians(), lat.to_radians()));
+    assert!((p.semi_major - 3.0).abs() < 1.0e-6 && (p.semi_minor - 1.0).abs() < 1.0e-6);
+    assert!((p.azimuth - az).abs() < 1.0e-6 && (p.sigma_u - 2.0).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_velocity() {
+    let (lambda, phi) = (2.1, -0.6);
+    // heading south-west at 10 m/s while climbing at 2 m/s
+    let (ve, vn, vu) = (-6.0, -8.0, 2.0);
+    let (vx, vy, vz) = velocity_enu2ecef(ve, vn, vu, lambda, phi);
+    assert!(((vx * vx + vy * vy + vz * vz).sqrt() - 104.0f64.sqrt()).abs() < 1.0e-12);
+    let (e, n, u) = velocity_ecef2enu(vx, vy, vz, lambda, phi);
+    assert!((e - ve).abs() < 1.0e-12 && (n - vn).abs() < 1.0e-12 && (u - vu).abs() < 1.0e-12);
+    let (n, e, d) = velocity_ecef2ned(vx, vy, vz, lambda, phi);
+    assert!((n - vn).abs() < 1.0e-12 && (e - ve).abs() < 1.0e-12 && (d + vu).abs() < 1.0e-12);
+    let (x, y, z) = velocity_ned2ecef(n, e, d, lambda, phi);
+    assert!((x - vx).abs() < 1.0e-12 && (y - vy).abs() < 1.0e-12 && (z - vz).abs() < 1.0e-12);
+
+    assert!((ground_speed(ve, vn) - 10.0).abs() < 1.0e-12);
+    assert!((course(ve, vn).to_degrees() - 216.86989764584402).abs() < 1.0e-9);
+    assert!((course(1.0, 0.0).to_degrees() - 90.0).abs() < 1.0e-12);
+
+    // at the equator and prime meridian east is +y, north is +z and up is +x
+    let (e, n, u) = velocity_ecef2enu(1.0, 2.0, 3.0, 0.0, 0.0);
+    assert!((e - 2.0).abs() < 1.0e-12 && (n - 3.0).abs() < 1.0e-12 && (u - 1.0).abs() < 1.0e-12);
+
+    // from an ECEF position and velocity at 120 W, where x is negative, as ecef2lla --velocity does
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let (lambda, phi) = (-120.0f64.to_radians(), 40.0f64.to_radians());
+    let (x, y, z) = convert::lla2ecef(lambda, phi, 100.0, wgs84);
+    let (vx, vy, vz) = velocity_enu2ecef(ve, vn, vu, lambda, phi);
+    let (lon, lat, _hae) = convert::ecef2lla(x, y, z, wgs84, "olson");
+    let (e, n, u) = velocity_ecef2enu(vx, vy, vz, lon.to_radians(), lat.to_radians());
+    assert!((e - ve).abs() < 1.0e-6 && (n - vn).abs() < 1.0e-6 && (u - vu).abs() < 1.0e-6);
+    assert!((course(e, n).to_degrees() - 216.86989764584402).abs() < 1.0e-6);
+}
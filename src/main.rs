@@ -4,10 +4,41 @@ extern crate csv;
 extern crate rustc_serialize;
 
 pub mod algorithm;
+pub mod azimuthal;
 pub mod convert;
+pub mod cylindrical;
+pub mod datum;
+pub mod eci;
 pub mod ellipsoid;
+pub mod equalarea;
+pub mod geodesic;
+pub mod geoid;
+pub mod geotiff;
+pub mod gravity;
+pub mod grid;
+pub mod harmonic;
+pub mod helmert;
+pub mod itrf;
+pub mod lcc;
+pub mod local;
+pub mod matrix;
+pub mod molodensky;
+pub mod motion;
+pub mod ntv2;
+pub mod positioning;
+pub mod projection;
+pub mod rinex;
+pub mod sgp4;
+pub mod sp3;
+pub mod spcs;
+pub mod tide;
+pub mod time;
+pub mod webmercator;
 
 use std::path::Path;
+use std::process;
+
+use projection::Projection;
 use rustc_serialize::{Encodable};
 
 /// Constructs a Point.
@@ -22,6 +53,417 @@ pub struct Point {
     z: f64,
 }
 
+/// Constructs a record annotated with the tile containing it.
+///
+#[derive(RustcEncodable)]
+pub struct TileRecord {
+    lat: f64,
+    lon: f64,
+    hae: f64,
+    zoom: u32,
+    x: u32,
+    y: u32,
+    quadkey: String,
+}
+
+/// Constructs a record of an instant in several time scales.
+///
+#[derive(RustcEncodable)]
+pub struct TimeRecord {
+    utc: String,
+    /// modified Julian dates in UTC and TT
+    mjd: f64,
+    tai_utc: f64,
+    tt: f64,
+    /// GPS week, without rollover, and seconds of the week
+    gps_week: i64,
+    gps_seconds: f64,
+    decimal_year: f64,
+}
+
+/// Constructs a record of a geocentric position and velocity.
+///
+#[derive(RustcEncodable)]
+pub struct VelocityRecord {
+    x: f64,
+    y: f64,
+    z: f64,
+    /// velocity (m/s)
+    vx: f64,
+    vy: f64,
+    vz: f64,
+}
+
+/// Constructs a record of a geodetic position with its local velocity and ground track.
+///
+#[derive(RustcEncodable)]
+pub struct TrackRecord {
+    lat: f64,
+    lon: f64,
+    hae: f64,
+    /// velocity east, north and up (m/s)
+    ve: f64,
+    vn: f64,
+    vu: f64,
+    /// ground speed (m/s) and course over ground (degrees clockwise from north)
+    speed: f64,
+    course: f64,
+}
+
+/// Constructs a record of a satellite's geodetic position with its look angles from an observer.
+///
+#[derive(RustcEncodable)]
+pub struct SatelliteRecord {
+    time: String,
+    lat: f64,
+    lon: f64,
+    hae: f64,
+    /// azimuth and elevation (degrees) and slant range (m), if an observer was given
+    azimuth: Option<f64>,
+    elevation: Option<f64>,
+    range: Option<f64>,
+}
+
+/// Constructs a record of a GNSS satellite position from the broadcast ephemeris.
+///
+#[derive(RustcEncodable)]
+pub struct NavRecord {
+    sat: String,
+    time: String,
+    x: f64,
+    y: f64,
+    z: f64,
+    /// satellite clock offset (s)
+    clock: f64,
+    lat: f64,
+    lon: f64,
+    hae: f64,
+}
+
+/// Constructs a record of a satellite position interpolated from precise orbits.
+///
+#[derive(RustcEncodable)]
+pub struct OrbitRecord {
+    sat: String,
+    time: String,
+    x: f64,
+    y: f64,
+    z: f64,
+    /// satellite clock offset (s), if given
+    clock: Option<f64>,
+    lat: f64,
+    lon: f64,
+    hae: f64,
+}
+
+/// Constructs a record of a least-squares position fix.
+///
+#[derive(RustcEncodable)]
+pub struct FixRecord {
+    id: String,
+    lat: f64,
+    lon: f64,
+    hae: f64,
+    x: f64,
+    y: f64,
+    z: f64,
+    /// receiver clock bias (m)
+    clock: f64,
+    /// number of ranges and a posteriori standard deviation of unit weight
+    n: usize,
+    sigma0: f64,
+    gdop: f64,
+    pdop: f64,
+    hdop: f64,
+    vdop: f64,
+}
+
+/// Constructs a record of a station corrected for tides.
+///
+#[derive(RustcEncodable)]
+pub struct TideRecord {
+    x: f64,
+    y: f64,
+    z: f64,
+    lat: f64,
+    lon: f64,
+    hae: f64,
+    /// tidal displacement removed (m)
+    dx: f64,
+    dy: f64,
+    dz: f64,
+}
+
+/// Constructs a record of a satellite pass over an observer.
+///
+#[derive(RustcEncodable)]
+pub struct PassRecord {
+    lat: f64,
+    lon: f64,
+    rise: String,
+    culmination: String,
+    set: String,
+    /// greatest elevation and azimuths at rise and set (degrees)
+    max_elevation: f64,
+    rise_azimuth: f64,
+    set_azimuth: f64,
+}
+
+/// Constructs a record of a geodetic position with its local precision.
+///
+#[derive(RustcEncodable)]
+pub struct SigmaRecord {
+    lat: f64,
+    lon: f64,
+    hae: f64,
+    /// standard deviations east, north and up (m)
+    sigma_e: f64,
+    sigma_n: f64,
+    sigma_u: f64,
+    /// horizontal standard deviation (m)
+    sigma_h: f64,
+    /// one sigma error ellipse axes (m) and azimuth of the major axis (degrees)
+    semi_major: f64,
+    semi_minor: f64,
+    azimuth: f64,
+}
+
+/// Parse a center point given as lat,lon in decimal degrees.
+///
+fn center(value:&str) -> (f64, f64) {
+    let parts:Vec<Result<f64, _>> = value.split(',').map(|v| v.trim().parse::<f64>()).collect();
+    match parts.as_slice() {
+        [Ok(lat), Ok(lon)] => (*lat, *lon),
+        _ => {
+            println!("Center must be given as lat,lon");
+            process::exit(1);
+        }
+    }
+}
+
+/// Parse a comma separated list of numbers.
+///
+fn numbers(value:&str) -> Vec<f64> {
+    value.split(',').map(|v| v.trim().parse::<f64>().unwrap_or_else(|_| {
+        println!("Could not parse {} as a number", v);
+        process::exit(1);
+    })).collect()
+}
+
+/// Parse a single number.
+///
+fn number(value:&str) -> f64 {
+    value.trim().parse::<f64>().unwrap_or_else(|_| {
+        println!("Could not parse {} as a number", value);
+        process::exit(1);
+    })
+}
+
+/// Parse a numeric column of a CSV record, if present and non-empty.
+///
+fn column(row:&[String], i:usize) -> Option<f64> {
+    match row.get(i) {
+        Some(v) if !v.trim().is_empty() => Some(v.trim().parse::<f64>().unwrap_or_else(|_| {
+            println!("Could not parse {} as a number", v);
+            process::exit(1);
+        })),
+        _ => None
+    }
+}
+
+/// Find the index of a named column in the CSV headers.
+///
+fn header(headers:&[String], name:&str) -> Option<usize> {
+    headers.iter().position(|h| h.trim() == name)
+}
+
+/// Find the timestamp of a row in its time column, falling back to the given time when the
+/// column is missing or the cell is empty.
+///
+fn row_time<'a>(row:&'a [String], itime:Option<usize>, fallback:Option<&'a str>) -> Option<&'a str> {
+    itime.and_then(|i| row.get(i)).map(|t| t.trim()).filter(|t| !t.is_empty()).or(fallback)
+}
+
+/// Parse an epoch given as a decimal year or a UTC timestamp, exiting if it is neither.
+///
+fn epoch(text:&str) -> f64 {
+    time::parse_epoch(text).unwrap_or_else(|| {
+        println!("Could not parse {} as a decimal year or UTC time", text);
+        process::exit(1);
+    })
+}
+
+/// Parse an epoch column of a CSV record, if present and non-empty.
+///
+fn epoch_column(row:&[String], i:usize) -> Option<f64> {
+    match row.get(i) {
+        Some(v) if !v.trim().is_empty() => Some(epoch(v)),
+        _ => None
+    }
+}
+
+/// Load the leap second table from --leap-seconds, or the built-in table.
+///
+fn leap_seconds(matches:&clap::ArgMatches) -> time::LeapSeconds {
+    match matches.value_of("LEAP_SECONDS") {
+        Some(path) => time::read_leap_seconds(path).unwrap_or_else(|err| {
+            println!("Could not read leap seconds {}: {}", path, err);
+            process::exit(1);
+        }),
+        None => time::LeapSeconds::builtin()
+    }
+}
+
+/// Warn when a UTC Julian date is past the expiry of the leap second table.
+///
+fn check_expiry(leaps:&time::LeapSeconds, jd:f64) {
+    if leaps.expired(jd) {
+        println!("Warning: {} is past the expiry of the leap second table, update it with --leap-seconds", time::format_utc(jd));
+    }
+}
+
+/// Load the satellite selected by --sat, or the first, from the --tle file.
+///
+fn load_satellite(matches:&clap::ArgMatches) -> sgp4::Satellite {
+    let path = matches.value_of("TLE").unwrap();
+    let tles = sgp4::read(path).unwrap_or_else(|err| {
+        println!("Could not read elements {}: {}", path, err);
+        process::exit(1);
+    });
+    let tle = match matches.value_of("SAT") {
+        Some(id) => tles.iter().find(|t| t.satnum.trim_start_matches('0') == id.trim_start_matches('0') || t.name == id),
+        None => tles.first()
+    };
+    let tle = tle.unwrap_or_else(|| {
+        println!("No satellite {} in {}", matches.value_of("SAT").unwrap_or(""), path);
+        process::exit(1);
+    });
+    sgp4::Satellite::new(tle).unwrap_or_else(|err| {
+        println!("Could not initialize satellite {}: {}", tle.satnum, err);
+        process::exit(1);
+    })
+}
+
+/// Load the Earth orientation table from --eop, if given.
+///
+fn load_eop(matches:&clap::ArgMatches) -> Option<eci::Eop> {
+    matches.value_of("EOP").map(|path| eci::read(path).unwrap_or_else(|err| {
+        println!("Could not read Earth orientation {}: {}", path, err);
+        process::exit(1);
+    }))
+}
+
+/// Parse a UTC timestamp into a Julian date, exiting if it is malformed.
+///
+fn utc(text:&str) -> f64 {
+    time::parse_utc(text).unwrap_or_else(|| {
+        println!("Could not parse {} as a UTC time", text);
+        process::exit(1);
+    })
+}
+
+/// Look up a named ellipsoid, exiting if it is unknown.
+///
+fn named_ellipsoid(name:&str) -> ellipsoid::Ellipsoid {
+    match ellipsoid::named(name) {
+        Some(ellps) => ellps,
+        None => {
+            println!("Unknown ellipsoid {}", name);
+            process::exit(1);
+        }
+    }
+}
+
+/// Load the chain of grid shifts named by the subcommand arguments, exiting if one cannot be read.
+///
+fn grid_chain(matches:&clap::ArgMatches) -> Option<grid::Chain> {
+    matches.values_of("GRID").map(|paths| grid::Chain {steps: paths.map(|path| match grid::load(path) {
+        Ok(step) => step,
+        Err(err) => {
+            println!("Could not read grid {}: {}", path, err);
+            process::exit(1);
+        }
+    }).collect()})
+}
+
+/// Apply a chain of grid shifts to geodetic lla in decimal degrees, exiting if the point is off a grid.
+///
+fn grid_shift(chain:&grid::Chain, lat:f64, lon:f64, hae:f64, inverse:bool) -> (f64, f64, f64) {
+    let (lambda, phi) = (lon.to_radians(), lat.to_radians());
+    let shifted = if inverse { chain.inverse(lambda, phi, hae) } else { chain.forward(lambda, phi, hae) };
+    match shifted {
+        Some((lambda, phi, h)) => (phi.to_degrees(), lambda.to_degrees(), h),
+        None => {
+            println!("({}, {}) is outside the grid", lat, lon);
+            process::exit(1);
+        }
+    }
+}
+
+/// Load the geoid named by the subcommand arguments, exiting if it cannot be read.
+///
+fn load_geoid(matches:&clap::ArgMatches) -> Option<geoid::Geoid> {
+    matches.value_of("GEOID").map(|path| match geoid::read(path, matches.is_present("CUBIC")) {
+        Ok(geoid) => geoid,
+        Err(err) => {
+            println!("Could not read geoid {}: {}", path, err);
+            process::exit(1);
+        }
+    })
+}
+
+/// Find the geoid undulation at lla in decimal degrees, exiting if the point is off the grid.
+///
+fn undulation(geoid:&geoid::Geoid, lat:f64, lon:f64) -> f64 {
+    match geoid.undulation(lon.to_radians(), lat.to_radians()) {
+        Some(n) => n,
+        None => {
+            println!("({}, {}) is outside the geoid grid", lat, lon);
+            process::exit(1);
+        }
+    }
+}
+
+/// Select the projection named by the project subcommand arguments.
+///
+fn projection(matches:&clap::ArgMatches, ellps:ellipsoid::Ellipsoid) -> Box<dyn Projection> {
+    if let Some(code) = matches.value_of("ZONE") {
+        return match spcs::zone(code) {
+            Some(zone) => Box::new(zone.projection(ellps)),
+            None => {
+                println!("Unknown SPCS83 zone {}", code);
+                process::exit(1);
+            }
+        };
+    }
+    let (lat0, lon0) = center(matches.value_of("CENTER").unwrap_or("0,0"));
+    let (lambda0, phi0) = (lon0.to_radians(), lat0.to_radians());
+    let name = matches.value_of("PROJ").unwrap_or("webmercator");
+    let lat_ts = matches.value_of("LAT_TS").map(number).unwrap_or(0.0);
+    if !(lat_ts.abs() < 90.0) {
+        println!("The latitude of true scale must be between -90 and 90 degrees");
+        process::exit(1);
+    }
+    match name {
+        "webmercator" => Box::new(webmercator::WebMercator::new()),
+        "mercator" => Box::new(cylindrical::Mercator::new_1sp(ellps, lambda0, 1.0, 0.0, 0.0)),
+        "equirectangular" => Box::new(cylindrical::Equirectangular::new(ellps, lambda0, lat_ts.to_radians(), 0.0, 0.0)),
+        "miller" => Box::new(cylindrical::Miller::new(ellps, lambda0, 0.0, 0.0)),
+        "robinson" => Box::new(cylindrical::Robinson::new(ellps, lambda0, 0.0, 0.0)),
+        "mollweide" => Box::new(cylindrical::Mollweide::new(ellps, lambda0, 0.0, 0.0)),
+        "eqearth" => Box::new(cylindrical::EqualEarth::new(ellps, lambda0, 0.0, 0.0)),
+        "laea" => Box::new(equalarea::LambertAzimuthalEqualArea::new(ellps, lambda0, phi0, 0.0, 0.0)),
+        "aeqd" => Box::new(azimuthal::AzimuthalEquidistant::new(ellps, lambda0, phi0, 0.0, 0.0)),
+        "ortho" => Box::new(azimuthal::Orthographic::new(ellps, lambda0, phi0, 0.0, 0.0)),
+        "gnomonic" => Box::new(azimuthal::Gnomonic::new(ellps, lambda0, phi0, 0.0, 0.0)),
+        "stereographic" => Box::new(azimuthal::ObliqueStereographic::new(ellps, lambda0, phi0, 1.0, 0.0, 0.0)),
+        _ => {
+            println!("Unknown projection {}", name);
+            process::exit(1);
+        }
+    }
+}
+
 /// A commandline utility to convert coordinate triples between LLA and ECEF.
 ///
 fn main() {
@@ -38,12 +480,169 @@ fn main() {
             (about: "Converts geodetic lla to geocentric xyz")
             (version: "0.0.1")
             (author: "Mike <mike@other.com>")
+            (@arg GRID: --grid +takes_value +multiple "Grid shift files applied in turn to the lla before conversion. Formats are .gsb NTv2, .b NADCON5, .gtx, .tif GeoTIFF.")
+            (@arg GRID_INVERSE: --grid-inverse "Applies the grid shifts in reverse, from the target datum back to the source datum")
+            (@arg GEOID: --geoid +takes_value "Geoid grid for input heights above mean sea level. Formats are .pgm, .gtx, .bin GEOID18.")
+            (@arg CUBIC: --cubic "Interpolates the geoid bicubically rather than bilinearly")
+            (@arg VELOCITY: --velocity "Carries ve, vn, vu velocity columns through, rotated to vx, vy, vz")
         )
         (@subcommand ecef2lla =>
             (about: "Converts geocentric xyz to geodetic lla")
             (version: "0.0.1")
             (author: "Mike <mike@other.com>")
             (@arg METHOD: -m --method +takes_value "Specify which algorithm to use. Choices are bowring, olson, ublox.")
+            (@arg GRID: --grid +takes_value +multiple "Grid shift files applied in turn to the lla after conversion. Formats are .gsb NTv2, .b NADCON5, .gtx, .tif GeoTIFF.")
+            (@arg GRID_INVERSE: --grid-inverse "Applies the grid shifts in reverse, from the target datum back to the source datum")
+            (@arg GEOID: --geoid +takes_value "Geoid grid for output heights above mean sea level. Formats are .pgm, .gtx, .bin GEOID18.")
+            (@arg CUBIC: --cubic "Interpolates the geoid bicubically rather than bilinearly")
+            (@arg HARMONICS: --harmonics +takes_value "Spherical harmonic coefficient file, e.g. EGM2008, for the undulation, gravity anomaly and deflection of the vertical")
+            (@arg DEGREE: --degree +takes_value "Maximum degree of the spherical harmonic model, defaults to 360")
+            (@arg CORRECTION: --correction +takes_value requires[HARMONICS] "Height anomaly to geoid correction coefficients, e.g. Zeta-to-N_to2160_egm2008, for the undulation over land")
+            (@arg GEOPOTENTIAL: --geopotential conflicts_with[GEOID] "Outputs geopotential height of the normal field in place of geometric height, with normal gravity")
+            (@arg VELOCITY: --velocity "Carries vx, vy, vz velocity columns through, rotated to ve, vn, vu with ground speed and course")
+        )
+        (@subcommand project =>
+            (about: "Projects geodetic lla to grid coordinates")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg ZONE: -z --zone +takes_value "SPCS83 zone code, e.g. 3702")
+            (@arg PROJ: -p --proj +takes_value "Projection to use when no zone is given. Choices are webmercator, mercator, equirectangular, miller, robinson, mollweide, eqearth, laea, aeqd, ortho, gnomonic, stereographic.")
+            (@arg CENTER: -c --center +takes_value "Center of the projection as lat,lon; world projections use only the lon")
+            (@arg LAT_TS: --lat_ts +takes_value "Latitude of true scale of the equirectangular projection in degrees, defaults to 0")
+            (@arg INVERSE: --inverse "Converts grid coordinates back to geodetic lla")
+            (@arg FACTORS: --factors "Prints the point scale factor and meridian convergence")
+        )
+        (@subcommand helmert =>
+            (about: "Transforms geodetic lla between datums by way of ECEF and a Helmert transformation")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg PARAMS: --params +takes_value "Helmert parameters as tx,ty,tz,rx,ry,rz,ds in m, arc-seconds and ppm")
+            (@arg FROM_DATUM: --from-datum +takes_value "Source datum by name, e.g. NAD27, ED50, OSGB36")
+            (@arg TO_DATUM: --to-datum +takes_value "Target datum by name, defaults to WGS84")
+            (@arg CONVENTION: --convention +takes_value "Rotation convention. Choices are position-vector, coordinate-frame.")
+            (@arg TO_ELLIPSOID: --to-ellipsoid +takes_value "Ellipsoid of the target datum")
+            (@arg INVERSE: --inverse "Applies the transformation from the target datum to the source datum")
+            (@arg METHOD: -m --method +takes_value "Specify which algorithm to use. Choices are bowring, olson, ublox.")
+        )
+        (@subcommand molodensky =>
+            (about: "Shifts geodetic lla between datums by the Molodensky formulas")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg SHIFT: --shift +takes_value +required "Geocentric translation as dx,dy,dz in m")
+            (@arg TO_ELLIPSOID: --to-ellipsoid +takes_value "Ellipsoid of the target datum")
+            (@arg ABRIDGED: --abridged "Uses the abridged Molodensky formulas")
+            (@arg COMPARE: --compare "Prints the difference from the exact geocentric translation")
+            (@arg METHOD: -m --method +takes_value "Specify which algorithm to use for the comparison. Choices are bowring, olson, ublox.")
+        )
+        (@subcommand itrf =>
+            (about: "Transforms geocentric xyz between ITRF and ETRF realisations")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg FROM: --from +takes_value +required "Source frame, e.g. ITRF2014")
+            (@arg TO: --to +takes_value +required "Target frame, e.g. ITRF2020")
+            (@arg EPOCH: --epoch +takes_value "Epoch in decimal years or UTC for rows without an epoch column")
+        )
+        (@subcommand propagate =>
+            (about: "Propagates geocentric xyz between epochs with station or plate velocities")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg FROM_EPOCH: --from-epoch +takes_value "Epoch in decimal years or UTC for rows without an epoch column")
+            (@arg TO_EPOCH: --to-epoch +takes_value +required "Target epoch in decimal years or UTC")
+            (@arg PLATE: --plate +takes_value "ITRF2014 plate for rows without vx, vy, vz columns, e.g. NOAM")
+        )
+        (@subcommand covariance =>
+            (about: "Converts geocentric xyz with sx, sy, sz sigma columns to lla with local precision")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg METHOD: -m --method +takes_value "Specify which algorithm to use. Choices are bowring, olson, ublox.")
+        )
+        (@subcommand eci =>
+            (about: "Converts inertial GCRF or TEME xyz to geocentric xyz at a UTC time")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg FRAME: -f --frame +takes_value "Inertial frame. Choices are gcrf, teme.")
+            (@arg TIME: -t --time +takes_value "UTC time for rows without a time column, e.g. 2004-04-06T07:51:28.386")
+            (@arg EOP: --eop +takes_value "Earth orientation file for polar motion and UT1-UTC, e.g. finals2000A.all")
+            (@arg TAI_UTC: --tai-utc +takes_value "TAI-UTC in seconds, overriding the leap second table")
+            (@arg LEAP_SECONDS: --leap-seconds +takes_value "Leap second table in the leap-seconds.list format")
+            (@arg OFFSETS: --offsets +takes_value "Celestial pole offsets dPsi,dEps of the IAU 1980 nutation in arc-seconds")
+            (@arg INVERSE: --inverse "Converts geocentric xyz to the inertial frame")
+        )
+        (@subcommand time =>
+            (about: "Converts a time column, or gps_week and gps_seconds columns, between UTC, TAI, TT, GPS week and seconds, and decimal year")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg SCALE: -s --scale +takes_value "Time scale of the input timestamps. Choices are utc, tai, tt, gps.")
+            (@arg LEAP_SECONDS: --leap-seconds +takes_value "Leap second table in the leap-seconds.list format")
+        )
+        (@subcommand sgp4 =>
+            (about: "Propagates a two-line element set to geodetic lla at each UTC time of the input")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg TLE: --tle +takes_value +required "Two-line element file")
+            (@arg SAT: --sat +takes_value "Catalogue number or name of the satellite, defaults to the first")
+            (@arg METHOD: -m --method +takes_value "Specify which algorithm to use. Choices are bowring, olson, ublox.")
+            (@arg OBSERVER: --observer +takes_value "Observer as lat,lon,hae for azimuth, elevation and range")
+            (@arg EOP: --eop +takes_value "Earth orientation file for polar motion and UT1-UTC, e.g. finals2000A.all")
+            (@arg LEAP_SECONDS: --leap-seconds +takes_value "Leap second table in the leap-seconds.list format")
+        )
+        (@subcommand passes =>
+            (about: "Predicts passes of a satellite over each observer lla of the input")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg TLE: --tle +takes_value +required "Two-line element file")
+            (@arg SAT: --sat +takes_value "Catalogue number or name of the satellite, defaults to the first")
+            (@arg START: --start +takes_value +required "Start of the window in UTC")
+            (@arg END: --end +takes_value +required "End of the window in UTC")
+            (@arg MASK: --mask +takes_value "Elevation mask in degrees, defaults to 10")
+            (@arg STEP: --step +takes_value "Search step in seconds, defaults to 30")
+            (@arg EOP: --eop +takes_value "Earth orientation file for polar motion and UT1-UTC, e.g. finals2000A.all")
+            (@arg LEAP_SECONDS: --leap-seconds +takes_value "Leap second table in the leap-seconds.list format")
+        )
+        (@subcommand nav =>
+            (about: "Computes GNSS satellite xyz and clock offsets from a RINEX 3 navigation file at each sat and UTC time of the input")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg NAV: --nav +takes_value +required "RINEX 3 navigation file with GPS, Galileo or BeiDou ephemerides")
+            (@arg METHOD: -m --method +takes_value "Specify which algorithm to use. Choices are bowring, olson, ublox.")
+            (@arg RECEIVER: --receiver +takes_value "Receiver as lat,lon,hae; the input time is then the time of reception, with light time and Earth rotation corrected")
+            (@arg LEAP_SECONDS: --leap-seconds +takes_value "Leap second table in the leap-seconds.list format")
+        )
+        (@subcommand sp3 =>
+            (about: "Interpolates precise satellite xyz from an SP3 file at each sat and UTC time of the input")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg SP3: --sp3 +takes_value +required "SP3-c or SP3-d precise orbit file")
+            (@arg WINDOW: --window +takes_value "Number of epochs to interpolate over, defaults to 10")
+            (@arg INTERPOLATION: --interpolation +takes_value "Interpolation. Choices are lagrange, neville.")
+            (@arg METHOD: -m --method +takes_value "Specify which algorithm to use. Choices are bowring, olson, ublox.")
+            (@arg SUBPOINT: --subpoint "Outputs the sub-satellite point on the ellipsoid in place of the satellite")
+            (@arg LEAP_SECONDS: --leap-seconds +takes_value "Leap second table in the leap-seconds.list format")
+        )
+        (@subcommand position =>
+            (about: "Solves receiver positions by least squares from rows of x, y, z, range grouped by an id column")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg METHOD: -m --method +takes_value "Specify which algorithm to use. Choices are bowring, olson, ublox.")
+            (@arg RANGES: --ranges "Treats the ranges as true ranges without a receiver clock bias, for multilateration")
+            (@arg START: --start +takes_value "Starting position as lat,lon,hae, defaults to the Earth's center, or the anchors' centroid with --ranges")
+        )
+        (@subcommand tide =>
+            (about: "Removes the solid earth tide, and optionally the pole tide, from geocentric xyz at a UTC time")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg SYSTEM: -s --system +takes_value "Tide system of the output. Choices are tide-free, mean-tide, zero-tide.")
+            (@arg POLE: --pole "Also removes the pole tide, from the polar motion of --eop")
+            (@arg TIME: -t --time +takes_value "UTC time for rows without a time column, e.g. 2009-04-13T00:00:00")
+            (@arg METHOD: -m --method +takes_value "Specify which algorithm to use. Choices are bowring, olson, ublox.")
+            (@arg EOP: --eop +takes_value "Earth orientation file for polar motion and UT1-UTC, e.g. finals2000A.all")
+            (@arg LEAP_SECONDS: --leap-seconds +takes_value "Leap second table in the leap-seconds.list format")
+        )
+        (@subcommand tile =>
+            (about: "Annotates geodetic lla with slippy-map tile indices")
+            (version: "0.0.1")
+            (author: "Mike <mike@other.com>")
+            (@arg ZOOM: -z --zoom +takes_value +required "Zoom level")
         )
     ).get_matches();
 
@@ -55,37 +654,683 @@ fn main() {
 
     let ellipsoid = matches.value_of("ELLIPSOID").unwrap_or("WGS84");
 
-    let ellps:ellipsoid::Ellipsoid =
-        match ellipsoid {
-            "WGS84" => ellipsoid::Ellipsoid::new(6378137.000, 298.257223563),
-            _ => ellipsoid::Ellipsoid::new(6378137.000, 298.257223563)
-        };
+    let ellps:ellipsoid::Ellipsoid = named_ellipsoid(ellipsoid);
 
     if let Some(matches) = matches.subcommand_matches("ecef2lla") {
-        if matches.is_present("METHOD") {
-            let method = matches.value_of("METHOD").unwrap();
-            println!("Using method...{}", method);
+        let method = match matches.value_of("METHOD") {
+            Some(method) => {
+                println!("Using method...{}", method);
+                method
+            },
+            None => {
+                println!("Using default method...");
+                "olson"
+            }
+        };
+        let chain = grid_chain(matches);
+        let geoid = load_geoid(matches);
+        if (matches.is_present("GEOPOTENTIAL") || matches.is_present("HARMONICS")) && !ellps.has_gravity() {
+            println!("Normal gravity needs a level ellipsoid such as WGS84 or GRS80");
+            process::exit(1);
+        }
+        let degree:usize = matches.value_of("DEGREE").unwrap_or("360").parse().unwrap_or_else(|_| {
+            println!("Degree must be a whole number");
+            process::exit(1);
+        });
+        let coefficients = |path:&str| harmonic::read(path, degree).unwrap_or_else(|err| {
+            println!("Could not read coefficients {}: {}", path, err);
+            process::exit(1);
+        });
+        let harmonics = matches.value_of("HARMONICS").map(&coefficients);
+        let correction = matches.value_of("CORRECTION").map(&coefficients);
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let (x, y, z) = (column(&row, 0).unwrap(), column(&row, 1).unwrap(), column(&row, 2).unwrap());
+            let (lon,lat,hae) = convert::ecef2lla(x,y,z,ellps,method);
+            let velocity = if matches.is_present("VELOCITY") {
+                let (vx, vy, vz) = (column(&row, 3).unwrap_or(0.0), column(&row, 4).unwrap_or(0.0), column(&row, 5).unwrap_or(0.0));
+                Some(local::velocity_ecef2enu(vx, vy, vz, lon.to_radians(), lat.to_radians()))
+            } else {
+                None
+            };
+            if let Some(ref model) = harmonics {
+                let (lambda, phi) = (lon.to_radians(), lat.to_radians());
+                let f = model.functionals(ellps, lambda, phi, hae);
+                let arcsec:f64 = (1.0f64 / 3600.0).to_radians();
+                println!("N {} m anomaly {} mGal xi {} eta {} arc-seconds",
+                    model.undulation(ellps, lambda, phi, correction.as_ref()), f.anomaly * 1.0e5, f.xi / arcsec, f.eta / arcsec);
+            }
+            // grid shifts act on ellipsoidal heights in the source datum, so they come before the
+            // geoid, which is sampled in the target datum
+            let (lat, lon, hae) = match chain {
+                Some(ref chain) => grid_shift(chain, lat, lon, hae, matches.is_present("GRID_INVERSE")),
+                None => (lat, lon, hae)
+            };
+            let hae = match geoid {
+                Some(ref geoid) => hae - undulation(geoid, lat, lon),
+                None => hae
+            };
+            let hae = if matches.is_present("GEOPOTENTIAL") {
+                let phi:f64 = lat.to_radians();
+                println!("normal gravity {} m/s^2", gravity::normal_gravity_at(ellps, phi, hae));
+                gravity::geopotential_height(ellps, phi, hae)
+            } else {
+                hae
+            };
+            println!("({}, {}, {})", lon, lat, hae);
+            if let Some((ve, vn, vu)) = velocity {
+                let (speed, course) = (local::ground_speed(ve, vn), local::course(ve, vn).to_degrees());
+                println!("speed {} m/s course {} vertical rate {} m/s", speed, course, vu);
+                let rec = TrackRecord { lat: lat, lon: lon, hae: hae, ve: ve, vn: vn, vu: vu, speed: speed, course: course };
+                wtr.encode(rec).ok().expect("CSV writer error");
+                continue;
+            }
+            let pt = Point { x: lat, y: lon, z: hae };
+            wtr.encode(pt).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("lla2ecef") {
+        let chain = grid_chain(matches);
+        let geoid = load_geoid(matches);
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let (lat, lon, hae) = (column(&row, 0).unwrap(), column(&row, 1).unwrap(), column(&row, 2).unwrap());
+            // the geoid is sampled at the input position, and the grid shifts then act on the
+            // ellipsoidal height
+            let hae = match geoid {
+                Some(ref geoid) => hae + undulation(geoid, lat, lon),
+                None => hae
+            };
+            let (lat, lon, hae) = match chain {
+                Some(ref chain) => grid_shift(chain, lat, lon, hae, matches.is_present("GRID_INVERSE")),
+                None => (lat, lon, hae)
+            };
+            let lambda:f64 = lon.to_radians();
+            let phi:f64 = lat.to_radians();
+            let (x,y,z) = convert::lla2ecef(lambda,phi,hae,ellps);
+            println!("({}, {}, {})", x, y, z);
+            if matches.is_present("VELOCITY") {
+                let (ve, vn, vu) = (column(&row, 3).unwrap_or(0.0), column(&row, 4).unwrap_or(0.0), column(&row, 5).unwrap_or(0.0));
+                let (vx, vy, vz) = local::velocity_enu2ecef(ve, vn, vu, lambda, phi);
+                let rec = VelocityRecord { x: x, y: y, z: z, vx: vx, vy: vy, vz: vz };
+                wtr.encode(rec).ok().expect("CSV writer error");
+                continue;
+            }
+            let pt = Point { x: x, y: y, z: z };
+            wtr.encode(pt).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("project") {
+        let proj = projection(matches, ellps);
+        if matches.is_present("INVERSE") {
             for record in rdr.decode() {
-                let (x, y, z): (f64, f64, f64) = record.unwrap();
-                let (lon,lat,hae) = convert::ecef2lla(x,y,z,ellps,method);
-                println!("({}, {}, {})", lon, lat, hae);
+                let (x, y, hae): (f64, f64, f64) = record.unwrap();
+                let (lambda, phi) = proj.inverse(x, y);
+                let (lat, lon) = (phi.to_degrees(), lambda.to_degrees());
+                println!("({}, {}, {})", lat, lon, hae);
                 let pt = Point { x: lat, y: lon, z: hae };
                 wtr.encode(pt).ok().expect("CSV writer error");
             }
-
         } else {
-            println!("Using default method...");
+            for record in rdr.decode() {
+                let (lat, lon, hae): (f64, f64, f64) = record.unwrap();
+                let (x, y) = proj.forward(lon.to_radians(), lat.to_radians());
+                println!("({}, {}, {})", x, y, hae);
+                if matches.is_present("FACTORS") {
+                    let factors = proj.factors(lon.to_radians(), lat.to_radians());
+                    println!("k {} h {} convergence {}", factors.k, factors.h, factors.convergence.to_degrees());
+                }
+                let pt = Point { x: x, y: y, z: hae };
+                wtr.encode(pt).ok().expect("CSV writer error");
+            }
         }
     }
-    if let Some(matches) = matches.subcommand_matches("lla2ecef") {
+    if let Some(matches) = matches.subcommand_matches("helmert") {
+        if matches.is_present("FROM_DATUM") || matches.is_present("TO_DATUM") {
+            let named_datum = |name:&str| match datum::named(name) {
+                Some(datum) => datum,
+                None => {
+                    println!("Unknown datum {}, choices are {}", name, datum::NAMES.join(", "));
+                    process::exit(1);
+                }
+            };
+            let from = named_datum(matches.value_of("FROM_DATUM").unwrap_or("WGS84"));
+            let to = named_datum(matches.value_of("TO_DATUM").unwrap_or("WGS84"));
+            let (from, to) = if matches.is_present("INVERSE") { (to, from) } else { (from, to) };
+            let method = matches.value_of("METHOD").unwrap_or("olson");
+            for record in rdr.decode() {
+                let (lat, lon, hae): (f64, f64, f64) = record.unwrap();
+                let (lambda, phi, h) = datum::transform(lon.to_radians(), lat.to_radians(), hae, &from, &to, method);
+                println!("({}, {}, {})", phi.to_degrees(), lambda.to_degrees(), h);
+                let pt = Point { x: phi.to_degrees(), y: lambda.to_degrees(), z: h };
+                wtr.encode(pt).ok().expect("CSV writer error");
+            }
+            return;
+        }
+        let p = match matches.value_of("PARAMS") {
+            Some(params) => numbers(params),
+            None => {
+                println!("Helmert transformation needs --params or a datum name");
+                process::exit(1);
+            }
+        };
+        if p.len() != 7 {
+            println!("Helmert transformation needs seven parameters");
+            process::exit(1);
+        }
+        let convention = matches.value_of("CONVENTION").unwrap_or("position-vector");
+        let convention = match helmert::Convention::named(convention) {
+            Some(convention) => convention,
+            None => {
+                println!("Unknown rotation convention {}", convention);
+                process::exit(1);
+            }
+        };
+        let transform = helmert::Helmert::new(p[0], p[1], p[2], p[3], p[4], p[5], p[6], convention);
+        let to_ellps = named_ellipsoid(matches.value_of("TO_ELLIPSOID").unwrap_or("WGS84"));
+        let method = matches.value_of("METHOD").unwrap_or("olson");
+        let inverse = matches.is_present("INVERSE");
+        let (src, dst) = if inverse { (to_ellps, ellps) } else { (ellps, to_ellps) };
         for record in rdr.decode() {
             let (lat, lon, hae): (f64, f64, f64) = record.unwrap();
-            let lambda:f64 = lon.to_radians();
-            let phi:f64 = lat.to_radians();
-            let (x,y,z) = convert::lla2ecef(lambda,phi,hae,ellps);
+            let (x, y, z) = convert::lla2ecef(lon.to_radians(), lat.to_radians(), hae, src);
+            let (x, y, z) = if inverse { transform.inverse(x, y, z) } else { transform.transform(x, y, z) };
+            let (lon, lat, hae) = convert::ecef2lla(x, y, z, dst, method);
+            println!("({}, {}, {})", lat, lon, hae);
+            let pt = Point { x: lat, y: lon, z: hae };
+            wtr.encode(pt).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("molodensky") {
+        let d = numbers(matches.value_of("SHIFT").unwrap());
+        if d.len() != 3 {
+            println!("Molodensky shift needs three parameters");
+            process::exit(1);
+        }
+        let to_ellps = named_ellipsoid(matches.value_of("TO_ELLIPSOID").unwrap_or("WGS84"));
+        let shift = if matches.is_present("ABRIDGED") { molodensky::abridged_molodensky } else { molodensky::molodensky };
+        let method = matches.value_of("METHOD").unwrap_or("olson");
+        for record in rdr.decode() {
+            let (lat, lon, hae): (f64, f64, f64) = record.unwrap();
+            let (lambda, phi, h) = shift(lon.to_radians(), lat.to_radians(), hae, d[0], d[1], d[2], ellps, to_ellps);
+            println!("({}, {}, {})", phi.to_degrees(), lambda.to_degrees(), h);
+            if matches.is_present("COMPARE") {
+                let (lambda2, phi2, h2) = molodensky::geocentric_translation(lon.to_radians(), lat.to_radians(), hae, d[0], d[1], d[2], ellps, to_ellps, method);
+                let (_r, rn, rm) = to_ellps.radii(phi2);
+                println!("differs from exact by north {} m east {} m up {} m",
+                    (phi - phi2) * rm, (lambda - lambda2) * rn * phi2.cos(), h - h2);
+            }
+            let pt = Point { x: phi.to_degrees(), y: lambda.to_degrees(), z: h };
+            wtr.encode(pt).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("itrf") {
+        let (from, to) = (matches.value_of("FROM").unwrap(), matches.value_of("TO").unwrap());
+        let params = match itrf::find(from, to) {
+            Some(params) => params,
+            None => {
+                println!("No transformation from {} to {}", from, to);
+                process::exit(1);
+            }
+        };
+        let default_epoch = matches.value_of("EPOCH").map(epoch);
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let (x, y, z) = (column(&row, 0).unwrap(), column(&row, 1).unwrap(), column(&row, 2).unwrap());
+            let epoch = match epoch_column(&row, 3).or(default_epoch) {
+                Some(epoch) => epoch,
+                None => {
+                    println!("Row has no epoch and no --epoch was given");
+                    process::exit(1);
+                }
+            };
+            let (x, y, z) = params.transform(x, y, z, epoch);
+            println!("({}, {}, {}) @ {}", x, y, z, epoch);
+            let pt = Point { x: x, y: y, z: z };
+            wtr.encode(pt).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("propagate") {
+        let headers = rdr.headers().unwrap();
+        let (ix, iy, iz) = (header(&headers, "x").unwrap_or(0), header(&headers, "y").unwrap_or(1), header(&headers, "z").unwrap_or(2));
+        let (ivx, ivy, ivz) = (header(&headers, "vx"), header(&headers, "vy"), header(&headers, "vz"));
+        let iepoch = header(&headers, "epoch");
+        let from_epoch = matches.value_of("FROM_EPOCH").map(epoch);
+        let to_epoch = epoch(matches.value_of("TO_EPOCH").unwrap());
+        let pole = matches.value_of("PLATE").map(|name| match motion::plate(name) {
+            Some(pole) => pole,
+            None => {
+                println!("Unknown plate {}", name);
+                process::exit(1);
+            }
+        });
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let (x, y, z) = (column(&row, ix).unwrap(), column(&row, iy).unwrap(), column(&row, iz).unwrap());
+            let from = match iepoch.and_then(|i| epoch_column(&row, i)).or(from_epoch) {
+                Some(epoch) => epoch,
+                None => {
+                    println!("Row has no epoch and no --from-epoch was given");
+                    process::exit(1);
+                }
+            };
+            let velocity = match (ivx.and_then(|i| column(&row, i)), ivy.and_then(|i| column(&row, i)), ivz.and_then(|i| column(&row, i))) {
+                (Some(vx), Some(vy), Some(vz)) => (vx, vy, vz),
+                _ => match pole {
+                    Some(pole) => pole.velocity(x, y, z),
+                    None => {
+                        println!("Row has no vx, vy, vz and no --plate was given");
+                        process::exit(1);
+                    }
+                }
+            };
+            let (x, y, z) = motion::propagate(x, y, z, velocity.0, velocity.1, velocity.2, from, to_epoch);
+            println!("({}, {}, {}) @ {}", x, y, z, to_epoch);
+            let pt = Point { x: x, y: y, z: z };
+            wtr.encode(pt).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("eci") {
+        let frame = matches.value_of("FRAME").unwrap_or("gcrf");
+        if frame != "gcrf" && frame != "teme" {
+            println!("Unknown inertial frame {}", frame);
+            process::exit(1);
+        }
+        let tai_utc = matches.value_of("TAI_UTC").map(number);
+        if let Some(tai_utc) = tai_utc {
+            if !(tai_utc >= 10.0 && tai_utc < 100.0) || tai_utc.fract() != 0.0 {
+                println!("TAI-UTC must be a whole number of seconds from 10, got {}", tai_utc);
+                process::exit(1);
+            }
+        }
+        let offsets = matches.value_of("OFFSETS").map(numbers);
+        if offsets.as_ref().map_or(false, |v| v.len() != 2) {
+            println!("Celestial pole offsets must be given as dPsi,dEps");
+            process::exit(1);
+        }
+        let leaps = leap_seconds(matches);
+        let eop = load_eop(matches);
+        let headers = rdr.headers().unwrap();
+        let (ix, iy, iz) = (header(&headers, "x").unwrap_or(0), header(&headers, "y").unwrap_or(1), header(&headers, "z").unwrap_or(2));
+        let itime = header(&headers, "time");
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let (x, y, z) = (column(&row, ix).unwrap(), column(&row, iy).unwrap(), column(&row, iz).unwrap());
+            let text = row_time(&row, itime, matches.value_of("TIME"));
+            let jd = match text.and_then(time::parse_utc) {
+                Some(jd) => jd,
+                None => {
+                    println!("Row has no valid time and no --time was given");
+                    process::exit(1);
+                }
+            };
+            if tai_utc.is_none() {
+                check_expiry(&leaps, jd);
+            }
+            let tai_utc = tai_utc.unwrap_or_else(|| leaps.tai_utc(jd));
+            let epoch = match eop {
+                Some(ref eop) => eop.epoch(jd, tai_utc).unwrap_or_else(|| {
+                    println!("Time {} is outside the Earth orientation file", text.unwrap());
+                    process::exit(1);
+                }),
+                None => eci::Epoch::new(jd, tai_utc, 0.0, 0.0, 0.0)
+            };
+            let epoch = match offsets {
+                Some(ref v) => epoch.with_offsets(v[0], v[1]),
+                None => epoch
+            };
+            let (x, y, z) = match (frame, matches.is_present("INVERSE")) {
+                ("teme", false) => eci::teme2ecef(x, y, z, &epoch),
+                ("teme", true) => eci::ecef2teme(x, y, z, &epoch),
+                (_, false) => eci::gcrf2ecef(x, y, z, &epoch),
+                (_, true) => eci::ecef2gcrf(x, y, z, &epoch)
+            };
             println!("({}, {}, {})", x, y, z);
             let pt = Point { x: x, y: y, z: z };
             wtr.encode(pt).ok().expect("CSV writer error");
         }
     }
+    if let Some(matches) = matches.subcommand_matches("covariance") {
+        let method = matches.value_of("METHOD").unwrap_or("olson");
+        let headers = rdr.headers().unwrap();
+        let (ix, iy, iz) = (header(&headers, "x").unwrap_or(0), header(&headers, "y").unwrap_or(1), header(&headers, "z").unwrap_or(2));
+        let sigmas = match (header(&headers, "sx"), header(&headers, "sy"), header(&headers, "sz")) {
+            (Some(sx), Some(sy), Some(sz)) => [sx, sy, sz],
+            _ => {
+                println!("Input needs sx, sy, sz sigma columns");
+                process::exit(1);
+            }
+        };
+        // optional off-diagonal covariances in m²
+        let (ixy, ixz, iyz) = (header(&headers, "cxy"), header(&headers, "cxz"), header(&headers, "cyz"));
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let (x, y, z) = (column(&row, ix).unwrap(), column(&row, iy).unwrap(), column(&row, iz).unwrap());
+            let s:Vec<f64> = sigmas.iter().map(|&i| column(&row, i).unwrap_or(0.0)).collect();
+            let off = |i:Option<usize>| i.and_then(|i| column(&row, i)).unwrap_or(0.0);
+            let (cxy, cxz, cyz) = (off(ixy), off(ixz), off(iyz));
+            let c:matrix::Matrix3 = [[s[0] * s[0], cxy, cxz],
+                                     [cxy, s[1] * s[1], cyz],
+                                     [cxz, cyz, s[2] * s[2]]];
+            let (lon, lat, hae) = convert::ecef2lla(x, y, z, ellps, method);
+            let p = local::precision(&local::covariance_ecef2enu(&c, lon.to_radians(), lat.to_radians()));
+            println!("({}, {}, {}) e {} n {} u {} ellipse {} x {} @ {}", lat, lon, hae,
+                p.sigma_e, p.sigma_n, p.sigma_u, p.semi_major, p.semi_minor, p.azimuth.to_degrees());
+            let rec = SigmaRecord { lat: lat, lon: lon, hae: hae, sigma_e: p.sigma_e, sigma_n: p.sigma_n,
+                sigma_u: p.sigma_u, sigma_h: p.horizontal, semi_major: p.semi_major, semi_minor: p.semi_minor,
+                azimuth: p.azimuth.to_degrees() };
+            wtr.encode(rec).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("time") {
+        let scale = matches.value_of("SCALE").unwrap_or("utc");
+        let leaps = leap_seconds(matches);
+        let headers = rdr.headers().unwrap();
+        let week_columns = match (header(&headers, "gps_week"), header(&headers, "gps_seconds"), header(&headers, "time")) {
+            (Some(iweek), Some(isow), None) => Some((iweek, isow)),
+            _ => None
+        };
+        let itime = header(&headers, "time").unwrap_or(0);
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let tai = match week_columns {
+                Some((iweek, isow)) => match (column(&row, iweek), column(&row, isow)) {
+                    (Some(week), Some(sow)) if week >= 0.0 && week.fract() == 0.0 && sow >= 0.0 && sow < 604800.0 =>
+                        time::gps2tai(time::gps_date(week as i64, sow)),
+                    _ => {
+                        println!("Could not parse GPS week and seconds of {:?}", row);
+                        process::exit(1);
+                    }
+                },
+                None => {
+                    let text = row.get(itime).map(|t| t.as_str()).unwrap_or("");
+                    let jd = time::parse_utc(text).unwrap_or_else(|| {
+                        println!("Could not parse {} as a timestamp", text);
+                        process::exit(1);
+                    });
+                    match scale {
+                        "utc" => time::utc2tai(jd, &leaps),
+                        "tai" => jd,
+                        "tt" => time::tt2tai(jd),
+                        "gps" => time::gps2tai(jd),
+                        _ => {
+                            println!("Unknown time scale {}", scale);
+                            process::exit(1);
+                        }
+                    }
+                }
+            };
+            let utc = time::tai2utc(tai, &leaps);
+            check_expiry(&leaps, utc);
+            let (week, sow) = time::gps_week(time::tai2gps(tai));
+            let rec = TimeRecord { utc: time::format_utc(utc), mjd: utc - time::MJD0, tai_utc: leaps.tai_utc(utc),
+                tt: time::tai2tt(tai) - time::MJD0, gps_week: week, gps_seconds: sow, decimal_year: time::decimal_year(utc) };
+            println!("{} week {} seconds {} year {}", rec.utc, week, sow, rec.decimal_year);
+            wtr.encode(rec).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("sgp4") {
+        let method = matches.value_of("METHOD").unwrap_or("olson");
+        let sat = load_satellite(matches);
+        let eop = load_eop(matches);
+        let leaps = leap_seconds(matches);
+        let observer = matches.value_of("OBSERVER").map(|v| {
+            let p = numbers(v);
+            if p.len() != 3 {
+                println!("Observer must be given as lat,lon,hae");
+                process::exit(1);
+            }
+            sgp4::Observer { lambda: p[1].to_radians(), phi: p[0].to_radians(), h: p[2], ellps: ellps }
+        });
+        let headers = rdr.headers().unwrap();
+        let itime = header(&headers, "time").unwrap_or(0);
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let jd = utc(row.get(itime).map(|t| t.as_str()).unwrap_or(""));
+            check_expiry(&leaps, jd);
+            let (x, y, z) = sat.ecef(jd, &leaps, eop.as_ref()).unwrap_or_else(|err| {
+                println!("Could not propagate {}: {}", sat.tle.satnum, err);
+                process::exit(1);
+            });
+            let (lon, lat, hae) = convert::ecef2lla(x, y, z, ellps, method);
+            let look = observer.map(|o| local::look_angles(x, y, z, o.lambda, o.phi, o.h, o.ellps));
+            println!("{} ({}, {}, {})", time::format_utc(jd), lat, lon, hae);
+            let rec = SatelliteRecord { time: time::format_utc(jd), lat: lat, lon: lon, hae: hae,
+                azimuth: look.map(|l| l.0.to_degrees()), elevation: look.map(|l| l.1.to_degrees()), range: look.map(|l| l.2) };
+            wtr.encode(rec).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("passes") {
+        let sat = load_satellite(matches);
+        let eop = load_eop(matches);
+        let leaps = leap_seconds(matches);
+        let (start, end) = (utc(matches.value_of("START").unwrap()), utc(matches.value_of("END").unwrap()));
+        let mask:f64 = matches.value_of("MASK").map(number).unwrap_or(10.0);
+        let step:f64 = matches.value_of("STEP").map(number).unwrap_or(30.0);
+        if !(step > 0.0) {
+            println!("The search step must be positive");
+            process::exit(1);
+        }
+        if !(mask >= -90.0 && mask <= 90.0) {
+            println!("The elevation mask must be between -90 and 90 degrees");
+            process::exit(1);
+        }
+        let mask = mask.to_radians();
+        check_expiry(&leaps, end);
+        for record in rdr.decode() {
+            let (lat, lon, hae): (f64, f64, f64) = record.unwrap();
+            let observer = sgp4::Observer { lambda: lon.to_radians(), phi: lat.to_radians(), h: hae, ellps: ellps };
+            let found = sgp4::passes(&sat, &observer, &leaps, eop.as_ref(), start, end, step, mask).unwrap_or_else(|err| {
+                println!("Could not propagate {}: {}", sat.tle.satnum, err);
+                process::exit(1);
+            });
+            for pass in found.iter() {
+                println!("({}, {}) {} to {} max elevation {}", lat, lon, time::format_utc(pass.rise),
+                    time::format_utc(pass.set), pass.max_elevation.to_degrees());
+                let rec = PassRecord { lat: lat, lon: lon, rise: time::format_utc(pass.rise),
+                    culmination: time::format_utc(pass.culmination), set: time::format_utc(pass.set),
+                    max_elevation: pass.max_elevation.to_degrees(), rise_azimuth: pass.rise_azimuth.to_degrees(),
+                    set_azimuth: pass.set_azimuth.to_degrees() };
+                wtr.encode(rec).ok().expect("CSV writer error");
+            }
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("nav") {
+        let method = matches.value_of("METHOD").unwrap_or("olson");
+        let leaps = leap_seconds(matches);
+        let path = matches.value_of("NAV").unwrap();
+        let nav = rinex::read(path).unwrap_or_else(|err| {
+            println!("Could not read navigation file {}: {}", path, err);
+            process::exit(1);
+        });
+        let receiver = matches.value_of("RECEIVER").map(|v| {
+            let p = numbers(v);
+            if p.len() != 3 {
+                println!("Receiver must be given as lat,lon,hae");
+                process::exit(1);
+            }
+            convert::lla2ecef(p[1].to_radians(), p[0].to_radians(), p[2], ellps)
+        });
+        let headers = rdr.headers().unwrap();
+        let isat = header(&headers, "sat").unwrap_or(0);
+        let itime = header(&headers, "time").unwrap_or(1);
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let sat = row.get(isat).map(|t| t.trim().to_string()).unwrap_or_default();
+            let jd = utc(row.get(itime).map(|t| t.as_str()).unwrap_or(""));
+            check_expiry(&leaps, jd);
+            let t = rinex::gps_seconds(jd, &leaps);
+            let eph = nav.ephemeris(&sat, t).unwrap_or_else(|| {
+                println!("No healthy ephemeris for {} at {}", sat, time::format_utc(jd));
+                process::exit(1);
+            });
+            let ((x, y, z), tau) = match receiver {
+                Some((rx, ry, rz)) => eph.received(t, rx, ry, rz),
+                None => (eph.position(t), 0.0)
+            };
+            let clock = eph.clock(t - tau);
+            let (lon, lat, hae) = convert::ecef2lla(x, y, z, ellps, method);
+            println!("{} {} ({}, {}, {}) {}", sat, time::format_utc(jd), x, y, z, clock);
+            let rec = NavRecord { sat: sat.clone(), time: time::format_utc(jd), x: x, y: y, z: z, clock: clock,
+                lat: lat, lon: lon, hae: hae };
+            wtr.encode(rec).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("sp3") {
+        let method = matches.value_of("METHOD").unwrap_or("olson");
+        let interpolation = matches.value_of("INTERPOLATION").unwrap_or("lagrange");
+        let window:usize = matches.value_of("WINDOW").map(|v| v.parse().unwrap_or_else(|_| {
+            println!("Window must be a whole number");
+            process::exit(1);
+        })).unwrap_or(10);
+        let leaps = leap_seconds(matches);
+        let path = matches.value_of("SP3").unwrap();
+        let orbits = sp3::read(path).unwrap_or_else(|err| {
+            println!("Could not read precise orbits {}: {}", path, err);
+            process::exit(1);
+        });
+        let headers = rdr.headers().unwrap();
+        let isat = header(&headers, "sat").unwrap_or(0);
+        let itime = header(&headers, "time").unwrap_or(1);
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let sat = row.get(isat).map(|t| t.trim().to_string()).unwrap_or_default();
+            let jd = utc(row.get(itime).map(|t| t.as_str()).unwrap_or(""));
+            check_expiry(&leaps, jd);
+            let found = orbits.position(&sat, orbits.seconds(jd, &leaps), window, interpolation).unwrap_or_else(|err| {
+                println!("{}", err);
+                process::exit(1);
+            });
+            let (x, y, z, clock) = found.unwrap_or_else(|| {
+                println!("No orbit for {} around {}", sat, time::format_utc(jd));
+                process::exit(1);
+            });
+            let (lon, lat, hae) = convert::ecef2lla(x, y, z, ellps, method);
+            let (x, y, z, hae) = if matches.is_present("SUBPOINT") {
+                let (xs, ys, zs) = convert::lla2ecef(lon.to_radians(), lat.to_radians(), 0.0, ellps);
+                (xs, ys, zs, 0.0)
+            } else {
+                (x, y, z, hae)
+            };
+            println!("{} {} ({}, {}, {})", sat, time::format_utc(jd), lat, lon, hae);
+            let rec = OrbitRecord { sat: sat.clone(), time: time::format_utc(jd), x: x, y: y, z: z, clock: clock,
+                lat: lat, lon: lon, hae: hae };
+            wtr.encode(rec).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("position") {
+        let method = matches.value_of("METHOD").unwrap_or("olson");
+        let clock = !matches.is_present("RANGES");
+        let start = match matches.value_of("START").map(numbers) {
+            Some(ref p) if p.len() == 3 => Some(convert::lla2ecef(p[1].to_radians(), p[0].to_radians(), p[2], ellps)),
+            Some(_) => {
+                println!("Start must be given as lat,lon,hae");
+                process::exit(1);
+            },
+            None => None
+        };
+        let headers = rdr.headers().unwrap();
+        let iid = header(&headers, "id");
+        let columns:Vec<usize> = ["x", "y", "z", "range"].iter().map(|name| header(&headers, name).unwrap_or_else(|| {
+            println!("Input has no {} column", name);
+            process::exit(1);
+        })).collect();
+        let isigma = header(&headers, "sigma");
+
+        // consecutive rows sharing an id form one fix
+        let mut groups:Vec<(String, Vec<positioning::Observation>)> = Vec::new();
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let id = iid.and_then(|i| row.get(i)).map(|v| v.trim().to_string()).unwrap_or_default();
+            let values:Vec<f64> = columns.iter().map(|&i| column(&row, i).unwrap_or_else(|| {
+                println!("Could not parse x, y, z and range of {:?}", row);
+                process::exit(1);
+            })).collect();
+            let sigma = isigma.and_then(|i| column(&row, i)).unwrap_or(1.0);
+            let observation = positioning::Observation { x: values[0], y: values[1], z: values[2], range: values[3], sigma: sigma };
+            if groups.last().map_or(false, |group| group.0 == id) {
+                groups.last_mut().unwrap().1.push(observation);
+            } else {
+                groups.push((id, vec![observation]));
+            }
+        }
+        for (id, observations) in groups.into_iter() {
+            let start = start.unwrap_or_else(|| if clock { (0.0, 0.0, 0.0) } else { positioning::centroid(&observations) });
+            let fix = positioning::solve(&observations, clock, start, ellps, method).unwrap_or_else(|err| {
+                println!("Could not solve {}: {}", id, err);
+                process::exit(1);
+            });
+            let (lon, lat, hae) = convert::ecef2lla(fix.x, fix.y, fix.z, ellps, method);
+            println!("{} ({}, {}, {}) residuals {:?} pdop {}", id, lat, lon, hae, fix.residuals, fix.pdop);
+            let rec = FixRecord { id: id, lat: lat, lon: lon, hae: hae, x: fix.x, y: fix.y, z: fix.z, clock: fix.clock,
+                n: observations.len(), sigma0: fix.sigma0, gdop: fix.gdop, pdop: fix.pdop, hdop: fix.hdop, vdop: fix.vdop };
+            wtr.encode(rec).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("tide") {
+        let system = matches.value_of("SYSTEM").unwrap_or("tide-free");
+        let method = matches.value_of("METHOD").unwrap_or("olson");
+        let leaps = leap_seconds(matches);
+        let eop = load_eop(matches);
+        if matches.is_present("POLE") && eop.is_none() {
+            println!("The pole tide needs polar motion from --eop");
+            process::exit(1);
+        }
+        let headers = rdr.headers().unwrap();
+        let (ix, iy, iz) = (header(&headers, "x").unwrap_or(0), header(&headers, "y").unwrap_or(1), header(&headers, "z").unwrap_or(2));
+        let itime = header(&headers, "time");
+        for record in rdr.records() {
+            let row = record.unwrap();
+            let (x, y, z) = (column(&row, ix).unwrap(), column(&row, iy).unwrap(), column(&row, iz).unwrap());
+            let text = row_time(&row, itime, matches.value_of("TIME"));
+            let jd = match text.and_then(time::parse_utc) {
+                Some(jd) => jd,
+                None => {
+                    println!("Row has no valid time and no --time was given");
+                    process::exit(1);
+                }
+            };
+            check_expiry(&leaps, jd);
+            let epoch = match eop {
+                Some(ref eop) => eop.epoch(jd, leaps.tai_utc(jd)).unwrap_or_else(|| {
+                    println!("Time {} is outside the Earth orientation file", text.unwrap());
+                    process::exit(1);
+                }),
+                None => eci::Epoch::new(jd, leaps.tai_utc(jd), 0.0, 0.0, 0.0)
+            };
+            let (mut dx, mut dy, mut dz) = tide::solid_earth_tide(x, y, z, &epoch, system).unwrap_or_else(|err| {
+                println!("{}", err);
+                process::exit(1);
+            });
+            if matches.is_present("POLE") {
+                let (px, py, pz) = tide::pole_tide(x, y, z, &epoch);
+                dx += px;
+                dy += py;
+                dz += pz;
+            }
+            let (x, y, z) = (x - dx, y - dy, z - dz);
+            let (lon, lat, hae) = convert::ecef2lla(x, y, z, ellps, method);
+            println!("({}, {}, {}) removed ({}, {}, {})", x, y, z, dx, dy, dz);
+            let rec = TideRecord { x: x, y: y, z: z, lat: lat, lon: lon, hae: hae, dx: dx, dy: dy, dz: dz };
+            wtr.encode(rec).ok().expect("CSV writer error");
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("tile") {
+        let zoom:u32 = matches.value_of("ZOOM").unwrap().parse().unwrap_or_else(|_| {
+            println!("Zoom level must be a whole number");
+            process::exit(1);
+        });
+        if zoom > webmercator::MAX_ZOOM {
+            println!("Zoom level must be at most {}", webmercator::MAX_ZOOM);
+            process::exit(1);
+        }
+        for record in rdr.decode() {
+            let (lat, lon, hae): (f64, f64, f64) = record.unwrap();
+            let (x, y) = webmercator::tile(lon.to_radians(), lat.to_radians(), zoom);
+            let quadkey = webmercator::quadkey(x, y, zoom);
+            println!("({}, {}, {}) {}/{}/{} {}", lat, lon, hae, zoom, x, y, quadkey);
+            let rec = TileRecord { lat: lat, lon: lon, hae: hae, zoom: zoom, x: x, y: y, quadkey: quadkey };
+            wtr.encode(rec).ok().expect("CSV writer error");
+        }
+    }
 }
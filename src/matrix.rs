@@ -0,0 +1,151 @@
+/// A 3x3 matrix stored by rows.
+///
+pub type Matrix3 = [[f64; 3]; 3];
+
+/// The 3x3 identity matrix.
+///
+pub const IDENTITY: Matrix3 = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+/// Multiply a matrix by a column vector.
+///
+pub fn mul_vec(m:&Matrix3, v:[f64; 3]) -> [f64; 3] {
+    let mut out = [0.0; 3];
+    for i in 0..3 {
+        out[i] = m[i][0] * v[0] + m[i][1] * v[1] + m[i][2] * v[2];
+    }
+    out
+}
+
+/// Multiply two matrices.
+///
+pub fn mul(a:&Matrix3, b:&Matrix3) -> Matrix3 {
+    let mut out = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            out[i][j] = a[i][0] * b[0][j] + a[i][1] * b[1][j] + a[i][2] * b[2][j];
+        }
+    }
+    out
+}
+
+/// Transpose a matrix.
+///
+pub fn transpose(m:&Matrix3) -> Matrix3 {
+    let mut out = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            out[i][j] = m[j][i];
+        }
+    }
+    out
+}
+
+/// Propagate a covariance matrix through a linear map with Jacobian j, giving J C Jᵀ.
+///
+pub fn congruence(j:&Matrix3, c:&Matrix3) -> Matrix3 {
+    mul(&mul(j, c), &transpose(j))
+}
+
+/// Rotate the coordinate axes by angle a, in radians, about the x axis.
+///
+pub fn rot1(a:f64) -> Matrix3 {
+    let (s, c) = (a.sin(), a.cos());
+    [[1.0, 0.0, 0.0], [0.0, c, s], [0.0, -s, c]]
+}
+
+/// Rotate the coordinate axes by angle a, in radians, about the y axis.
+///
+pub fn rot2(a:f64) -> Matrix3 {
+    let (s, c) = (a.sin(), a.cos());
+    [[c, 0.0, -s], [0.0, 1.0, 0.0], [s, 0.0, c]]
+}
+
+/// Rotate the coordinate axes by angle a, in radians, about the z axis.
+///
+pub fn rot3(a:f64) -> Matrix3 {
+    let (s, c) = (a.sin(), a.cos());
+    [[c, s, 0.0], [-s, c, 0.0], [0.0, 0.0, 1.0]]
+}
+
+/// Calculate the determinant of a matrix.
+///
+pub fn determinant(m:&Matrix3) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+/// Invert a matrix by its adjugate, returning None if it is singular.
+///
+pub fn inverse(m:&Matrix3) -> Option<Matrix3> {
+    let det:f64 = determinant(m);
+    if det.abs() < 1.0e-300 {
+        return None;
+    }
+    let mut out = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            // cofactor of element (j, i)
+            let (r1, r2) = ((j + 1) % 3, (j + 2) % 3);
+            let (c1, c2) = ((i + 1) % 3, (i + 2) % 3);
+            out[i][j] = (m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]) / det;
+        }
+    }
+    Some(out)
+}
+
+/// Invert a square matrix of any size by Gauss-Jordan elimination with partial pivoting,
+/// returning None if it is singular.
+///
+pub fn invert(m:&[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = m.len();
+    let mut a:Vec<Vec<f64>> = m.to_vec();
+    let mut out:Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+    let scale:f64 = m.iter().flat_map(|row| row.iter()).fold(0.0, |s:f64, v| s.max(v.abs()));
+    for k in 0..n {
+        let pivot = (k..n).max_by(|&i, &j| a[i][k].abs().partial_cmp(&a[j][k].abs()).unwrap())?;
+        if a[pivot][k].abs() <= 1.0e-14 * scale {
+            return None;
+        }
+        a.swap(k, pivot);
+        out.swap(k, pivot);
+        let p = a[k][k];
+        for j in 0..n {
+            a[k][j] /= p;
+            out[k][j] /= p;
+        }
+        for i in 0..n {
+            if i != k {
+                let f = a[i][k];
+                for j in 0..n {
+                    a[i][j] -= f * a[k][j];
+                    out[i][j] -= f * out[k][j];
+                }
+            }
+        }
+    }
+    Some(out)
+}
+
+#[test]
+fn test_inverse() {
+    let m:Matrix3 = [[2.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 2.0]];
+    let inv = inverse(&m).unwrap();
+    let product = mul(&m, &inv);
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((product[i][j] - IDENTITY[i][j]).abs() < 1.0e-15);
+        }
+    }
+    assert!(inverse(&[[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 0.0, 1.0]]).is_none());
+
+    let m4:Vec<Vec<f64>> = vec![vec![4.0, 1.0, 0.0, 2.0], vec![1.0, 3.0, 1.0, 0.0], vec![0.0, 1.0, 5.0, 1.0], vec![2.0, 0.0, 1.0, 6.0]];
+    let inv4 = invert(&m4).unwrap();
+    for i in 0..4 {
+        for j in 0..4 {
+            let p:f64 = (0..4).map(|k| m4[i][k] * inv4[k][j]).sum();
+            assert!((p - if i == j { 1.0 } else { 0.0 }).abs() < 1.0e-14);
+        }
+    }
+    assert!(invert(&[vec![1.0, 2.0], vec![2.0, 4.0]]).is_none());
+}
@@ -0,0 +1,108 @@
+use convert;
+use ellipsoid;
+
+/// Shift geodetic coordinates between datums by the standard Molodensky formulas.
+///
+/// Longitude lambda and latitude phi are given and returned in radians, height h in meters.
+/// The translations dx, dy, dz, in meters, take the source datum origin to the target datum
+/// origin.
+///
+/// References:
+/// -----------
+/// IOGP (2019). Geomatics Guidance Note 7-2, Molodensky, EPSG method 9604.
+///
+pub fn molodensky(lambda:f64, phi:f64, h:f64, dx:f64, dy:f64, dz:f64, from:ellipsoid::Ellipsoid, to:ellipsoid::Ellipsoid) -> (f64, f64, f64) {
+    let da:f64 = to.a - from.a;
+    let df:f64 = to.f - from.f;
+    let (sp, cp) = (phi.sin(), phi.cos());
+    let (sl, cl) = (lambda.sin(), lambda.cos());
+    let (_r, rn, rm) = from.radii(phi);
+
+    let dphi:f64 = (-dx * sp * cl - dy * sp * sl + dz * cp
+        + da * rn * from.eccsq * sp * cp / from.a
+        + df * (rm * from.a / from.b + rn * from.b / from.a) * sp * cp) / (rm + h);
+    let dlambda:f64 = (-dx * sl + dy * cl) / ((rn + h) * cp);
+    let dh:f64 = dx * cp * cl + dy * cp * sl + dz * sp - da * from.a / rn + df * from.b / from.a * rn * sp * sp;
+
+    (lambda + dlambda, phi + dphi, h + dh)
+}
+
+/// Shift geodetic coordinates between datums by the abridged Molodensky formulas.
+///
+/// References:
+/// -----------
+/// IOGP (2019). Geomatics Guidance Note 7-2, Abridged Molodensky, EPSG method 9605.
+///
+pub fn abridged_molodensky(lambda:f64, phi:f64, h:f64, dx:f64, dy:f64, dz:f64, from:ellipsoid::Ellipsoid, to:ellipsoid::Ellipsoid) -> (f64, f64, f64) {
+    let da:f64 = to.a - from.a;
+    let df:f64 = to.f - from.f;
+    let (sp, cp) = (phi.sin(), phi.cos());
+    let (sl, cl) = (lambda.sin(), lambda.cos());
+    let (_r, rn, rm) = from.radii(phi);
+    let flattening:f64 = from.a * df + from.f * da;
+
+    let dphi:f64 = (-dx * sp * cl - dy * sp * sl + dz * cp + flattening * (2.0 * phi).sin()) / rm;
+    let dlambda:f64 = (-dx * sl + dy * cl) / (rn * cp);
+    let dh:f64 = dx * cp * cl + dy * cp * sl + dz * sp + flattening * sp * sp - da;
+
+    (lambda + dlambda, phi + dphi, h + dh)
+}
+
+/// Shift geodetic coordinates between datums exactly by way of ECEF and a geocentric translation.
+///
+/// This is the reference the Molodensky formulas approximate. The ECEF to geodetic conversion
+/// uses the named algorithm, as for convert::ecef2lla.
+///
+pub fn geocentric_translation(lambda:f64, phi:f64, h:f64, dx:f64, dy:f64, dz:f64, from:ellipsoid::Ellipsoid, to:ellipsoid::Ellipsoid, algo:&str) -> (f64, f64, f64) {
+    let (x, y, z) = convert::lla2ecef(lambda, phi, h, from);
+    let (lon, lat, hae) = convert::ecef2lla(x + dx, y + dy, z + dz, to, algo);
+
+    (lon.to_radians(), lat.to_radians(), hae)
+}
+
+#[test]
+fn test_molodensky() {
+    // EPSG Guidance Note 7-2 example, WGS 84 to ED50
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let intl1924 = ellipsoid::Ellipsoid::new(6378388.000, 297.0);
+    let lambda:f64 = (2.0 + 7.0/60.0 + 46.38/3600.0f64).to_radians();
+    let phi:f64 = (53.0 + 48.0/60.0 + 33.82/3600.0f64).to_radians();
+    let (dx, dy, dz) = (84.87, 96.49, 116.95);
+
+    let (lambda1, phi1, h1) = molodensky(lambda, phi, 73.0, dx, dy, dz, wgs84, intl1924);
+    let (lambda2, phi2, h2) = abridged_molodensky(lambda, phi, 73.0, dx, dy, dz, wgs84, intl1924);
+    let (lambda3, phi3, h3) = geocentric_translation(lambda, phi, 73.0, dx, dy, dz, wgs84, intl1924, "olson");
+    println!("molodensky {0} {1} {2}", lambda1.to_degrees(), phi1.to_degrees(), h1);
+    println!("abridged {0} {1} {2}", lambda2.to_degrees(), phi2.to_degrees(), h2);
+    println!("exact {0} {1} {2}", lambda3.to_degrees(), phi3.to_degrees(), h3);
+
+    let arcsec:f64 = (1.0f64 / 3600.0).to_radians();
+    assert!((lambda1 - (2.0 + 7.0/60.0 + 51.477/3600.0f64).to_radians()).abs() < 0.001 * arcsec);
+    assert!((phi1 - (53.0 + 48.0/60.0 + 36.565/3600.0f64).to_radians()).abs() < 0.001 * arcsec);
+    assert!((h1 - 28.02).abs() < 0.01);
+
+    // both approximations stay within a decimeter or two of the exact shift
+    for &(l, p, h) in [(lambda1, phi1, h1), (lambda2, phi2, h2)].iter() {
+        assert!((l - lambda3).abs() * wgs84.a < 0.1);
+        assert!((p - phi3).abs() * wgs84.a < 0.1);
+        assert!((h - h3).abs() < 0.2);
+    }
+}
+
+#[test]
+fn test_east() {
+    // Tokyo datum to WGS84 by the EPSG 1231 translation, east of 90 degrees where x is negative
+    let bessel = ellipsoid::Ellipsoid::new(6377397.155, 299.1528128);
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let lambda:f64 = 139.7f64.to_radians();
+    let phi:f64 = 35.7f64.to_radians();
+    let (dx, dy, dz) = (-148.0, 507.0, 685.0);
+
+    let (lambda1, phi1, h1) = molodensky(lambda, phi, 0.0, dx, dy, dz, bessel, wgs84);
+    for algo in ["olson", "bowring"].iter() {
+        let (lambda2, phi2, h2) = geocentric_translation(lambda, phi, 0.0, dx, dy, dz, bessel, wgs84, algo);
+        // the shift is about 10 arc-seconds west and north
+        assert!((lambda2 - lambda).abs() < 20.0 * (1.0f64 / 3600.0).to_radians());
+        assert!((lambda1 - lambda2).abs() * wgs84.a < 0.1 && (phi1 - phi2).abs() * wgs84.a < 0.1 && (h1 - h2).abs() < 0.2);
+    }
+}
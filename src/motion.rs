@@ -0,0 +1,111 @@
+/// Propagate an ECEF position from one epoch to another with a constant station velocity.
+///
+/// Velocities are given in meters per year and epochs in decimal years.
+///
+pub fn propagate(x:f64, y:f64, z:f64, vx:f64, vy:f64, vz:f64, from:f64, to:f64) -> (f64, f64, f64) {
+    let dt:f64 = to - from;
+
+    (x + vx * dt, y + vy * dt, z + vz * dt)
+}
+
+/// Tectonic plate rotation about an Euler pole, expressed as a Cartesian rotation rate vector.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct EulerPole {
+    /// plate abbreviation
+    pub name : &'static str,
+    /// rotation rate about x (mas/yr)
+    pub wx : f64,
+    /// rotation rate about y (mas/yr)
+    pub wy : f64,
+    /// rotation rate about z (mas/yr)
+    pub wz : f64
+}
+
+/// Origin rate bias of the ITRF2014 plate motion model (mm/yr).
+///
+pub const ORIGIN_RATE_BIAS: [f64; 3] = [0.41, 0.22, 0.41];
+
+impl EulerPole {
+
+    /// Calculate the ECEF velocity, in meters per year, of a point riding on the plate.
+    ///
+    /// The origin rate bias of the model is included, so velocities are expressed in ITRF2014.
+    ///
+    pub fn velocity(&self, x:f64, y:f64, z:f64) -> (f64, f64, f64) {
+        let mas:f64 = (1.0e-3f64 / 3600.0).to_radians();
+        let (wx, wy, wz) = (self.wx * mas, self.wy * mas, self.wz * mas);
+        let bias = |i:usize| ORIGIN_RATE_BIAS[i] * 1.0e-3;
+
+        (wy * z - wz * y + bias(0), wz * x - wx * z + bias(1), wx * y - wy * x + bias(2))
+    }
+
+    /// Propagate an ECEF position from one epoch to another with the plate velocity.
+    ///
+    pub fn propagate(&self, x:f64, y:f64, z:f64, from:f64, to:f64) -> (f64, f64, f64) {
+        let (vx, vy, vz) = self.velocity(x, y, z);
+        propagate(x, y, z, vx, vy, vz, from, to)
+    }
+}
+
+macro_rules! pole {
+    ($name:expr, $wx:expr, $wy:expr, $wz:expr) => (
+        EulerPole { name: $name, wx: $wx, wy: $wy, wz: $wz }
+    )
+}
+
+/// Plate rotation rates of the ITRF2014 plate motion model.
+///
+/// References:
+/// -----------
+/// Altamimi, Z., Métivier, L., Rebischung, P., Rouby, H. and Collilieux, X.
+/// (2017). ITRF2014 plate motion model. Geophys J Int 209(3):1906-1912, table 1.
+///
+pub static PLATES: &'static [EulerPole] = &[
+    pole!("ANTA", -0.248, -0.324, 0.675),
+    pole!("ARAB", 1.154, -0.136, 1.444),
+    pole!("AUST", 1.510, 1.182, 1.215),
+    pole!("EURA", -0.085, -0.531, 0.770),
+    pole!("INDI", 1.154, -0.005, 1.454),
+    pole!("NAZC", -0.333, -1.544, 1.623),
+    pole!("NOAM", 0.024, -0.694, -0.063),
+    pole!("NUBI", 0.099, -0.614, 0.733),
+    pole!("PCFC", -0.409, 1.047, -2.169),
+    pole!("SOAM", -0.270, -0.301, -0.140),
+    pole!("SOMA", -0.121, -0.794, 0.884),
+];
+
+/// Look up a plate of the ITRF2014 plate motion model by abbreviation, e.g. NOAM or EURA.
+///
+pub fn plate(name:&str) -> Option<EulerPole> {
+    PLATES.iter().find(|p| p.name == name).map(|p| *p)
+}
+
+#[test]
+fn test_propagate() {
+    let (x, y, z) = propagate(1162172.3972, -4753390.2396, 4077519.5845, -0.0160, -0.0017, 0.0036, 2010.0, 2020.0);
+    assert!((x - 1162172.2372).abs() < 1.0e-6);
+    assert!((y + 4753390.2566).abs() < 1.0e-6);
+    assert!((z - 4077519.6205).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_plate_velocity() {
+    // a station in Pennsylvania moves about 15 mm/yr to the west-southwest on the North American plate
+    let noam = plate("NOAM").unwrap();
+    let (x, y, z) = (1162172.3972, -4753390.2396, 4077519.5845);
+    let (vx, vy, vz) = noam.velocity(x, y, z);
+    let speed:f64 = (vx * vx + vy * vy + vz * vz).sqrt();
+    println!("vx {0} vy {1} vz {2}", vx, vy, vz);
+    assert!(speed > 0.012 && speed < 0.018);
+
+    // the motion is horizontal apart from the origin rate bias
+    let r:f64 = (x * x + y * y + z * z).sqrt();
+    let radial:f64 = (vx * x + vy * y + vz * z) / r;
+    assert!(radial.abs() < 1.0e-3);
+
+    let (x2, y2, z2) = noam.propagate(x, y, z, 2015.0, 2025.0);
+    assert!((x2 - x - 10.0 * vx).abs() < 1.0e-9);
+    assert!((y2 - y - 10.0 * vy).abs() < 1.0e-9);
+    assert!((z2 - z - 10.0 * vz).abs() < 1.0e-9);
+}
@@ -0,0 +1,324 @@
+use std::io;
+use std::path::Path;
+
+use grid::{contents, invalid};
+
+/// One sub-grid of an NTv2 grid shift file.
+///
+/// Bounds and increments are kept as stored in the file, in arc-seconds with longitudes positive
+/// west. Nodes run from the south east corner westward along each row, then northward.
+///
+#[derive(Clone, Debug)]
+pub struct SubGrid {
+    /// sub-grid name
+    pub name : String,
+    /// parent sub-grid name, NONE for a top level grid
+    pub parent : String,
+    /// southern latitude (arc-seconds)
+    pub s_lat : f64,
+    /// northern latitude (arc-seconds)
+    pub n_lat : f64,
+    /// eastern longitude, positive west (arc-seconds)
+    pub e_long : f64,
+    /// western longitude, positive west (arc-seconds)
+    pub w_long : f64,
+    /// latitude increment (arc-seconds)
+    pub lat_inc : f64,
+    /// longitude increment (arc-seconds)
+    pub long_inc : f64,
+    /// number of rows
+    pub rows : usize,
+    /// number of columns
+    pub cols : usize,
+    /// latitude shift of each node (arc-seconds)
+    pub lat_shift : Vec<f32>,
+    /// longitude shift of each node, positive west (arc-seconds)
+    pub long_shift : Vec<f32>
+}
+
+impl SubGrid {
+
+    /// Test whether a point, in arc-seconds with longitude positive west, lies within the sub-grid.
+    ///
+    pub fn contains(&self, lat:f64, long:f64) -> bool {
+        lat >= self.s_lat && lat <= self.n_lat && long >= self.e_long && long <= self.w_long
+    }
+
+    /// Interpolate the latitude and longitude shifts bilinearly between the four surrounding nodes.
+    ///
+    /// The point is in arc-seconds with longitude positive west, as are the returned shifts.
+    ///
+    pub fn interpolate(&self, lat:f64, long:f64) -> (f64, f64) {
+        let x:f64 = (long - self.e_long) / self.long_inc;
+        let y:f64 = (lat - self.s_lat) / self.lat_inc;
+        let i:usize = (x.floor().max(0.0) as usize).min(self.cols - 2);
+        let j:usize = (y.floor().max(0.0) as usize).min(self.rows - 2);
+        let (dx, dy) = (x - i as f64, y - j as f64);
+
+        let node = |v:&[f32], i:usize, j:usize| v[j * self.cols + i] as f64;
+        let bilinear = |v:&[f32]| {
+            node(v, i, j) * (1.0 - dx) * (1.0 - dy) + node(v, i + 1, j) * dx * (1.0 - dy)
+                + node(v, i, j + 1) * (1.0 - dx) * dy + node(v, i + 1, j + 1) * dx * dy
+        };
+
+        (bilinear(&self.lat_shift), bilinear(&self.long_shift))
+    }
+}
+
+/// NTv2 grid shift file: a hierarchy of sub-grids between a source and target datum.
+///
+/// References:
+/// -----------
+/// Junkins, D. R. and Farley, S. A. (1995). NTv2 Developer's Guide. Geodetic
+/// Survey Division, Natural Resources Canada.
+///
+#[derive(Clone, Debug)]
+pub struct Grid {
+    /// source datum name
+    pub system_from : String,
+    /// target datum name
+    pub system_to : String,
+    /// sub-grids in file order
+    pub subgrids : Vec<SubGrid>
+}
+
+/// Fixed size record reader over the bytes of an NTv2 file, in either byte order.
+///
+struct Records<'a> {
+    bytes : &'a [u8],
+    offset : usize,
+    big_endian : bool
+}
+
+impl<'a> Records<'a> {
+
+    fn take(&mut self, n:usize) -> io::Result<&'a [u8]> {
+        if self.offset + n > self.bytes.len() {
+            return Err(invalid("NTv2 file is truncated"));
+        }
+        let value = &self.bytes[self.offset..self.offset + n];
+        self.offset += n;
+        Ok(value)
+    }
+
+    fn uint(&self, b:&[u8]) -> u64 {
+        let fold = |v:u64, b:&u8| (v << 8) | *b as u64;
+        if self.big_endian { b.iter().fold(0, fold) } else { b.iter().rev().fold(0, fold) }
+    }
+
+    /// Read a 16 byte record and check its key.
+    ///
+    fn record(&mut self, key:&str) -> io::Result<&'a [u8]> {
+        let name = String::from_utf8_lossy(self.take(8)?).trim().to_string();
+        if name != key {
+            return Err(invalid(&format!("expected NTv2 record {} but found {}", key, name)));
+        }
+        self.take(8)
+    }
+
+    fn int(&mut self, key:&str) -> io::Result<usize> {
+        let value = self.record(key)?;
+        Ok(self.uint(&value[0..4]) as usize)
+    }
+
+    fn double(&mut self, key:&str) -> io::Result<f64> {
+        let value = self.record(key)?;
+        Ok(f64::from_bits(self.uint(value)))
+    }
+
+    fn text(&mut self, key:&str) -> io::Result<String> {
+        let value = self.record(key)?;
+        Ok(String::from_utf8_lossy(value).trim().to_string())
+    }
+
+    fn float(&mut self) -> io::Result<f32> {
+        let value = self.take(4)?;
+        Ok(f32::from_bits(self.uint(value) as u32))
+    }
+}
+
+/// Parse the contents of an NTv2 grid shift file.
+///
+pub fn parse(bytes:&[u8]) -> io::Result<Grid> {
+    if bytes.len() < 16 {
+        return Err(invalid("NTv2 file is truncated"));
+    }
+    // the overview header always has 11 records, which fixes the byte order
+    let big_endian = bytes[8] == 0 && bytes[11] == 11;
+    let mut rec = Records {bytes: bytes, offset: 0, big_endian: big_endian};
+
+    let num_orec = rec.int("NUM_OREC")?;
+    let num_srec = rec.int("NUM_SREC")?;
+    let num_file = rec.int("NUM_FILE")?;
+    let gs_type = rec.text("GS_TYPE")?;
+    if num_orec != 11 || num_srec != 11 || gs_type != "SECONDS" {
+        return Err(invalid("unsupported NTv2 header"));
+    }
+    rec.text("VERSION")?;
+    let system_from = rec.text("SYSTEM_F")?;
+    let system_to = rec.text("SYSTEM_T")?;
+    rec.double("MAJOR_F")?;
+    rec.double("MINOR_F")?;
+    rec.double("MAJOR_T")?;
+    rec.double("MINOR_T")?;
+
+    let mut subgrids = Vec::with_capacity(num_file);
+    for _ in 0..num_file {
+        let name = rec.text("SUB_NAME")?;
+        let parent = rec.text("PARENT")?;
+        rec.text("CREATED")?;
+        rec.text("UPDATED")?;
+        let s_lat = rec.double("S_LAT")?;
+        let n_lat = rec.double("N_LAT")?;
+        let e_long = rec.double("E_LONG")?;
+        let w_long = rec.double("W_LONG")?;
+        let lat_inc = rec.double("LAT_INC")?;
+        let long_inc = rec.double("LONG_INC")?;
+        let count = rec.int("GS_COUNT")?;
+
+        let rows:usize = ((n_lat - s_lat) / lat_inc).round() as usize + 1;
+        let cols:usize = ((w_long - e_long) / long_inc).round() as usize + 1;
+        if rows < 2 || cols < 2 || rows * cols != count {
+            return Err(invalid(&format!("NTv2 sub-grid {} has inconsistent size", name)));
+        }
+        let mut lat_shift = Vec::with_capacity(count);
+        let mut long_shift = Vec::with_capacity(count);
+        for _ in 0..count {
+            lat_shift.push(rec.float()?);
+            long_shift.push(rec.float()?);
+            // accuracies are not used
+            rec.float()?;
+            rec.float()?;
+        }
+        subgrids.push(SubGrid {name: name, parent: parent, s_lat: s_lat, n_lat: n_lat, e_long: e_long,
+            w_long: w_long, lat_inc: lat_inc, long_inc: long_inc, rows: rows, cols: cols,
+            lat_shift: lat_shift, long_shift: long_shift});
+    }
+
+    Ok(Grid {system_from: system_from, system_to: system_to, subgrids: subgrids})
+}
+
+/// Read an NTv2 grid shift file, e.g. ntv2_0.gsb or BETA2007.gsb.
+///
+pub fn read<P: AsRef<Path>>(path:P) -> io::Result<Grid> {
+    parse(&contents(path)?)
+}
+
+impl Grid {
+
+    /// Find the most densely nested sub-grid containing a point, in arc-seconds with longitude
+    /// positive west.
+    ///
+    pub fn subgrid(&self, lat:f64, long:f64) -> Option<&SubGrid> {
+        let mut found = self.subgrids.iter()
+            .find(|g| g.parent.eq_ignore_ascii_case("NONE") && g.contains(lat, long))?;
+        while let Some(child) = self.subgrids.iter().find(|g| g.parent == found.name && g.contains(lat, long)) {
+            found = child;
+        }
+        Some(found)
+    }
+}
+
+/// Build a little endian NTv2 file in memory with shifts given by a function of the node position.
+///
+#[cfg(test)]
+pub fn synthetic(grids:&[(&str, &str, f64, f64, f64, f64, f64)], f:&dyn Fn(f64, f64) -> (f32, f32)) -> Vec<u8> {
+    let mut bytes:Vec<u8> = Vec::new();
+    let key = |bytes:&mut Vec<u8>, k:&str| bytes.extend(format!("{:<8}", k).as_bytes());
+    let int = |bytes:&mut Vec<u8>, k:&str, v:u32| {
+        key(bytes, k);
+        bytes.extend(&[v as u8, (v >> 8) as u8, (v >> 16) as u8, (v >> 24) as u8, 0, 0, 0, 0]);
+    };
+    let text = |bytes:&mut Vec<u8>, k:&str, v:&str| {
+        key(bytes, k);
+        bytes.extend(format!("{:<8}", v).as_bytes());
+    };
+    let double = |bytes:&mut Vec<u8>, k:&str, v:f64| {
+        key(bytes, k);
+        let b = v.to_bits();
+        bytes.extend((0..8).map(|i| (b >> (8 * i)) as u8));
+    };
+    let float = |bytes:&mut Vec<u8>, v:f32| {
+        let b = v.to_bits();
+        bytes.extend((0..4).map(|i| (b >> (8 * i)) as u8));
+    };
+
+    int(&mut bytes, "NUM_OREC", 11);
+    int(&mut bytes, "NUM_SREC", 11);
+    int(&mut bytes, "NUM_FILE", grids.len() as u32);
+    text(&mut bytes, "GS_TYPE", "SECONDS");
+    text(&mut bytes, "VERSION", "NTv2.0");
+    text(&mut bytes, "SYSTEM_F", "NAD27");
+    text(&mut bytes, "SYSTEM_T", "NAD83");
+    for k in ["MAJOR_F", "MINOR_F", "MAJOR_T", "MINOR_T"].iter() {
+        double(&mut bytes, k, 6378137.0);
+    }
+    for &(name, parent, s_lat, n_lat, e_long, w_long, inc) in grids.iter() {
+        text(&mut bytes, "SUB_NAME", name);
+        text(&mut bytes, "PARENT", parent);
+        text(&mut bytes, "CREATED", "");
+        text(&mut bytes, "UPDATED", "");
+        double(&mut bytes, "S_LAT", s_lat);
+        double(&mut bytes, "N_LAT", n_lat);
+        double(&mut bytes, "E_LONG", e_long);
+        double(&mut bytes, "W_LONG", w_long);
+        double(&mut bytes, "LAT_INC", inc);
+        double(&mut bytes, "LONG_INC", inc);
+        let rows:usize = ((n_lat - s_lat) / inc).round() as usize + 1;
+        let cols:usize = ((w_long - e_long) / inc).round() as usize + 1;
+        int(&mut bytes, "GS_COUNT", (rows * cols) as u32);
+        for j in 0..rows {
+            for i in 0..cols {
+                let (dlat, dlong) = f(s_lat + j as f64 * inc, e_long + i as f64 * inc);
+                float(&mut bytes, dlat);
+                float(&mut bytes, dlong);
+                float(&mut bytes, 0.0);
+                float(&mut bytes, 0.0);
+            }
+        }
+    }
+    key(&mut bytes, "END");
+    bytes.extend(&[0u8; 8]);
+    bytes
+}
+
+#[test]
+fn test_ntv2_bilinear() {
+    use grid::GridShift;
+    // a one degree grid at 45N 75W with shifts linear in position, which interpolate exactly
+    let linear = |lat:f64, long:f64| ((0.1 + (lat - 162000.0) * 1.0e-4) as f32, (-2.0 + (long - 270000.0) * 2.0e-4) as f32);
+    let bytes = synthetic(&[("ALL", "NONE", 162000.0, 165600.0, 270000.0, 273600.0, 900.0)], &linear);
+    let grid = parse(&bytes).unwrap();
+    assert_eq!(grid.system_from, "NAD27");
+    assert_eq!(grid.subgrids[0].rows, 5);
+
+    let arcsec:f64 = (1.0f64 / 3600.0).to_radians();
+    let lambda:f64 = -(270000.0 + 1234.5) * arcsec;
+    let phi:f64 = (162000.0 + 2345.6) * arcsec;
+    let (lambda2, phi2, _h2) = grid.forward(lambda, phi, 0.0).unwrap();
+    let (dlat, dlong) = linear(162000.0 + 2345.6, 270000.0 + 1234.5);
+    assert!(((phi2 - phi) / arcsec - dlat as f64).abs() < 1.0e-5);
+    assert!(((lambda - lambda2) / arcsec - dlong as f64).abs() < 1.0e-5);
+
+    let (lambda3, phi3, _h3) = grid.inverse(lambda2, phi2, 0.0).unwrap();
+    assert!((lambda3 - lambda).abs() < 1.0e-12 && (phi3 - phi).abs() < 1.0e-12);
+
+    assert!(grid.forward(0.0, phi, 0.0).is_none());
+}
+
+#[test]
+fn test_ntv2_subgrid() {
+    use grid::GridShift;
+    // a dense child grid overrides its parent where they overlap
+    let bytes = synthetic(&[("PARENT", "NONE", 0.0, 3600.0, 0.0, 3600.0, 900.0),
+                            ("CHILD", "PARENT", 900.0, 1800.0, 900.0, 1800.0, 300.0)],
+                          &|lat, _long| if lat > 0.0 && lat < 3600.0 && lat % 900.0 != 0.0 { (2.0, 0.0) } else { (1.0, 0.0) });
+    let grid = parse(&bytes).unwrap();
+    assert_eq!(grid.subgrid(1000.0, 1000.0).unwrap().name, "CHILD");
+    assert_eq!(grid.subgrid(2000.0, 1000.0).unwrap().name, "PARENT");
+    assert!(grid.subgrid(4000.0, 1000.0).is_none());
+
+    let arcsec:f64 = (1.0f64 / 3600.0).to_radians();
+    let (_dl, dphi, _dh) = grid.shift(-1000.0 * arcsec, 1200.0 * arcsec).unwrap();
+    assert!((dphi / arcsec - 2.0).abs() < 1.0e-9);
+}
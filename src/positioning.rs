@@ -0,0 +1,261 @@
+use std::io;
+
+use convert;
+use ellipsoid;
+use grid::invalid;
+use local;
+use matrix;
+
+/// A measured range, or pseudorange, from a known satellite or anchor position.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Observation {
+    /// ECEF position of the satellite at transmission, in the frame at reception (m)
+    pub x : f64,
+    pub y : f64,
+    pub z : f64,
+    /// measured range (m)
+    pub range : f64,
+    /// standard deviation of the range (m)
+    pub sigma : f64
+}
+
+/// Least-squares solution of a receiver position.
+///
+pub struct Solution {
+    /// ECEF position (m)
+    pub x : f64,
+    pub y : f64,
+    pub z : f64,
+    /// receiver clock bias as a range (m), zero without a clock
+    pub clock : f64,
+    /// measured minus computed range of each observation (m)
+    pub residuals : Vec<f64>,
+    /// a posteriori standard deviation of unit weight
+    pub sigma0 : f64,
+    /// covariance of x, y, z and the clock bias (m²)
+    pub covariance : Vec<Vec<f64>>,
+    /// dilution of precision: geometric, position, horizontal, vertical and time
+    pub gdop : f64,
+    pub pdop : f64,
+    pub hdop : f64,
+    pub vdop : f64,
+    pub tdop : f64,
+    /// number of iterations taken
+    pub iterations : usize
+}
+
+/// Solve the normal equations of design matrix a, weights w and misclosures l, returning the
+/// correction and the inverse of the normal matrix.
+///
+fn normal(a:&[Vec<f64>], w:&[f64], l:&[f64]) -> Option<(Vec<f64>, Vec<Vec<f64>>)> {
+    let m = a[0].len();
+    let mut n = vec![vec![0.0; m]; m];
+    let mut u = vec![0.0; m];
+    for k in 0..a.len() {
+        for i in 0..m {
+            u[i] += a[k][i] * w[k] * l[k];
+            for j in 0..m {
+                n[i][j] += a[k][i] * w[k] * a[k][j];
+            }
+        }
+    }
+    let q = matrix::invert(&n)?;
+    let dx = (0..m).map(|i| (0..m).map(|j| q[i][j] * u[j]).sum()).collect();
+    Some((dx, q))
+}
+
+/// Calculate the centroid of the satellite or anchor positions, a starting position for
+/// multilateration.
+///
+pub fn centroid(observations:&[Observation]) -> (f64, f64, f64) {
+    let n = observations.len().max(1) as f64;
+    observations.iter().fold((0.0, 0.0, 0.0), |c, o| (c.0 + o.x / n, c.1 + o.y / n, c.2 + o.z / n))
+}
+
+/// Solve a receiver position from ranges by iterated least squares.
+///
+/// With clock set the ranges are pseudoranges sharing a receiver clock bias, which is solved for
+/// as a fourth unknown, as in GNSS point positioning. Without it they are true ranges, as in
+/// multilateration from anchors. The iteration starts from the given ECEF position, which may
+/// be the Earth's center for satellites but should be near the receiver for local anchors.
+///
+/// The DOPs are from the unweighted geometry, rotated to east, north and up at the geodetic
+/// position of the solution, found by the given ecef2lla method.
+///
+/// References:
+/// -----------
+/// Misra, P. & Enge, P. (2011). Global Positioning System: Signals, Measurements, and
+/// Performance, 2nd ed., sec. 6.1.
+///
+pub fn solve(observations:&[Observation], clock:bool, start:(f64, f64, f64), ellps:ellipsoid::Ellipsoid, method:&str) -> io::Result<Solution> {
+    let m:usize = if clock { 4 } else { 3 };
+    if observations.len() < m {
+        return Err(invalid(&format!("{} ranges cannot fix {} unknowns", observations.len(), m)));
+    }
+    let w:Vec<f64> = observations.iter().map(|o| if o.sigma > 0.0 { 1.0 / (o.sigma * o.sigma) } else { 1.0 }).collect();
+    let mut p = [start.0, start.1, start.2, 0.0];
+
+    let mut iterations:usize = 0;
+    loop {
+        iterations += 1;
+        let mut a:Vec<Vec<f64>> = Vec::new();
+        let mut l:Vec<f64> = Vec::new();
+        for o in observations.iter() {
+            let (dx, dy, dz) = (p[0] - o.x, p[1] - o.y, p[2] - o.z);
+            let rho = (dx * dx + dy * dy + dz * dz).sqrt();
+            let mut row = vec![dx / rho, dy / rho, dz / rho];
+            if clock {
+                row.push(1.0);
+            }
+            a.push(row);
+            l.push(o.range - rho - p[3]);
+        }
+        let (dx, q) = normal(&a, &w, &l).ok_or_else(|| invalid("range geometry is singular"))?;
+        for i in 0..m {
+            p[i] += dx[i];
+        }
+        let step = (dx[0] * dx[0] + dx[1] * dx[1] + dx[2] * dx[2]).sqrt();
+        if step > 1.0e-4 && iterations < 20 {
+            continue;
+        }
+        if step > 1.0e-4 {
+            return Err(invalid("least squares did not converge"));
+        }
+
+        // residuals at the solution
+        let residuals:Vec<f64> = observations.iter().map(|o| {
+            let rho = ((p[0] - o.x).powi(2) + (p[1] - o.y).powi(2) + (p[2] - o.z).powi(2)).sqrt();
+            o.range - rho - p[3]
+        }).collect();
+        let redundancy = observations.len() - m;
+        let sigma0 = if redundancy > 0 {
+            (residuals.iter().zip(w.iter()).map(|(v, w)| v * v * w).sum::<f64>() / redundancy as f64).sqrt()
+        } else {
+            1.0
+        };
+        let covariance:Vec<Vec<f64>> = q.iter().map(|row| row.iter().map(|v| v * sigma0 * sigma0).collect()).collect();
+
+        // dilution of precision of the unit weight geometry in the local frame
+        let g = matrix::invert(&a.iter().fold(vec![vec![0.0; m]; m], |mut n, row| {
+            for i in 0..m {
+                for j in 0..m {
+                    n[i][j] += row[i] * row[j];
+                }
+            }
+            n
+        })).ok_or_else(|| invalid("range geometry is singular"))?;
+        let (lon, lat, _h) = convert::ecef2lla(p[0], p[1], p[2], ellps, method);
+        let block = [[g[0][0], g[0][1], g[0][2]], [g[1][0], g[1][1], g[1][2]], [g[2][0], g[2][1], g[2][2]]];
+        let enu = local::covariance_ecef2enu(&block, lon.to_radians(), lat.to_radians());
+        let tt = if clock { g[3][3] } else { 0.0 };
+
+        return Ok(Solution {
+            x: p[0], y: p[1], z: p[2], clock: p[3],
+            residuals: residuals,
+            sigma0: sigma0,
+            covariance: covariance,
+            gdop: (enu[0][0] + enu[1][1] + enu[2][2] + tt).sqrt(),
+            pdop: (enu[0][0] + enu[1][1] + enu[2][2]).sqrt(),
+            hdop: (enu[0][0] + enu[1][1]).sqrt(),
+            vdop: enu[2][2].sqrt(),
+            tdop: tt.sqrt(),
+            iterations: iterations
+        });
+    }
+}
+
+#[test]
+fn test_point_positioning() {
+    let ellps = ellipsoid::Ellipsoid::new(6378137.0, 298.257223563);
+    let receiver = convert::lla2ecef(10.0f64.to_radians(), 45.0f64.to_radians(), 300.0, ellps);
+    // satellites at 20200 km altitude spread over the sky, the last overhead
+    let sky:[(f64, f64); 7] = [(0.0, 15.0), (70.0, 40.0), (140.0, 20.0), (200.0, 60.0), (260.0, 25.0), (320.0, 45.0), (0.0, 90.0)];
+    let bias:f64 = 12345.678;
+    let mut observations:Vec<Observation> = Vec::new();
+    for (k, &(az, el)) in sky.iter().enumerate() {
+        let (az, el) = (az.to_radians(), el.to_radians());
+        // slant range to a satellite at radius 26560 km along the line of sight
+        let r0 = (receiver.0 * receiver.0 + receiver.1 * receiver.1 + receiver.2 * receiver.2).sqrt();
+        let s = -r0 * el.sin() + ((r0 * el.sin()).powi(2) + 26560.0e3f64.powi(2) - r0 * r0).sqrt();
+        let (x, y, z) = local::enu2ecef(s * el.cos() * az.sin(), s * el.cos() * az.cos(), s * el.sin(),
+            10.0f64.to_radians(), 45.0f64.to_radians(), 300.0, ellps);
+        let noise = [0.3, -0.2, 0.1, 0.0, -0.4, 0.25, 0.05][k];
+        observations.push(Observation {x: x, y: y, z: z, range: s + bias + noise, sigma: 1.0});
+    }
+
+    let solution = solve(&observations, true, (0.0, 0.0, 0.0), ellps, "olson").unwrap();
+    let error = ((solution.x - receiver.0).powi(2) + (solution.y - receiver.1).powi(2) + (solution.z - receiver.2).powi(2)).sqrt();
+    assert!(error < 1.0);
+    assert!((solution.clock - bias).abs() < 1.0);
+    assert_eq!(solution.residuals.len(), 7);
+    assert!(solution.sigma0 > 0.1 && solution.sigma0 < 0.6);
+    assert!(solution.hdop < solution.pdop && solution.vdop < solution.pdop && solution.pdop < solution.gdop);
+    let dops = solution.gdop * solution.gdop - solution.pdop * solution.pdop - solution.tdop * solution.tdop;
+    assert!(dops.abs() < 1.0e-9);
+    assert!(solution.hdop * solution.hdop + solution.vdop * solution.vdop - solution.pdop * solution.pdop < 1.0e-9);
+
+    // exact pseudoranges are recovered exactly, and four satellites leave no redundancy
+    for (o, n) in observations.iter_mut().zip([0.3, -0.2, 0.1, 0.0, -0.4, 0.25, 0.05].iter()) {
+        o.range -= n;
+    }
+    let exact = solve(&observations[..4], true, (0.0, 0.0, 0.0), ellps, "olson").unwrap();
+    assert!((exact.x - receiver.0).abs() < 1.0e-4 && (exact.y - receiver.1).abs() < 1.0e-4 && (exact.z - receiver.2).abs() < 1.0e-4);
+    assert_eq!(exact.sigma0, 1.0);
+    assert!(solve(&observations[..3], true, (0.0, 0.0, 0.0), ellps, "olson").is_err());
+}
+
+#[test]
+fn test_multilateration() {
+    let ellps = ellipsoid::Ellipsoid::new(6378137.0, 298.257223563);
+    let (lambda, phi) = (-75.0f64.to_radians(), 40.0f64.to_radians());
+    let target = local::enu2ecef(12.0, -7.0, 3.0, lambda, phi, 0.0, ellps);
+    let anchors = [(0.0, 0.0, 10.0), (50.0, 0.0, 0.0), (0.0, 50.0, 5.0), (50.0, 50.0, 20.0), (-30.0, 20.0, 0.0)];
+    let observations:Vec<Observation> = anchors.iter().map(|&(e, n, u)| {
+        let (x, y, z) = local::enu2ecef(e, n, u, lambda, phi, 0.0, ellps);
+        let range = ((x - target.0).powi(2) + (y - target.1).powi(2) + (z - target.2).powi(2)).sqrt();
+        Observation {x: x, y: y, z: z, range: range, sigma: 0.0}
+    }).collect();
+    let start = local::enu2ecef(0.0, 0.0, -100.0, lambda, phi, 0.0, ellps);
+    let solution = solve(&observations, false, start, ellps, "olson").unwrap();
+    assert!((solution.x - target.0).abs() < 1.0e-4 && (solution.y - target.1).abs() < 1.0e-4 && (solution.z - target.2).abs() < 1.0e-4);
+    assert_eq!(solution.clock, 0.0);
+    assert_eq!(solution.tdop, 0.0);
+    assert!((solution.gdop - solution.pdop).abs() < 1.0e-12);
+    assert!(solution.residuals.iter().all(|v| v.abs() < 1.0e-6));
+
+    // the anchor centroid is a start that converges to the same fix, with the same DOPs by bowring
+    let solution = solve(&observations, false, centroid(&observations), ellps, "bowring").unwrap();
+    assert!((solution.x - target.0).abs() < 1.0e-4 && (solution.y - target.1).abs() < 1.0e-4 && (solution.z - target.2).abs() < 1.0e-4);
+    assert!((solution.gdop - solution.pdop).abs() < 1.0e-12);
+}
+
+#[test]
+fn test_dop_east() {
+    // a receiver at 150 E, where x is negative, with exact pseudoranges
+    let ellps = ellipsoid::Ellipsoid::new(6378137.0, 298.257223563);
+    let (lambda, phi) = (150.0f64.to_radians(), -30.0f64.to_radians());
+    let receiver = convert::lla2ecef(lambda, phi, 50.0, ellps);
+    let r0 = (receiver.0 * receiver.0 + receiver.1 * receiver.1 + receiver.2 * receiver.2).sqrt();
+    let sky:[(f64, f64); 6] = [(10.0, 20.0), (95.0, 35.0), (170.0, 65.0), (230.0, 15.0), (300.0, 50.0), (40.0, 80.0)];
+    let mut observations:Vec<Observation> = Vec::new();
+    let mut rows:Vec<Vec<f64>> = Vec::new();
+    for &(az, el) in sky.iter() {
+        let (az, el) = (az.to_radians(), el.to_radians());
+        let s = -r0 * el.sin() + ((r0 * el.sin()).powi(2) + 26560.0e3f64.powi(2) - r0 * r0).sqrt();
+        let (x, y, z) = local::enu2ecef(s * el.cos() * az.sin(), s * el.cos() * az.cos(), s * el.sin(), lambda, phi, 50.0, ellps);
+        observations.push(Observation {x: x, y: y, z: z, range: s, sigma: 1.0});
+        // unit vector from the satellite to the receiver in east, north and up
+        rows.push(vec![-el.cos() * az.sin(), -el.cos() * az.cos(), -el.sin(), 1.0]);
+    }
+
+    let solution = solve(&observations, true, (0.0, 0.0, 0.0), ellps, "olson").unwrap();
+    assert!((solution.x - receiver.0).abs() < 1.0e-4 && (solution.y - receiver.1).abs() < 1.0e-4 && (solution.z - receiver.2).abs() < 1.0e-4);
+
+    // DOPs straight from the local geometry
+    let n:Vec<Vec<f64>> = (0..4).map(|i| (0..4).map(|j| rows.iter().map(|r| r[i] * r[j]).sum()).collect()).collect();
+    let q = matrix::invert(&n).unwrap();
+    assert!((solution.hdop - (q[0][0] + q[1][1]).sqrt()).abs() < 1.0e-6);
+    assert!((solution.vdop - q[2][2].sqrt()).abs() < 1.0e-6);
+    assert!((solution.tdop - q[3][3].sqrt()).abs() < 1.0e-6);
+}
@@ -0,0 +1,287 @@
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use ellipsoid;
+
+/// Local distortion of a projection at a point.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, pp. 20-27.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Factors {
+    /// scale factor along the meridian
+    pub h : f64,
+    /// scale factor along the parallel
+    pub k : f64,
+    /// meridian convergence, the angle from true north to grid north (radians, positive clockwise)
+    pub convergence : f64,
+    /// areal scale factor
+    pub s : f64,
+    /// angle between the projected meridian and parallel (radians)
+    pub theta : f64,
+    /// Tissot indicatrix semi-major axis, the maximum scale factor
+    pub a : f64,
+    /// Tissot indicatrix semi-minor axis, the minimum scale factor
+    pub b : f64,
+    /// maximum angular distortion (radians)
+    pub omega : f64
+}
+
+/// A map projection between geodetic coordinates and grid coordinates on an ellipsoid.
+///
+/// Longitude lambda and latitude phi are given in radians; easting x and northing y in meters.
+///
+pub trait Projection {
+    /// The ellipsoid the projection is defined on.
+    ///
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid;
+
+    /// Project geodetic longitude and latitude to easting and northing.
+    ///
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64);
+
+    /// Recover geodetic longitude and latitude from easting and northing.
+    ///
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64);
+
+    /// Calculate the scale factors, convergence and Tissot indicatrix at longitude lambda and latitude phi.
+    ///
+    /// Partial derivatives of the forward projection are found by central differences and
+    /// compared to the meridional and normal radii of curvature of the ellipsoid.
+    ///
+    fn factors(&self, lambda:f64, phi:f64) -> Factors {
+        let step:f64 = 1.0e-6;
+        // keep the latitude stencil off the poles
+        let phi:f64 = phi.max(-FRAC_PI_2 + 2.0 * step).min(FRAC_PI_2 - 2.0 * step);
+
+        let (xe, ye) = self.forward(lambda + step, phi);
+        let (xw, yw) = self.forward(lambda - step, phi);
+        let (xn, yn) = self.forward(lambda, phi + step);
+        let (xs, ys) = self.forward(lambda, phi - step);
+
+        let x_l:f64 = (xe - xw) / (2.0 * step);
+        let y_l:f64 = (ye - yw) / (2.0 * step);
+        let x_p:f64 = (xn - xs) / (2.0 * step);
+        let y_p:f64 = (yn - ys) / (2.0 * step);
+
+        let (_r, rn, rm) = self.ellipsoid().radii(phi);
+        let rp:f64 = rn * phi.cos();
+
+        let h:f64 = (x_p * x_p + y_p * y_p).sqrt() / rm;
+        let k:f64 = (x_l * x_l + y_l * y_l).sqrt() / rp;
+        let s:f64 = (y_p * x_l - x_p * y_l).abs() / (rm * rp);
+        let theta:f64 = (s / (h * k)).min(1.0).asin();
+
+        let ap:f64 = (h * h + k * k + 2.0 * s).sqrt();
+        let bp:f64 = (h * h + k * k - 2.0 * s).max(0.0).sqrt();
+        let a:f64 = (ap + bp) / 2.0;
+        let b:f64 = (ap - bp) / 2.0;
+        let omega:f64 = 2.0 * ((a - b) / (a + b)).asin();
+
+        Factors {h: h, k: k, convergence: (-x_p).atan2(y_p), s: s, theta: theta, a: a, b: b, omega: omega}
+    }
+
+    /// Calculate the point scale factors h, along the meridian, and k, along the parallel.
+    ///
+    fn scale_factor(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let factors = self.factors(lambda, phi);
+        (factors.h, factors.k)
+    }
+
+    /// Calculate the meridian convergence angle, in radians, positive where grid north lies east of true north.
+    ///
+    fn convergence(&self, lambda:f64, phi:f64) -> f64 {
+        self.factors(lambda, phi).convergence
+    }
+}
+
+/// Calculate the small circle radius ratio m at latitude phi, given in radians.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, eq. 14-15.
+///
+pub fn msfn(phi:f64, ecc:f64) -> f64 {
+    let slat = phi.sin();
+    phi.cos() / (1.0 - ecc * ecc * slat * slat).sqrt()
+}
+
+/// Calculate the conformal latitude function t at latitude phi, given in radians.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, eq. 15-9.
+///
+pub fn tsfn(phi:f64, ecc:f64) -> f64 {
+    let esin = ecc * phi.sin();
+    (FRAC_PI_4 - phi / 2.0).tan() / ((1.0 - esin) / (1.0 + esin)).powf(ecc / 2.0)
+}
+
+/// Recover latitude, in radians, from the conformal latitude function t by iteration.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, eq. 7-9.
+///
+pub fn phi2(t:f64, ecc:f64) -> f64 {
+    let mut phi:f64 = FRAC_PI_2 - 2.0 * t.atan();
+    for _ in 0..15 {
+        let esin = ecc * phi.sin();
+        let next:f64 = FRAC_PI_2 - 2.0 * (t * ((1.0 - esin) / (1.0 + esin)).powf(ecc / 2.0)).atan();
+        let delta = (next - phi).abs();
+        phi = next;
+        if delta < 1.0e-12 {
+            break;
+        }
+    }
+    phi
+}
+
+/// Calculate the authalic function q at latitude phi, given in radians.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, eq. 3-12.
+///
+pub fn qsfn(phi:f64, ecc:f64) -> f64 {
+    let slat = phi.sin();
+    if ecc < 1.0e-10 {
+        return 2.0 * slat;
+    }
+    let esin = ecc * slat;
+    (1.0 - ecc * ecc) * (slat / (1.0 - esin * esin) - (1.0 / (2.0 * ecc)) * ((1.0 - esin) / (1.0 + esin)).ln())
+}
+
+/// Recover latitude, in radians, from the authalic function q by Newton iteration.
+///
+/// References:
+/// -----------
+/// Snyder, J. P. (1987). Map Projections: A Working Manual. USGS
+/// Professional Paper 1395, eq. 3-16.
+///
+pub fn phi_from_q(q:f64, ecc:f64) -> f64 {
+    let qp:f64 = qsfn(FRAC_PI_2, ecc);
+    let ratio:f64 = q / qp;
+    if ratio.abs() >= 1.0 - 1.0e-14 {
+        return ratio.signum() * FRAC_PI_2;
+    }
+    if ecc < 1.0e-10 {
+        return (q / 2.0).asin();
+    }
+    let esq:f64 = ecc * ecc;
+    let mut phi:f64 = ratio.asin();
+    for _ in 0..15 {
+        let slat = phi.sin();
+        let clat = phi.cos();
+        let esin = ecc * slat;
+        let w = 1.0 - esin * esin;
+        let delta = w * w / (2.0 * clat)
+            * (q / (1.0 - esq) - slat / w + (1.0 / (2.0 * ecc)) * ((1.0 - esin) / (1.0 + esin)).ln());
+        phi = phi + delta;
+        if delta.abs() < 1.0e-14 {
+            break;
+        }
+    }
+    phi
+}
+
+/// Calculate the authalic latitude, in radians, at geodetic latitude phi.
+///
+pub fn authalic(phi:f64, ecc:f64) -> f64 {
+    let ratio:f64 = qsfn(phi, ecc) / qsfn(FRAC_PI_2, ecc);
+    ratio.max(-1.0).min(1.0).asin()
+}
+
+/// Calculate the geodetic latitude, in radians, at authalic latitude beta.
+///
+pub fn authalic_inverse(beta:f64, ecc:f64) -> f64 {
+    phi_from_q(qsfn(FRAC_PI_2, ecc) * beta.sin(), ecc)
+}
+
+/// Calculate the meridian arc length, in meters, from the equator to latitude phi, given in radians.
+///
+/// References:
+/// -----------
+/// Deakin, R. E. and Hunter, M. N. (2010). Geometric Geodesy Part A. RMIT
+/// University, eq. 99, series in the third flattening n.
+///
+pub fn mlfn(phi:f64, ellps:ellipsoid::Ellipsoid) -> f64 {
+    let n:f64 = ellps.f / (2.0 - ellps.f);
+    let (n2, n3, n4) = (n * n, n * n * n, n * n * n * n);
+
+    let b0:f64 = ellps.a / (1.0 + n) * (1.0 + n2 / 4.0 + n4 / 64.0);
+    b0 * (phi
+        - (1.5 * n - 3.0 / 16.0 * n3) * (2.0 * phi).sin()
+        + (15.0 / 16.0 * n2 - 15.0 / 64.0 * n4) * (4.0 * phi).sin()
+        - 35.0 / 48.0 * n3 * (6.0 * phi).sin()
+        + 315.0 / 512.0 * n4 * (8.0 * phi).sin())
+}
+
+/// Recover latitude, in radians, from meridian arc length by Newton iteration.
+///
+pub fn inv_mlfn(m:f64, ellps:ellipsoid::Ellipsoid) -> f64 {
+    let mut phi:f64 = m / ellps.a;
+    for _ in 0..15 {
+        let (_r, _rn, rm) = ellps.radii(phi);
+        let delta:f64 = (m - mlfn(phi, ellps)) / rm;
+        phi = phi + delta;
+        if delta.abs() < 1.0e-14 {
+            break;
+        }
+    }
+    phi
+}
+
+#[test]
+fn test_factors_conformal() {
+    use lcc;
+
+    let grs80 = ellipsoid::Ellipsoid::new(6378137.000, 298.257222101);
+    let proj = lcc::LambertConformalConic::new_2sp(grs80, -77.75f64.to_radians(), 39.33333333333334f64.to_radians(),
+        40.96666666666667f64.to_radians(), 39.93333333333333f64.to_radians(), 600000.0, 0.0);
+
+    // true to scale on the standard parallel
+    let factors = proj.factors(-77.75f64.to_radians(), 40.96666666666667f64.to_radians());
+    assert!((factors.k - 1.0).abs() < 1.0e-9);
+
+    // conformal, with convergence n * (lambda - lambda0)
+    let lambda:f64 = -76.0f64.to_radians();
+    let factors = proj.factors(lambda, 40.5f64.to_radians());
+    println!("{:?}", factors);
+    assert!((factors.h - factors.k).abs() < 1.0e-9);
+    assert!(factors.k < 1.0);
+    assert!(factors.omega.abs() < 1.0e-6);
+    assert!((factors.theta - FRAC_PI_2).abs() < 1.0e-6);
+    assert!((factors.convergence - proj.n * (lambda - proj.lambda0)).abs() < 1.0e-9);
+}
+
+#[test]
+fn test_factors_equal_area() {
+    use equalarea;
+
+    let grs80 = ellipsoid::Ellipsoid::new(6378137.000, 298.257222101);
+    let proj = equalarea::AlbersEqualArea::new(grs80, -96f64.to_radians(), 23f64.to_radians(),
+        29.5f64.to_radians(), 45.5f64.to_radians(), 0.0, 0.0);
+
+    let factors = proj.factors(-75f64.to_radians(), 35f64.to_radians());
+    println!("{:?}", factors);
+    assert!((factors.s - 1.0).abs() < 1.0e-9);
+    assert!((factors.a * factors.b - 1.0).abs() < 1.0e-9);
+    assert!((factors.h * factors.k - 1.0).abs() < 1.0e-9);
+    assert!(factors.omega > 0.0);
+}
+
+#[test]
+fn test_mlfn() {
+    // quarter meridian of WGS84
+    let wgs84 = ellipsoid::Ellipsoid::new(6378137.000, 298.257223563);
+    let quarter:f64 = mlfn(FRAC_PI_2, wgs84);
+    assert!((quarter - 10001965.729).abs() < 0.001);
+    assert!((inv_mlfn(mlfn(0.7, wgs84), wgs84) - 0.7).abs() < 1.0e-14);
+}
@@ -0,0 +1,462 @@
+use std::io;
+
+use grid;
+use grid::invalid;
+use matrix;
+use time;
+
+/// Speed of light (m/s).
+///
+pub const C:f64 = 299792458.0;
+
+/// Seconds in a week.
+///
+const WEEK:f64 = 604800.0;
+
+/// Offset of BeiDou time from GPS time (s), and of the BeiDou week number from the GPS week.
+///
+const BDT_GPS:f64 = -14.0;
+const BDT_WEEK:f64 = 1356.0;
+
+/// Broadcast Keplerian ephemeris and clock polynomial of a GPS, Galileo, QZSS or BeiDou satellite.
+///
+/// Times are continuous GPS time in seconds since the GPS epoch, 1980-01-06 00:00.
+///
+#[derive(Clone, Debug)]
+pub struct Ephemeris {
+    /// satellite system letter and number, e.g. G01
+    pub sat : String,
+    /// clock reference time (s)
+    pub toc : f64,
+    /// clock bias (s)
+    pub af0 : f64,
+    /// clock drift (s/s)
+    pub af1 : f64,
+    /// clock drift rate (s/s²)
+    pub af2 : f64,
+    /// issue of data
+    pub iode : f64,
+    /// ephemeris reference time (s)
+    pub toe : f64,
+    /// square root of the semi-major axis (m^½)
+    pub sqrt_a : f64,
+    /// eccentricity
+    pub e : f64,
+    /// mean anomaly at toe (radians)
+    pub m0 : f64,
+    /// mean motion difference (radians/s)
+    pub delta_n : f64,
+    /// longitude of the ascending node at the start of the week (radians)
+    pub omega0 : f64,
+    /// rate of right ascension (radians/s)
+    pub omega_dot : f64,
+    /// inclination at toe (radians)
+    pub i0 : f64,
+    /// rate of inclination (radians/s)
+    pub idot : f64,
+    /// argument of perigee (radians)
+    pub omega : f64,
+    /// harmonic corrections to the argument of latitude (radians)
+    pub cuc : f64,
+    pub cus : f64,
+    /// harmonic corrections to the orbit radius (m)
+    pub crc : f64,
+    pub crs : f64,
+    /// harmonic corrections to the inclination (radians)
+    pub cic : f64,
+    pub cis : f64,
+    /// group delay, TGD for GPS and BeiDou B1 or BGD E5a/E1 for Galileo (s)
+    pub tgd : f64,
+    /// health flags, zero when healthy
+    pub health : f64
+}
+
+/// Convert a week number and seconds of the week in a satellite system's own time scale to
+/// continuous GPS time.
+///
+fn continuous(system:char, week:f64, sow:f64) -> f64 {
+    match system {
+        'C' => (week + BDT_WEEK) * WEEK + sow - BDT_GPS,
+        _ => week * WEEK + sow
+    }
+}
+
+/// Calculate the continuous GPS time, in seconds, of a UTC Julian date.
+///
+pub fn gps_seconds(jd_utc:f64, leaps:&time::LeapSeconds) -> f64 {
+    (time::tai2gps(time::utc2tai(jd_utc, leaps)) - time::GPS0) * 86400.0
+}
+
+impl Ephemeris {
+
+    /// Gravitational parameter (m³/s²) and Earth rotation rate (radians/s) of the satellite system.
+    ///
+    pub fn constants(&self) -> (f64, f64) {
+        match self.system() {
+            'E' => (3.986004418e14, 7.2921151467e-5),
+            'C' => (3.986004418e14, 7.2921150e-5),
+            _ => (3.986005e14, 7.2921151467e-5)
+        }
+    }
+
+    /// Satellite system letter: G GPS, E Galileo, C BeiDou, J QZSS.
+    ///
+    pub fn system(&self) -> char {
+        self.sat.chars().next().unwrap_or('G')
+    }
+
+    /// Whether the satellite is a BeiDou geostationary satellite, whose orbit is broadcast in an
+    /// inclined frame.
+    ///
+    pub fn geostationary(&self) -> bool {
+        let prn:u32 = self.sat[1..].trim().parse().unwrap_or(0);
+        self.system() == 'C' && (prn <= 5 || prn >= 59)
+    }
+
+    /// Time from a reference time, wrapped across week crossovers.
+    ///
+    fn since(t:f64, reference:f64) -> f64 {
+        let mut dt:f64 = t - reference;
+        if dt > WEEK / 2.0 {
+            dt -= WEEK;
+        } else if dt < -WEEK / 2.0 {
+            dt += WEEK;
+        }
+        dt
+    }
+
+    /// Solve Kepler's equation for the eccentric anomaly at time t.
+    ///
+    fn eccentric_anomaly(&self, t:f64) -> f64 {
+        let (mu, _omega_e) = self.constants();
+        let a:f64 = self.sqrt_a * self.sqrt_a;
+        let n:f64 = (mu / (a * a * a)).sqrt() + self.delta_n;
+        let m:f64 = self.m0 + n * Ephemeris::since(t, self.toe);
+        let mut e:f64 = m;
+        for _ in 0..20 {
+            let next:f64 = m + self.e * e.sin();
+            let done = (next - e).abs() < 1.0e-14;
+            e = next;
+            if done {
+                break;
+            }
+        }
+        e
+    }
+
+    /// Calculate the ECEF position, in meters, at the time of transmission t in GPS seconds.
+    ///
+    /// References:
+    /// -----------
+    /// IS-GPS-200 (2022). Navstar GPS Space Segment/Navigation User Interfaces, table 20-IV.
+    ///
+    /// BeiDou ICD B1I (2019). BeiDou Navigation Satellite System Signal In Space Interface
+    /// Control Document, version 3.0, sec. 5.2.4.12.
+    ///
+    pub fn position(&self, t:f64) -> (f64, f64, f64) {
+        let (_mu, omega_e) = self.constants();
+        let a:f64 = self.sqrt_a * self.sqrt_a;
+        let tk:f64 = Ephemeris::since(t, self.toe);
+        let ek:f64 = self.eccentric_anomaly(t);
+
+        let nu:f64 = ((1.0 - self.e * self.e).sqrt() * ek.sin()).atan2(ek.cos() - self.e);
+        let phi:f64 = nu + self.omega;
+        let (s2, c2) = ((2.0 * phi).sin(), (2.0 * phi).cos());
+        let u:f64 = phi + self.cus * s2 + self.cuc * c2;
+        let r:f64 = a * (1.0 - self.e * ek.cos()) + self.crs * s2 + self.crc * c2;
+        let i:f64 = self.i0 + self.cis * s2 + self.cic * c2 + self.idot * tk;
+        let (xp, yp) = (r * u.cos(), r * u.sin());
+
+        // seconds of the week at toe in the system's own time scale, from which the node is counted
+        let toe_week:f64 = match self.system() {
+            'C' => (self.toe + BDT_GPS).rem_euclid(WEEK),
+            _ => self.toe.rem_euclid(WEEK)
+        };
+        if self.geostationary() {
+            // inertial node, then rotated from the frame inclined by -5 degrees
+            let node:f64 = self.omega0 + self.omega_dot * tk - omega_e * toe_week;
+            let g = [xp * node.cos() - yp * i.cos() * node.sin(),
+                     xp * node.sin() + yp * i.cos() * node.cos(),
+                     yp * i.sin()];
+            let rotation = matrix::mul(&matrix::rot3(omega_e * tk), &matrix::rot1(-5.0f64.to_radians()));
+            let v = matrix::mul_vec(&rotation, g);
+            return (v[0], v[1], v[2]);
+        }
+        let node:f64 = self.omega0 + (self.omega_dot - omega_e) * tk - omega_e * toe_week;
+
+        (xp * node.cos() - yp * i.cos() * node.sin(),
+         xp * node.sin() + yp * i.cos() * node.cos(),
+         yp * i.sin())
+    }
+
+    /// Calculate the satellite clock offset, in seconds, at the time of transmission t including
+    /// the relativistic correction, but not the group delay.
+    ///
+    pub fn clock(&self, t:f64) -> f64 {
+        let (mu, _omega_e) = self.constants();
+        let f:f64 = -2.0 * mu.sqrt() / (C * C);
+        let dt:f64 = Ephemeris::since(t, self.toc);
+
+        self.af0 + self.af1 * dt + self.af2 * dt * dt + f * self.e * self.sqrt_a * self.eccentric_anomaly(t).sin()
+    }
+
+    /// Calculate the ECEF position at transmission of a signal received at time t_rx at a receiver,
+    /// expressed in the Earth-fixed frame at reception, with the light time in seconds.
+    ///
+    /// The light time is iterated from the geometric range, and the Earth's rotation during it is
+    /// removed by rotating the transmit position about the z axis.
+    ///
+    pub fn received(&self, t_rx:f64, x:f64, y:f64, z:f64) -> ((f64, f64, f64), f64) {
+        let (_mu, omega_e) = self.constants();
+        let mut tau:f64 = 0.075;
+        let mut p = (0.0, 0.0, 0.0);
+        for _ in 0..5 {
+            p = earth_rotation(self.position(t_rx - tau), omega_e * tau);
+            let range:f64 = ((p.0 - x).powi(2) + (p.1 - y).powi(2) + (p.2 - z).powi(2)).sqrt();
+            tau = range / C;
+        }
+        (p, tau)
+    }
+}
+
+/// Rotate an ECEF position by the Earth's rotation through angle theta, in radians, giving the
+/// same point in the frame fixed at the later time.
+///
+pub fn earth_rotation(p:(f64, f64, f64), theta:f64) -> (f64, f64, f64) {
+    let v = matrix::mul_vec(&matrix::rot3(theta), [p.0, p.1, p.2]);
+    (v[0], v[1], v[2])
+}
+
+/// Broadcast ephemerides of a RINEX navigation file.
+///
+#[derive(Clone, Debug)]
+pub struct Navigation {
+    /// ephemerides in the order of the file
+    pub ephemerides : Vec<Ephemeris>
+}
+
+impl Navigation {
+
+    /// Select the healthy ephemeris of a satellite with the reference time nearest to t, within
+    /// four hours.
+    ///
+    pub fn ephemeris(&self, sat:&str, t:f64) -> Option<&Ephemeris> {
+        self.ephemerides.iter()
+            .filter(|eph| eph.sat == sat && eph.health == 0.0 && (t - eph.toe).abs() <= 4.0 * 3600.0)
+            .min_by(|a, b| (t - a.toe).abs().partial_cmp(&(t - b.toe).abs()).unwrap())
+    }
+}
+
+/// Parse a number of a RINEX record, allowing D exponents and treating blanks as zero.
+///
+fn number(line:&str, a:usize) -> io::Result<f64> {
+    let text = line.get(a..(a + 19).min(line.len())).unwrap_or("").trim();
+    if text.is_empty() {
+        return Ok(0.0);
+    }
+    text.replace('D', "E").replace('d', "e").parse::<f64>()
+        .map_err(|_| invalid(&format!("could not parse {} in navigation record", text)))
+}
+
+/// Parse a RINEX 3 navigation file, keeping the GPS, Galileo, QZSS and BeiDou ephemerides.
+///
+/// GLONASS, SBAS and other records are skipped. RINEX 2 and RINEX 4 files are rejected.
+///
+/// References:
+/// -----------
+/// IGS (2021). RINEX The Receiver Independent Exchange Format, version 3.05.
+/// https://files.igs.org/pub/data/format/rinex305.pdf
+///
+pub fn parse(text:&str) -> io::Result<Navigation> {
+    let mut lines = text.lines();
+    let version = lines.next().and_then(|l| l.get(0..9)).and_then(|v| v.trim().parse::<f64>().ok());
+    // RINEX 4 navigation files frame each record with a message type line
+    if version.map_or(true, |v| v < 3.0 || v >= 4.0) {
+        return Err(invalid("navigation file is not RINEX 3"));
+    }
+    let body:Vec<&str> = lines.skip_while(|l| !l.contains("END OF HEADER")).skip(1).collect();
+
+    let mut ephemerides:Vec<Ephemeris> = Vec::new();
+    let mut i:usize = 0;
+    while i < body.len() {
+        let first = body[i];
+        let mut n:usize = 1;
+        while i + n < body.len() && body[i + n].starts_with(' ') {
+            n += 1;
+        }
+        let record = &body[i..i + n];
+        i += n;
+        let system = match first.chars().next() {
+            Some(c) if "GECJ".contains(c) => c,
+            _ => continue
+        };
+        if record.len() < 8 {
+            return Err(invalid(&format!("navigation record for {} is truncated", &first[..3.min(first.len())])));
+        }
+
+        let field = |l:usize, k:usize| number(record[l], 4 + 19 * k);
+        let date:Vec<i64> = first.get(4..23).unwrap_or("").split_whitespace().filter_map(|v| v.parse().ok()).collect();
+        if date.len() != 6 {
+            return Err(invalid(&format!("could not parse the epoch of {}", first)));
+        }
+        let jd = time::julian_date(date[0], date[1], date[2], date[3], date[4], date[5] as f64);
+        let toc = (jd - time::GPS0) * 86400.0 - if system == 'C' { BDT_GPS } else { 0.0 };
+        let week = field(5, 2)?;
+
+        ephemerides.push(Ephemeris {
+            sat: format!("{}{:02}", system, first[1..3].trim().parse::<u32>().unwrap_or(0)),
+            toc: toc,
+            af0: number(first, 23)?,
+            af1: number(first, 42)?,
+            af2: number(first, 61)?,
+            iode: field(1, 0)?,
+            crs: field(1, 1)?,
+            delta_n: field(1, 2)?,
+            m0: field(1, 3)?,
+            cuc: field(2, 0)?,
+            e: field(2, 1)?,
+            cus: field(2, 2)?,
+            sqrt_a: field(2, 3)?,
+            toe: continuous(system, week, field(3, 0)?),
+            cic: field(3, 1)?,
+            omega0: field(3, 2)?,
+            cis: field(3, 3)?,
+            i0: field(4, 0)?,
+            crc: field(4, 1)?,
+            omega: field(4, 2)?,
+            omega_dot: field(4, 3)?,
+            idot: field(5, 0)?,
+            health: field(6, 1)?,
+            tgd: field(6, 2)?
+        });
+    }
+    Ok(Navigation {ephemerides: ephemerides})
+}
+
+/// Read a RINEX 3 navigation file.
+///
+pub fn read(path:&str) -> io::Result<Navigation> {
+    let bytes = grid::contents(path)?;
+    parse(&String::from_utf8_lossy(&bytes))
+}
+
+#[test]
+fn test_broadcast() {
+    use convert;
+    use ellipsoid;
+
+    let text = "\
+     3.04           N: GNSS NAV DATA    M: Mixed            RINEX VERSION / TYPE
+                                                            END OF HEADER
+G01 2020 01 01 00 00 00-3.670812584460D-04-6.366462912410D-12 0.000000000000D+00
+     4.400000000000D+01-1.134375000000D+02 4.183031367022D-09 2.494617662030D+00
+    -5.919113755226D-06 8.687092643231D-03 3.898516297340D-06 5.153602233887D+03
+     2.592000000000D+05 1.601874828339D-07-1.534139960587D+00-1.043081283569D-07
+     9.756389245017D-01 2.982500000000D+02 7.262016041280D-01-8.135695116520D-09
+    -2.235807465911D-10 1.000000000000D+00 2.086000000000D+03 0.000000000000D+00
+     2.000000000000D+00 0.000000000000D+00 5.587935447693D-09 4.400000000000D+01
+     2.520180000000D+05 4.000000000000D+00
+R01 2020 01 01 00 15 00 1.122243702412D-05 0.000000000000D+00 2.592000000000D+05
+    -1.124873730469D+04-1.983337402344D+00 0.000000000000D+00 0.000000000000D+00
+     1.079199267578D+04-1.618804931641D+00 9.313225746155D-10 1.000000000000D+00
+     1.986657031250D+04 1.939628601074D+00-2.793967723846D-09 0.000000000000D+00
+E11 2020 01 01 00 10 00-6.370618753135D-04-7.815970093361D-12 0.000000000000D+00
+     7.400000000000D+01-2.125000000000D+01 2.919050983917D-09-2.567466311549D+00
+    -9.909272193909D-07 2.495134249330D-04 9.670108556747D-06 5.440622089386D+03
+     2.598000000000D+05 1.303851604462D-08 1.625186237710D+00-1.862645149231D-09
+     9.718453398456D-01 1.549062500000D+02 2.880104658120D-01-5.432725409054D-09
+     4.400183283648D-10 2.580000000000D+02 2.086000000000D+03 0.000000000000D+00
+     3.120000000000D+00 0.000000000000D+00-1.862645149231D-09-2.095475792885D-09
+     2.604540000000D+05
+C01 2020 01 01 00 00 00 2.329139271751D-04 4.058753134958D-11 0.000000000000D+00
+     1.000000000000D+00 6.845312500000D+02 1.133261512089D-09 2.873064709310D+00
+     2.161785960197D-05 7.133656181395D-04 4.011206328869D-05 6.493354694366D+03
+     2.592000000000D+05-4.470348358154D-08 3.009193576998D+00-3.911554813385D-08
+     7.711768430213D-02 1.227234375000D+03 2.896713584773D+00 6.568845215237D-10
+    -1.260767085463D-10 0.000000000000D+00 7.300000000000D+02 0.000000000000D+00
+     2.000000000000D+00 0.000000000000D+00-5.800000000000D-09-1.020000000000D-08
+     2.592000000000D+05 0.000000000000D+00
+";
+    let nav = parse(text).unwrap();
+    assert_eq!(nav.ephemerides.len(), 3);
+    let gps = &nav.ephemerides[0];
+    assert_eq!(gps.sat, "G01");
+    assert!((gps.toe - (2086.0 * WEEK + 259200.0)).abs() < 1.0e-6);
+    assert!((gps.toc - gps.toe).abs() < 1.0e-6);
+    assert!((gps.sqrt_a - 5153.602233887).abs() < 1.0e-9);
+    let bds = &nav.ephemerides[2];
+    assert!(bds.geostationary());
+    // BeiDou week 730 and 259200 s of BDT are 14 s later in GPS time
+    assert!((bds.toe - ((730.0 + BDT_WEEK) * WEEK + 259214.0)).abs() < 1.0e-6);
+    assert!((bds.toc - bds.toe).abs() < 1.0e-6);
+
+    // positions and clocks 900 s after toe from an independent evaluation of the equations of
+    // IS-GPS-200, the Galileo OS SIS ICD and, for the inclined GEO frame, the BDS-SIS-ICD
+    let reference = [
+        (-1145971.7549, 26299526.1921, -4814033.1364, -3.670967233698823e-04),
+        (11950131.8017, -18026792.6270, -20217439.8648, -6.370685278573339e-04),
+        (-32360432.4914, 27064661.0889, 765100.7046, 2.329500415468756e-04)];
+    for (eph, &(x0, y0, z0, clock)) in nav.ephemerides.iter().zip(reference.iter()) {
+        let (x, y, z) = eph.position(eph.toe + 900.0);
+        assert!((x - x0).abs() < 1.0e-3 && (y - y0).abs() < 1.0e-3 && (z - z0).abs() < 1.0e-3);
+        assert!((eph.clock(eph.toe + 900.0) - clock).abs() < 1.0e-15);
+    }
+
+    // the GEO stays within a degree of the equator near 140E only when rotated out of the
+    // inclined frame, where its orbit is inclined by about 4.4 degrees; its geodetic longitude
+    // is as the nav command reports it, with x negative
+    let wgs84 = ellipsoid::named("WGS84").unwrap();
+    for k in -24..25 {
+        let (x, y, z) = bds.position(bds.toe + 1800.0 * k as f64);
+        let (lon, lat, _hae) = convert::ecef2lla(x, y, z, wgs84, "olson");
+        assert!(x < 0.0 && lat.abs() < 1.1 && (lon - 140.07).abs() < 0.1);
+    }
+
+    for eph in nav.ephemerides.iter() {
+        let t = eph.toe + 900.0;
+        let (x, y, z) = eph.position(t);
+        let r = (x * x + y * y + z * z).sqrt();
+        let a = eph.sqrt_a * eph.sqrt_a;
+        assert!(r > a * (1.0 - eph.e) - 2000.0 && r < a * (1.0 + eph.e) + 2000.0);
+        // Earth-fixed speeds: about 3.9 km/s in MEO, nearly still for the BeiDou GEO
+        let (x1, y1, z1) = eph.position(t + 0.5);
+        let (x0, y0, z0) = eph.position(t - 0.5);
+        let speed = ((x1 - x0).powi(2) + (y1 - y0).powi(2) + (z1 - z0).powi(2)).sqrt();
+        if eph.geostationary() { assert!(speed < 300.0) } else { assert!(speed > 2500.0 && speed < 4000.0) }
+    }
+
+    // the relativistic term follows the eccentric anomaly, within tens of nanoseconds for GPS
+    let dt = gps.clock(gps.toc + 3600.0) - (gps.af0 + gps.af1 * 3600.0);
+    assert!(dt.abs() < 3.0e-8);
+    assert!(nav.ephemeris("G01", gps.toe + 7200.0).is_some());
+    assert!(nav.ephemeris("G01", gps.toe + 5.0 * 3600.0).is_none());
+    assert!(nav.ephemeris("R01", gps.toe).is_none());
+
+    let version4 = text.replacen("3.04", "4.00", 1);
+    assert!(parse(&version4).is_err());
+}
+
+#[test]
+fn test_circular() {
+    // a circular equatorial orbit with its node at the Greenwich meridian at toe
+    let a:f64 = 26560.0e3;
+    let eph = Ephemeris {sat: "G99".to_string(), toc: 0.0, af0: 0.0, af1: 0.0, af2: 0.0, iode: 0.0,
+        toe: 100.0 * WEEK + 3600.0, sqrt_a: a.sqrt(), e: 0.0, m0: 0.3, delta_n: 0.0, omega0: 7.2921151467e-5 * 3600.0,
+        omega_dot: 0.0, i0: 0.0, idot: 0.0, omega: 0.0, cuc: 0.0, cus: 0.0, crc: 0.0, crs: 0.0, cic: 0.0, cis: 0.0,
+        tgd: 0.0, health: 0.0};
+    let (mu, omega_e) = eph.constants();
+    let n = (mu / (a * a * a)).sqrt();
+    let t:f64 = 1800.0;
+    let (x, y, z) = eph.position(eph.toe + t);
+    let angle = 0.3 + (n - omega_e) * t;
+    assert!((x - a * angle.cos()).abs() < 1.0e-6 && (y - a * angle.sin()).abs() < 1.0e-6 && z.abs() < 1.0e-9);
+
+    // a receiver on the orbit's radius below the satellite sees it straight overhead
+    let (rx, ry, rz) = (6.4e6 * angle.cos(), 6.4e6 * angle.sin(), 0.0);
+    let (p, tau) = eph.received(eph.toe + t, rx, ry, rz);
+    assert!((tau - (a - 6.4e6) / C).abs() < 1.0e-6);
+    // Earth rotation correction moves the satellite by ωe τ r, about 130 m
+    let q = eph.position(eph.toe + t - tau);
+    let moved = ((p.0 - q.0).powi(2) + (p.1 - q.1).powi(2) + (p.2 - q.2).powi(2)).sqrt();
+    assert!((moved - omega_e * tau * a).abs() < 1.0);
+}
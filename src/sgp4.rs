@@ -0,0 +1,1092 @@
+use std::f64::consts::PI;
+use std::io;
+
+use eci;
+use ellipsoid;
+use grid;
+use grid::invalid;
+use local;
+use time;
+
+/// WGS72 gravitational parameter (km³/s²), as used to generate two-line elements.
+///
+const MU:f64 = 398600.8;
+
+/// WGS72 equatorial radius (km).
+///
+const RADIUS:f64 = 6378.135;
+
+/// WGS72 zonal harmonics.
+///
+const J2:f64 = 0.001082616;
+const J3:f64 = -0.00000253881;
+const J4:f64 = -0.00000165597;
+
+const TWOPI:f64 = 2.0 * PI;
+const X2O3:f64 = 2.0 / 3.0;
+
+/// Square root of the gravitational parameter in Earth radii³ per minute².
+///
+fn xke() -> f64 {
+    60.0 / (RADIUS * RADIUS * RADIUS / MU).sqrt()
+}
+
+/// Mean elements of a two-line element set.
+///
+#[derive(Clone, Debug)]
+pub struct Tle {
+    /// satellite name, from the line preceding the elements if any
+    pub name : String,
+    /// NORAD catalogue number
+    pub satnum : String,
+    /// epoch (Julian date, UTC)
+    pub epoch : f64,
+    /// drag term (1/earth radii)
+    pub bstar : f64,
+    /// inclination (radians)
+    pub inclo : f64,
+    /// right ascension of the ascending node (radians)
+    pub nodeo : f64,
+    /// eccentricity
+    pub ecco : f64,
+    /// argument of perigee (radians)
+    pub argpo : f64,
+    /// mean anomaly (radians)
+    pub mo : f64,
+    /// Kozai mean motion (radians/minute)
+    pub no_kozai : f64
+}
+
+/// Parse a fixed column field of a two-line element set.
+///
+fn field(line:&str, a:usize, b:usize) -> io::Result<f64> {
+    let text = line.get(a..b.min(line.len())).unwrap_or("").trim();
+    text.parse::<f64>().map_err(|_| invalid(&format!("could not parse {} in TLE line {}", text, line)))
+}
+
+/// Parse a field with an implied leading decimal point and exponent, e.g. -11606-4 for -0.11606e-4.
+///
+fn exponent_field(line:&str, a:usize, b:usize) -> io::Result<f64> {
+    let text = line.get(a..b.min(line.len())).unwrap_or("").trim().replace(' ', "");
+    if text.is_empty() {
+        return Ok(0.0);
+    }
+    let split = text.rfind(|c| c == '-' || c == '+').filter(|&i| i > 0).unwrap_or(text.len());
+    let (mantissa, exponent) = text.split_at(split);
+    let (sign, digits) = match mantissa.chars().next() {
+        Some('-') => (-1.0, &mantissa[1..]),
+        Some('+') => (1.0, &mantissa[1..]),
+        _ => (1.0, mantissa)
+    };
+    let value = format!("0.{}e{}", digits, if exponent.is_empty() { "0" } else { exponent });
+    value.parse::<f64>().map(|v| sign * v).map_err(|_| invalid(&format!("could not parse {} in TLE line {}", text, line)))
+}
+
+/// Parse the two lines of a two-line element set.
+///
+/// References:
+/// -----------
+/// CelesTrak. NORAD Two-Line Element Set Format. https://celestrak.org/NORAD/documentation/tle-fmt.php
+///
+pub fn parse_tle(name:&str, line1:&str, line2:&str) -> io::Result<Tle> {
+    if !line1.starts_with('1') || !line2.starts_with('2') || line1.len() < 62 || line2.len() < 63 {
+        return Err(invalid(&format!("malformed TLE for {}", name)));
+    }
+    let year = field(line1, 18, 20)? as i64;
+    let year = if year < 57 { year + 2000 } else { year + 1900 };
+    let days = field(line1, 20, 32)?;
+    let degrees = |a:usize, b:usize| field(line2, a, b).map(|v| v.to_radians());
+
+    Ok(Tle {
+        name: name.trim().to_string(),
+        satnum: line1[2..7].trim().to_string(),
+        epoch: time::julian_date(year, 1, 1, 0, 0, 0.0) + days - 1.0,
+        bstar: exponent_field(line1, 53, 61)?,
+        inclo: degrees(8, 16)?,
+        nodeo: degrees(17, 25)?,
+        ecco: field(line2, 26, 33)? * 1.0e-7,
+        argpo: degrees(34, 42)?,
+        mo: degrees(43, 51)?,
+        no_kozai: field(line2, 52, 63)? * TWOPI / 1440.0
+    })
+}
+
+/// Parse a file of two-line element sets, each optionally preceded by a name line.
+///
+pub fn parse(text:&str) -> io::Result<Vec<Tle>> {
+    let lines:Vec<&str> = text.lines().map(|l| l.trim_end()).filter(|l| !l.trim().is_empty()).collect();
+    let mut tles:Vec<Tle> = Vec::new();
+    let mut i:usize = 0;
+    while i < lines.len() {
+        if lines[i].starts_with("1 ") && i + 1 < lines.len() && lines[i + 1].starts_with("2 ") {
+            let name = if i > 0 && !lines[i - 1].starts_with("2 ") { lines[i - 1].trim_start_matches("0 ") } else { "" };
+            tles.push(parse_tle(name, lines[i], lines[i + 1])?);
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    if tles.is_empty() {
+        return Err(invalid("no two-line element sets found"));
+    }
+    Ok(tles)
+}
+
+/// Read a file of two-line element sets.
+///
+pub fn read(path:&str) -> io::Result<Vec<Tle>> {
+    let bytes = grid::contents(path)?;
+    parse(&String::from_utf8_lossy(&bytes))
+}
+
+/// Lunar-solar periodic coefficients of the deep space model.
+///
+#[derive(Copy, Clone, Debug, Default)]
+struct Periodics {
+    e3: f64, ee2: f64, se2: f64, se3: f64, sgh2: f64, sgh3: f64, sgh4: f64, sh2: f64, sh3: f64,
+    si2: f64, si3: f64, sl2: f64, sl3: f64, sl4: f64, xgh2: f64, xgh3: f64, xgh4: f64, xh2: f64,
+    xh3: f64, xi2: f64, xi3: f64, xl2: f64, xl3: f64, xl4: f64, zmol: f64, zmos: f64
+}
+
+/// Secular and resonance rates of the deep space model.
+///
+#[derive(Copy, Clone, Debug, Default)]
+struct Resonance {
+    irez: u8,
+    d2201: f64, d2211: f64, d3210: f64, d3222: f64, d4410: f64, d4422: f64, d5220: f64,
+    d5232: f64, d5421: f64, d5433: f64, dedt: f64, didt: f64, dmdt: f64, dnodt: f64, domdt: f64,
+    del1: f64, del2: f64, del3: f64, xfact: f64, xlamo: f64, gsto: f64
+}
+
+/// Lunar or solar terms of the deep space model for one orbit orientation.
+///
+#[derive(Copy, Clone, Debug, Default)]
+struct Terms {
+    s: [f64; 8],
+    z1: f64, z2: f64, z3: f64, z11: f64, z12: f64, z13: f64, z21: f64, z22: f64, z23: f64,
+    z31: f64, z32: f64, z33: f64
+}
+
+/// Satellite initialized for propagation by SGP4, or SDP4 for periods of 225 minutes and longer.
+///
+/// The implementation follows the revised code of Vallado et al., with the integrator restarted
+/// from epoch on every call so propagation has no hidden state.
+///
+/// References:
+/// -----------
+/// Hoots, F. R. and Roehrich, R. L. (1980). Spacetrack Report #3: Models for Propagation of NORAD
+/// Element Sets.
+///
+/// Vallado, D. A., Crawford, P., Hujsak, R. and Kelso, T. S. (2006). Revisiting Spacetrack
+/// Report #3. AIAA 2006-6753.
+///
+#[derive(Clone, Debug)]
+pub struct Satellite {
+    /// elements the satellite was initialized from
+    pub tle : Tle,
+    /// un-Kozai'd mean motion (radians/minute)
+    pub no : f64,
+    /// whether the deep space model is used
+    pub deep : bool,
+    isimp: bool,
+    aycof: f64, con41: f64, cc1: f64, cc4: f64, cc5: f64, d2: f64, d3: f64, d4: f64, delmo: f64,
+    eta: f64, argpdot: f64, omgcof: f64, sinmao: f64, t2cof: f64, t3cof: f64, t4cof: f64,
+    t5cof: f64, x1mth2: f64, x7thm1: f64, mdot: f64, nodedot: f64, xlcof: f64, xmcof: f64,
+    nodecf: f64,
+    periodics: Periodics,
+    resonance: Resonance
+}
+
+impl Satellite {
+
+    /// Initialize a satellite from its elements.
+    ///
+    pub fn new(tle:&Tle) -> io::Result<Satellite> {
+        let xke = xke();
+        let j3oj2 = J3 / J2;
+        let ss:f64 = 78.0 / RADIUS + 1.0;
+        let qzms2t:f64 = ((120.0 - 78.0) / RADIUS).powi(4);
+        let ecco = tle.ecco;
+
+        // recover the Brouwer mean motion from the Kozai mean motion
+        let eccsq = ecco * ecco;
+        let omeosq = 1.0 - eccsq;
+        let rteosq = omeosq.sqrt();
+        let cosio = tle.inclo.cos();
+        let cosio2 = cosio * cosio;
+        let ak = (xke / tle.no_kozai).powf(X2O3);
+        let d1 = 0.75 * J2 * (3.0 * cosio2 - 1.0) / (rteosq * omeosq);
+        let mut del = d1 / (ak * ak);
+        let adel = ak * (1.0 - del * del - del * (1.0 / 3.0 + 134.0 * del * del / 81.0));
+        del = d1 / (adel * adel);
+        let no = tle.no_kozai / (1.0 + del);
+        let ao = (xke / no).powf(X2O3);
+        let sinio = tle.inclo.sin();
+        let po = ao * omeosq;
+        let con42 = 1.0 - 5.0 * cosio2;
+        let con41 = -con42 - cosio2 - cosio2;
+        let posq = po * po;
+        let rp = ao * (1.0 - ecco);
+
+        if ecco < 0.0 || ecco >= 1.0 || no <= 0.0 {
+            return Err(invalid(&format!("elements of {} are not an elliptic orbit", tle.satnum)));
+        }
+
+        let mut isimp = rp < 220.0 / RADIUS + 1.0;
+        let mut sfour = ss;
+        let mut qzms24 = qzms2t;
+        let perige = (rp - 1.0) * RADIUS;
+        // for perigees below 156 km, s and qoms2t are altered
+        if perige < 156.0 {
+            sfour = if perige < 98.0 { 20.0 } else { perige - 78.0 };
+            qzms24 = ((120.0 - sfour) / RADIUS).powi(4);
+            sfour = sfour / RADIUS + 1.0;
+        }
+        let pinvsq = 1.0 / posq;
+        let tsi = 1.0 / (ao - sfour);
+        let eta = ao * ecco * tsi;
+        let etasq = eta * eta;
+        let eeta = ecco * eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qzms24 * tsi.powi(4);
+        let coef1 = coef / psisq.powf(3.5);
+        let cc2 = coef1 * no * (ao * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+            + 0.375 * J2 * tsi / psisq * con41 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        let cc1 = tle.bstar * cc2;
+        let cc3 = if ecco > 1.0e-4 { -2.0 * coef * tsi * j3oj2 * no * sinio / ecco } else { 0.0 };
+        let x1mth2 = 1.0 - cosio2;
+        let cc4 = 2.0 * no * coef1 * ao * omeosq * (eta * (2.0 + 0.5 * etasq) + ecco * (0.5 + 2.0 * etasq)
+            - J2 * tsi / (ao * psisq) * (-3.0 * con41 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+            + 0.75 * x1mth2 * (2.0 * etasq - eeta * (1.0 + etasq)) * (2.0 * tle.argpo).cos()));
+        let cc5 = 2.0 * coef1 * ao * omeosq * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
+        let cosio4 = cosio2 * cosio2;
+        let temp1 = 1.5 * J2 * pinvsq * no;
+        let temp2 = 0.5 * temp1 * J2 * pinvsq;
+        let temp3 = -0.46875 * J4 * pinvsq * pinvsq * no;
+        let mdot = no + 0.5 * temp1 * rteosq * con41 + 0.0625 * temp2 * rteosq * (13.0 - 78.0 * cosio2 + 137.0 * cosio4);
+        let argpdot = -0.5 * temp1 * con42 + 0.0625 * temp2 * (7.0 - 114.0 * cosio2 + 395.0 * cosio4)
+            + temp3 * (3.0 - 36.0 * cosio2 + 49.0 * cosio4);
+        let xhdot1 = -temp1 * cosio;
+        let nodedot = xhdot1 + (0.5 * temp2 * (4.0 - 19.0 * cosio2) + 2.0 * temp3 * (3.0 - 7.0 * cosio2)) * cosio;
+        let xpidot = argpdot + nodedot;
+        let omgcof = tle.bstar * cc3 * tle.argpo.cos();
+        let xmcof = if ecco > 1.0e-4 { -X2O3 * coef * tle.bstar / eeta } else { 0.0 };
+        let nodecf = 3.5 * omeosq * xhdot1 * cc1;
+        let t2cof = 1.5 * cc1;
+        // avoid a division by zero at 180 degrees inclination
+        let xlcof = -0.25 * j3oj2 * sinio * (3.0 + 5.0 * cosio) / (1.0 + cosio).abs().max(1.5e-12);
+        let aycof = -0.5 * j3oj2 * sinio;
+
+        let mut sat = Satellite {tle: tle.clone(), no: no, deep: false, isimp: isimp, aycof: aycof, con41: con41,
+            cc1: cc1, cc4: cc4, cc5: cc5, d2: 0.0, d3: 0.0, d4: 0.0, delmo: (1.0 + eta * tle.mo.cos()).powi(3),
+            eta: eta, argpdot: argpdot, omgcof: omgcof, sinmao: tle.mo.sin(), t2cof: t2cof, t3cof: 0.0,
+            t4cof: 0.0, t5cof: 0.0, x1mth2: x1mth2, x7thm1: 7.0 * cosio2 - 1.0, mdot: mdot, nodedot: nodedot,
+            xlcof: xlcof, xmcof: xmcof, nodecf: nodecf, periodics: Periodics::default(), resonance: Resonance::default()};
+
+        if TWOPI / no >= 225.0 {
+            sat.deep = true;
+            isimp = true;
+            sat.isimp = true;
+            let epoch = tle.epoch - 2433281.5;
+            let (sun, moon, periodics) = dscom(epoch, ecco, tle.argpo, tle.inclo, tle.nodeo, no);
+            sat.periodics = periodics;
+            sat.resonance = dsinit(&sat, &sun, &moon, eci::gmst(tle.epoch), xpidot);
+        }
+
+        if !isimp {
+            let cc1sq = cc1 * cc1;
+            sat.d2 = 4.0 * ao * tsi * cc1sq;
+            let temp = sat.d2 * tsi * cc1 / 3.0;
+            sat.d3 = (17.0 * ao + sfour) * temp;
+            sat.d4 = 0.5 * temp * ao * tsi * (221.0 * ao + 31.0 * sfour) * cc1;
+            sat.t3cof = sat.d2 + 2.0 * cc1sq;
+            sat.t4cof = 0.25 * (3.0 * sat.d3 + cc1 * (12.0 * sat.d2 + 10.0 * cc1sq));
+            sat.t5cof = 0.2 * (3.0 * sat.d4 + 12.0 * cc1 * sat.d3 + 6.0 * sat.d2 * sat.d2 + 15.0 * cc1sq * (2.0 * sat.d2 + cc1sq));
+        }
+
+        sat.propagate(0.0)?;
+        Ok(sat)
+    }
+
+    /// Propagate to a time in minutes since the element epoch, giving TEME position, in meters,
+    /// and velocity, in m/s.
+    ///
+    pub fn propagate(&self, tsince:f64) -> io::Result<([f64; 3], [f64; 3])> {
+        let xke = xke();
+        let j3oj2 = J3 / J2;
+        let vkmpersec = RADIUS * xke / 60.0;
+        let tle = &self.tle;
+        let t = tsince;
+
+        // secular gravity and atmospheric drag
+        let xmdf = tle.mo + self.mdot * t;
+        let argpdf = tle.argpo + self.argpdot * t;
+        let nodedf = tle.nodeo + self.nodedot * t;
+        let mut argpm = argpdf;
+        let mut mm = xmdf;
+        let t2 = t * t;
+        let mut nodem = nodedf + self.nodecf * t2;
+        let mut tempa = 1.0 - self.cc1 * t;
+        let mut tempe = tle.bstar * self.cc4 * t;
+        let mut templ = self.t2cof * t2;
+
+        if !self.isimp {
+            let delomg = self.omgcof * t;
+            let delm = self.xmcof * ((1.0 + self.eta * xmdf.cos()).powi(3) - self.delmo);
+            let temp = delomg + delm;
+            mm = xmdf + temp;
+            argpm = argpdf - temp;
+            let t3 = t2 * t;
+            let t4 = t3 * t;
+            tempa = tempa - self.d2 * t2 - self.d3 * t3 - self.d4 * t4;
+            tempe += tle.bstar * self.cc5 * (mm.sin() - self.sinmao);
+            templ += self.t3cof * t3 + t4 * (self.t4cof + t * self.t5cof);
+        }
+
+        let mut nm = self.no;
+        let mut em = tle.ecco;
+        let mut inclm = tle.inclo;
+        if self.deep {
+            dspace(&self.resonance, tle.argpo, self.argpdot, t, self.no,
+                &mut em, &mut argpm, &mut inclm, &mut mm, &mut nodem, &mut nm);
+        }
+
+        if nm <= 0.0 {
+            return Err(invalid(&format!("mean motion of {} is not positive", tle.satnum)));
+        }
+        let am = (xke / nm).powf(X2O3) * tempa * tempa;
+        nm = xke / am.powf(1.5);
+        em -= tempe;
+        if em >= 1.0 || em < -0.001 {
+            return Err(invalid(&format!("mean eccentricity of {} is out of range", tle.satnum)));
+        }
+        if em < 1.0e-6 {
+            em = 1.0e-6;
+        }
+        mm += self.no * templ;
+        let mut xlm = mm + argpm + nodem;
+
+        nodem %= TWOPI;
+        argpm %= TWOPI;
+        xlm %= TWOPI;
+        mm = (xlm - argpm - nodem) % TWOPI;
+
+        // lunar-solar periodics
+        let (mut ep, mut xincp, mut argpp, mut nodep, mut mp) = (em, inclm, argpm, nodem, mm);
+        let (mut aycof, mut xlcof) = (self.aycof, self.xlcof);
+        if self.deep {
+            dpper(&self.periodics, t, &mut ep, &mut xincp, &mut nodep, &mut argpp, &mut mp);
+            if xincp < 0.0 {
+                xincp = -xincp;
+                nodep += PI;
+                argpp -= PI;
+            }
+            if ep < 0.0 || ep > 1.0 {
+                return Err(invalid(&format!("perturbed eccentricity of {} is out of range", tle.satnum)));
+            }
+            let (sinip, cosip) = (xincp.sin(), xincp.cos());
+            aycof = -0.5 * j3oj2 * sinip;
+            xlcof = -0.25 * j3oj2 * sinip * (3.0 + 5.0 * cosip) / (1.0 + cosip).abs().max(1.5e-12);
+        }
+        let (sinip, cosip) = (xincp.sin(), xincp.cos());
+
+        // long period periodics
+        let axnl = ep * argpp.cos();
+        let temp = 1.0 / (am * (1.0 - ep * ep));
+        let aynl = ep * argpp.sin() + temp * aycof;
+        let xl = mp + argpp + nodep + temp * xlcof * axnl;
+
+        // solve Kepler's equation
+        let u = (xl - nodep) % TWOPI;
+        let mut eo1 = u;
+        let (mut sineo1, mut coseo1) = (0.0, 0.0);
+        for _ in 0..10 {
+            sineo1 = eo1.sin();
+            coseo1 = eo1.cos();
+            let mut tem5 = (u - aynl * coseo1 + axnl * sineo1 - eo1) / (1.0 - coseo1 * axnl - sineo1 * aynl);
+            tem5 = tem5.max(-0.95).min(0.95);
+            eo1 += tem5;
+            if tem5.abs() < 1.0e-12 {
+                break;
+            }
+        }
+
+        // short period preliminary quantities
+        let ecose = axnl * coseo1 + aynl * sineo1;
+        let esine = axnl * sineo1 - aynl * coseo1;
+        let el2 = axnl * axnl + aynl * aynl;
+        let pl = am * (1.0 - el2);
+        if pl < 0.0 {
+            return Err(invalid(&format!("semi-latus rectum of {} is negative", tle.satnum)));
+        }
+        let rl = am * (1.0 - ecose);
+        let rdotl = am.sqrt() * esine / rl;
+        let rvdotl = pl.sqrt() / rl;
+        let betal = (1.0 - el2).sqrt();
+        let temp = esine / (1.0 + betal);
+        let sinu = am / rl * (sineo1 - aynl - axnl * temp);
+        let cosu = am / rl * (coseo1 - axnl + aynl * temp);
+        let mut su = sinu.atan2(cosu);
+        let sin2u = (cosu + cosu) * sinu;
+        let cos2u = 1.0 - 2.0 * sinu * sinu;
+        let temp = 1.0 / pl;
+        let temp1 = 0.5 * J2 * temp;
+        let temp2 = temp1 * temp;
+
+        // short period periodics
+        let (con41, x1mth2, x7thm1) = if self.deep {
+            let cosisq = cosip * cosip;
+            (3.0 * cosisq - 1.0, 1.0 - cosisq, 7.0 * cosisq - 1.0)
+        } else {
+            (self.con41, self.x1mth2, self.x7thm1)
+        };
+        let mrt = rl * (1.0 - 1.5 * temp2 * betal * con41) + 0.5 * temp1 * x1mth2 * cos2u;
+        su -= 0.25 * temp2 * x7thm1 * sin2u;
+        let xnode = nodep + 1.5 * temp2 * cosip * sin2u;
+        let xinc = xincp + 1.5 * temp2 * cosip * sinip * cos2u;
+        let mvt = rdotl - nm * temp1 * x1mth2 * sin2u / xke;
+        let rvdot = rvdotl + nm * temp1 * (x1mth2 * cos2u + 1.5 * con41) / xke;
+
+        // orientation vectors
+        let (sinsu, cossu) = (su.sin(), su.cos());
+        let (snod, cnod) = (xnode.sin(), xnode.cos());
+        let (sini, cosi) = (xinc.sin(), xinc.cos());
+        let xmx = -snod * cosi;
+        let xmy = cnod * cosi;
+        let ux = [xmx * sinsu + cnod * cossu, xmy * sinsu + snod * cossu, sini * sinsu];
+        let vx = [xmx * cossu - cnod * sinsu, xmy * cossu - snod * sinsu, sini * cossu];
+
+        if mrt < 1.0 {
+            return Err(invalid(&format!("satellite {} has decayed", tle.satnum)));
+        }
+        let mut r = [0.0; 3];
+        let mut v = [0.0; 3];
+        for i in 0..3 {
+            r[i] = mrt * ux[i] * RADIUS * 1000.0;
+            v[i] = (mvt * ux[i] + rvdot * vx[i]) * vkmpersec * 1000.0;
+        }
+        Ok((r, v))
+    }
+
+    /// Propagate to a UTC Julian date, giving TEME position and velocity.
+    ///
+    pub fn at(&self, jd_utc:f64) -> io::Result<([f64; 3], [f64; 3])> {
+        self.propagate((jd_utc - self.tle.epoch) * 1440.0)
+    }
+
+    /// Calculate the ECEF position, in meters, at a UTC Julian date.
+    ///
+    /// Earth orientation is taken from the table where it covers the date, and as zero otherwise.
+    ///
+    pub fn ecef(&self, jd_utc:f64, leaps:&time::LeapSeconds, eop:Option<&eci::Eop>) -> io::Result<(f64, f64, f64)> {
+        let (r, _v) = self.at(jd_utc)?;
+        let tai_utc = leaps.tai_utc(jd_utc);
+        let epoch = eop.and_then(|eop| eop.epoch(jd_utc, tai_utc))
+            .unwrap_or_else(|| eci::Epoch::new(jd_utc, tai_utc, 0.0, 0.0, 0.0));
+        Ok(eci::teme2ecef(r[0], r[1], r[2], &epoch))
+    }
+}
+
+/// Ground station observing a satellite.
+///
+#[derive(Copy, Clone)]
+pub struct Observer {
+    /// longitude (radians)
+    pub lambda : f64,
+    /// geodetic latitude (radians)
+    pub phi : f64,
+    /// height above the ellipsoid (m)
+    pub h : f64,
+    /// ellipsoid of the geodetic coordinates
+    pub ellps : ellipsoid::Ellipsoid
+}
+
+impl Observer {
+
+    /// Calculate the azimuth and elevation, in radians, and range, in meters, of a satellite at a
+    /// UTC Julian date.
+    ///
+    pub fn look(&self, sat:&Satellite, jd_utc:f64, leaps:&time::LeapSeconds, eop:Option<&eci::Eop>) -> io::Result<(f64, f64, f64)> {
+        let (x, y, z) = sat.ecef(jd_utc, leaps, eop)?;
+        Ok(local::look_angles(x, y, z, self.lambda, self.phi, self.h, self.ellps))
+    }
+}
+
+/// Pass of a satellite above an observer's elevation mask.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Pass {
+    /// time the satellite rises above the mask, or the start of the window (Julian date, UTC)
+    pub rise : f64,
+    /// time of greatest elevation (Julian date, UTC)
+    pub culmination : f64,
+    /// time the satellite sets below the mask, or the end of the window (Julian date, UTC)
+    pub set : f64,
+    /// greatest elevation (radians)
+    pub max_elevation : f64,
+    /// azimuth at rise (radians)
+    pub rise_azimuth : f64,
+    /// azimuth at set (radians)
+    pub set_azimuth : f64
+}
+
+/// Predict the passes of a satellite above an elevation mask, in radians, between two UTC Julian
+/// dates.
+///
+/// The elevation is sampled every step seconds, and rise and set are refined by bisection to a
+/// millisecond, so passes shorter than the step may be missed. The step must be positive.
+///
+pub fn passes(sat:&Satellite, observer:&Observer, leaps:&time::LeapSeconds, eop:Option<&eci::Eop>,
+              start:f64, end:f64, step:f64, mask:f64) -> io::Result<Vec<Pass>> {
+    let elevation = |t:f64| observer.look(sat, t, leaps, eop).map(|look| look.1 - mask);
+    let crossing = |mut a:f64, mut b:f64| -> io::Result<f64> {
+        // a and b bracket a change of sign of the elevation above the mask
+        let rising = elevation(a)? < 0.0;
+        while (b - a) * 86400.0 > 1.0e-3 {
+            let m = 0.5 * (a + b);
+            if (elevation(m)? < 0.0) == rising { a = m } else { b = m }
+        }
+        Ok(0.5 * (a + b))
+    };
+    let peak = |mut a:f64, mut b:f64| -> io::Result<f64> {
+        // golden section search for the greatest elevation
+        let ratio:f64 = 0.5 * (5.0f64.sqrt() - 1.0);
+        while (b - a) * 86400.0 > 1.0e-2 {
+            let (c, d) = (b - ratio * (b - a), a + ratio * (b - a));
+            if elevation(c)? > elevation(d)? { b = d } else { a = c }
+        }
+        Ok(0.5 * (a + b))
+    };
+
+    if !(step > 0.0) {
+        return Err(invalid("the pass search step must be positive"));
+    }
+    let dt:f64 = step / 86400.0;
+    let mut found:Vec<Pass> = Vec::new();
+    let mut t = start;
+    let mut previous = elevation(t)?;
+    let mut rise = if previous >= 0.0 { Some(start) } else { None };
+    let mut best = (start, previous);
+    while t < end {
+        let next = (t + dt).min(end);
+        let value = elevation(next)?;
+        if previous < 0.0 && value >= 0.0 {
+            rise = Some(crossing(t, next)?);
+            best = (next, value);
+        }
+        if rise.is_some() && value > best.1 {
+            best = (next, value);
+        }
+        let setting = previous >= 0.0 && value < 0.0;
+        if let Some(r) = rise {
+            if setting || (next >= end && value >= 0.0) {
+                let set = if setting { crossing(t, next)? } else { end };
+                let culmination = peak((best.0 - dt).max(r), (best.0 + dt).min(set))?;
+                found.push(Pass {rise: r, culmination: culmination, set: set,
+                    max_elevation: elevation(culmination)? + mask,
+                    rise_azimuth: observer.look(sat, r, leaps, eop)?.0,
+                    set_azimuth: observer.look(sat, set, leaps, eop)?.0});
+                rise = None;
+            }
+        }
+        previous = value;
+        t = next;
+    }
+    Ok(found)
+}
+
+/// Calculate the terms of the deep space model for a perturbing body given by the cosines and
+/// sines of its argument g, inclination i and node h, and its coefficient cc.
+///
+fn terms(g:(f64, f64), i:(f64, f64), h:(f64, f64), cc:f64, nm:f64, em:f64, inclp:f64, argpp:f64) -> Terms {
+    let ((zcosg, zsing), (zcosi, zsini), (zcosh, zsinh)) = (g, i, h);
+    let (sinim, cosim) = (inclp.sin(), inclp.cos());
+    let (sinomm, cosomm) = (argpp.sin(), argpp.cos());
+    let emsq = em * em;
+    let betasq = 1.0 - emsq;
+    let rtemsq = betasq.sqrt();
+
+    let a1 = zcosg * zcosh + zsing * zcosi * zsinh;
+    let a3 = -zsing * zcosh + zcosg * zcosi * zsinh;
+    let a7 = -zcosg * zsinh + zsing * zcosi * zcosh;
+    let a8 = zsing * zsini;
+    let a9 = zsing * zsinh + zcosg * zcosi * zcosh;
+    let a10 = zcosg * zsini;
+    let a2 = cosim * a7 + sinim * a8;
+    let a4 = cosim * a9 + sinim * a10;
+    let a5 = -sinim * a7 + cosim * a8;
+    let a6 = -sinim * a9 + cosim * a10;
+
+    let x1 = a1 * cosomm + a2 * sinomm;
+    let x2 = a3 * cosomm + a4 * sinomm;
+    let x3 = -a1 * sinomm + a2 * cosomm;
+    let x4 = -a3 * sinomm + a4 * cosomm;
+    let x5 = a5 * sinomm;
+    let x6 = a6 * sinomm;
+    let x7 = a5 * cosomm;
+    let x8 = a6 * cosomm;
+
+    let z31 = 12.0 * x1 * x1 - 3.0 * x3 * x3;
+    let z32 = 24.0 * x1 * x2 - 6.0 * x3 * x4;
+    let z33 = 12.0 * x2 * x2 - 3.0 * x4 * x4;
+    let z1 = 3.0 * (a1 * a1 + a2 * a2) + z31 * emsq;
+    let z2 = 6.0 * (a1 * a3 + a2 * a4) + z32 * emsq;
+    let z3 = 3.0 * (a3 * a3 + a4 * a4) + z33 * emsq;
+    let s3 = cc / nm;
+    let s4 = s3 * rtemsq;
+
+    Terms {
+        s: [0.0, -15.0 * em * s4, -0.5 * s3 / rtemsq, s3, s4, x1 * x3 + x2 * x4, x2 * x3 + x1 * x4, x2 * x4 - x1 * x3],
+        z1: z1 + z1 + betasq * z31,
+        z2: z2 + z2 + betasq * z32,
+        z3: z3 + z3 + betasq * z33,
+        z11: -6.0 * a1 * a5 + emsq * (-24.0 * x1 * x7 - 6.0 * x3 * x5),
+        z12: -6.0 * (a1 * a6 + a3 * a5) + emsq * (-24.0 * (x2 * x7 + x1 * x8) - 6.0 * (x3 * x6 + x4 * x5)),
+        z13: -6.0 * a3 * a6 + emsq * (-24.0 * x2 * x8 - 6.0 * x4 * x6),
+        z21: 6.0 * a2 * a5 + emsq * (24.0 * x1 * x5 - 6.0 * x3 * x7),
+        z22: 6.0 * (a4 * a5 + a2 * a6) + emsq * (24.0 * (x2 * x5 + x1 * x6) - 6.0 * (x4 * x7 + x3 * x8)),
+        z23: 6.0 * a4 * a6 + emsq * (24.0 * x2 * x6 - 6.0 * x4 * x8),
+        z31: z31,
+        z32: z32,
+        z33: z33
+    }
+}
+
+/// Calculate the solar and lunar terms of the deep space model, and their periodic coefficients,
+/// at epoch in days from 1950 January 0.0 UT.
+///
+fn dscom(epoch:f64, ep:f64, argpp:f64, inclp:f64, nodep:f64, np:f64) -> (Terms, Terms, Periodics) {
+    let (zes, zel, c1ss, c1l) = (0.01675, 0.05490, 2.9864797e-6, 4.7968065e-7);
+    let (zsinis, zcosis, zcosgs, zsings) = (0.39785416, 0.91744867, 0.1945905, -0.98088458);
+    let (snodm, cnodm) = (nodep.sin(), nodep.cos());
+    let emsq = ep * ep;
+
+    // orientation of the lunar orbit
+    let day = epoch + 18261.5;
+    let xnodce = (4.5236020 - 9.2422029e-4 * day) % TWOPI;
+    let (stem, ctem) = (xnodce.sin(), xnodce.cos());
+    let zcosil = 0.91375164 - 0.03568096 * ctem;
+    let zsinil = (1.0 - zcosil * zcosil).sqrt();
+    let zsinhl = 0.089683511 * stem / zsinil;
+    let zcoshl = (1.0 - zsinhl * zsinhl).sqrt();
+    let gam = 5.8351514 + 0.0019443680 * day;
+    let zx = 0.39785416 * stem / zsinil;
+    let zy = zcoshl * ctem + 0.91744867 * zsinhl * stem;
+    let zx = gam + zx.atan2(zy) - xnodce;
+
+    let sun = terms((zcosgs, zsings), (zcosis, zsinis), (cnodm, snodm), c1ss, np, ep, inclp, argpp);
+    let moon = terms((zx.cos(), zx.sin()), (zcosil, zsinil),
+        (zcoshl * cnodm + zsinhl * snodm, snodm * zcoshl - cnodm * zsinhl), c1l, np, ep, inclp, argpp);
+    let (ss, s) = (sun.s, moon.s);
+
+    let p = Periodics {
+        zmol: (4.7199672 + 0.22997150 * day - gam) % TWOPI,
+        zmos: (6.2565837 + 0.017201977 * day) % TWOPI,
+        se2: 2.0 * ss[1] * ss[6],
+        se3: 2.0 * ss[1] * ss[7],
+        si2: 2.0 * ss[2] * sun.z12,
+        si3: 2.0 * ss[2] * (sun.z13 - sun.z11),
+        sl2: -2.0 * ss[3] * sun.z2,
+        sl3: -2.0 * ss[3] * (sun.z3 - sun.z1),
+        sl4: -2.0 * ss[3] * (-21.0 - 9.0 * emsq) * zes,
+        sgh2: 2.0 * ss[4] * sun.z32,
+        sgh3: 2.0 * ss[4] * (sun.z33 - sun.z31),
+        sgh4: -18.0 * ss[4] * zes,
+        sh2: -2.0 * ss[2] * sun.z22,
+        sh3: -2.0 * ss[2] * (sun.z23 - sun.z21),
+        ee2: 2.0 * s[1] * s[6],
+        e3: 2.0 * s[1] * s[7],
+        xi2: 2.0 * s[2] * moon.z12,
+        xi3: 2.0 * s[2] * (moon.z13 - moon.z11),
+        xl2: -2.0 * s[3] * moon.z2,
+        xl3: -2.0 * s[3] * (moon.z3 - moon.z1),
+        xl4: -2.0 * s[3] * (-21.0 - 9.0 * emsq) * zel,
+        xgh2: 2.0 * s[4] * moon.z32,
+        xgh3: 2.0 * s[4] * (moon.z33 - moon.z31),
+        xgh4: -18.0 * s[4] * zel,
+        xh2: -2.0 * s[2] * moon.z22,
+        xh3: -2.0 * s[2] * (moon.z23 - moon.z21)
+    };
+    (sun, moon, p)
+}
+
+/// Initialize the secular lunar-solar rates and the geopotential resonance terms for 12 and 24
+/// hour orbits.
+///
+fn dsinit(sat:&Satellite, sun:&Terms, moon:&Terms, gsto:f64, xpidot:f64) -> Resonance {
+    let (q22, q31, q33) = (1.7891679e-6, 2.1460748e-6, 2.2123015e-7);
+    let (root22, root44, root54) = (1.7891679e-6, 7.3636953e-9, 2.1765803e-9);
+    let (root32, root52) = (3.7393792e-7, 1.1428639e-7);
+    let (znl, zns) = (1.5835218e-4, 1.19459e-5);
+    let rptim = 4.37526908801129966e-3;
+    let tle = &sat.tle;
+    let (nm, em, inclm) = (sat.no, tle.ecco, tle.inclo);
+    let (sinim, cosim) = (inclm.sin(), inclm.cos());
+    let emsq = em * em;
+    let (ss, s) = (sun.s, moon.s);
+
+    let mut r = Resonance::default();
+    r.gsto = gsto;
+    if nm < 0.0052359877 && nm > 0.0034906585 {
+        r.irez = 1;
+    }
+    if nm >= 8.26e-3 && nm <= 9.24e-3 && em >= 0.5 {
+        r.irez = 2;
+    }
+
+    // solar terms
+    let equatorial = inclm < 5.2359877e-2 || inclm > PI - 5.2359877e-2;
+    let ses = ss[1] * zns * ss[5];
+    let sis = ss[2] * zns * (sun.z11 + sun.z13);
+    let sls = -zns * ss[3] * (sun.z1 + sun.z3 - 14.0 - 6.0 * emsq);
+    let sghs = ss[4] * zns * (sun.z31 + sun.z33 - 6.0);
+    let mut shs = if equatorial { 0.0 } else { -zns * ss[2] * (sun.z21 + sun.z23) };
+    if sinim != 0.0 {
+        shs /= sinim;
+    }
+    let sgs = sghs - cosim * shs;
+
+    // lunar terms
+    r.dedt = ses + s[1] * znl * s[5];
+    r.didt = sis + s[2] * znl * (moon.z11 + moon.z13);
+    r.dmdt = sls - znl * s[3] * (moon.z1 + moon.z3 - 14.0 - 6.0 * emsq);
+    let sghl = s[4] * znl * (moon.z31 + moon.z33 - 6.0);
+    let shll = if equatorial { 0.0 } else { -znl * s[2] * (moon.z21 + moon.z23) };
+    r.domdt = sgs + sghl;
+    r.dnodt = shs;
+    if sinim != 0.0 {
+        r.domdt -= cosim / sinim * shll;
+        r.dnodt += shll / sinim;
+    }
+
+    let theta = gsto % TWOPI;
+    let aonv = (nm / xke()).powf(X2O3);
+    if r.irez == 2 {
+        // geopotential resonance for 12 hour orbits
+        let cosisq = cosim * cosim;
+        let eoc = em * emsq;
+        let g201 = -0.306 - (em - 0.64) * 0.440;
+        let (g211, g310, g322, g410, g422, g520);
+        if em <= 0.65 {
+            g211 = 3.616 - 13.2470 * em + 16.2900 * emsq;
+            g310 = -19.302 + 117.3900 * em - 228.4190 * emsq + 156.5910 * eoc;
+            g322 = -18.9068 + 109.7927 * em - 214.6334 * emsq + 146.5816 * eoc;
+            g410 = -41.122 + 242.6940 * em - 471.0940 * emsq + 313.9530 * eoc;
+            g422 = -146.407 + 841.8800 * em - 1629.014 * emsq + 1083.4350 * eoc;
+            g520 = -532.114 + 3017.977 * em - 5740.032 * emsq + 3708.2760 * eoc;
+        } else {
+            g211 = -72.099 + 331.819 * em - 508.738 * emsq + 266.724 * eoc;
+            g310 = -346.844 + 1582.851 * em - 2415.925 * emsq + 1246.113 * eoc;
+            g322 = -342.585 + 1554.908 * em - 2366.899 * emsq + 1215.972 * eoc;
+            g410 = -1052.797 + 4758.686 * em - 7193.992 * emsq + 3651.957 * eoc;
+            g422 = -3581.690 + 16178.110 * em - 24462.770 * emsq + 12422.520 * eoc;
+            g520 = if em > 0.715 {
+                -5149.66 + 29936.92 * em - 54087.36 * emsq + 31324.56 * eoc
+            } else {
+                1464.74 - 4664.75 * em + 3763.64 * emsq
+            };
+        }
+        let (g533, g521, g532);
+        if em < 0.7 {
+            g533 = -919.22770 + 4988.6100 * em - 9064.7700 * emsq + 5542.21 * eoc;
+            g521 = -822.71072 + 4568.6173 * em - 8491.4146 * emsq + 5337.524 * eoc;
+            g532 = -853.66600 + 4690.2500 * em - 8624.7700 * emsq + 5341.4 * eoc;
+        } else {
+            g533 = -37995.780 + 161616.52 * em - 229838.20 * emsq + 109377.94 * eoc;
+            g521 = -51752.104 + 218913.95 * em - 309468.16 * emsq + 146349.42 * eoc;
+            g532 = -40023.880 + 170470.89 * em - 242699.48 * emsq + 115605.82 * eoc;
+        }
+
+        let sini2 = sinim * sinim;
+        let f220 = 0.75 * (1.0 + 2.0 * cosim + cosisq);
+        let f221 = 1.5 * sini2;
+        let f321 = 1.875 * sinim * (1.0 - 2.0 * cosim - 3.0 * cosisq);
+        let f322 = -1.875 * sinim * (1.0 + 2.0 * cosim - 3.0 * cosisq);
+        let f441 = 35.0 * sini2 * f220;
+        let f442 = 39.3750 * sini2 * sini2;
+        let f522 = 9.84375 * sinim * (sini2 * (1.0 - 2.0 * cosim - 5.0 * cosisq)
+            + 0.33333333 * (-2.0 + 4.0 * cosim + 6.0 * cosisq));
+        let f523 = sinim * (4.92187512 * sini2 * (-2.0 - 4.0 * cosim + 10.0 * cosisq)
+            + 6.56250012 * (1.0 + 2.0 * cosim - 3.0 * cosisq));
+        let f542 = 29.53125 * sinim * (2.0 - 8.0 * cosim + cosisq * (-12.0 + 8.0 * cosim + 10.0 * cosisq));
+        let f543 = 29.53125 * sinim * (-2.0 - 8.0 * cosim + cosisq * (12.0 + 8.0 * cosim - 10.0 * cosisq));
+
+        let mut temp1 = 3.0 * nm * nm * aonv * aonv;
+        let mut temp = temp1 * root22;
+        r.d2201 = temp * f220 * g201;
+        r.d2211 = temp * f221 * g211;
+        temp1 *= aonv;
+        temp = temp1 * root32;
+        r.d3210 = temp * f321 * g310;
+        r.d3222 = temp * f322 * g322;
+        temp1 *= aonv;
+        temp = 2.0 * temp1 * root44;
+        r.d4410 = temp * f441 * g410;
+        r.d4422 = temp * f442 * g422;
+        temp1 *= aonv;
+        temp = temp1 * root52;
+        r.d5220 = temp * f522 * g520;
+        r.d5232 = temp * f523 * g532;
+        temp = 2.0 * temp1 * root54;
+        r.d5421 = temp * f542 * g521;
+        r.d5433 = temp * f543 * g533;
+        r.xlamo = (tle.mo + tle.nodeo + tle.nodeo - theta - theta) % TWOPI;
+        r.xfact = sat.mdot + r.dmdt + 2.0 * (sat.nodedot + r.dnodt - rptim) - sat.no;
+    }
+    if r.irez == 1 {
+        // synchronous resonance terms
+        let g200 = 1.0 + emsq * (-2.5 + 0.8125 * emsq);
+        let g310 = 1.0 + 2.0 * emsq;
+        let g300 = 1.0 + emsq * (-6.0 + 6.60937 * emsq);
+        let f220 = 0.75 * (1.0 + cosim) * (1.0 + cosim);
+        let f311 = 0.9375 * sinim * sinim * (1.0 + 3.0 * cosim) - 0.75 * (1.0 + cosim);
+        let f330 = 1.875 * (1.0 + cosim).powi(3);
+        let del1 = 3.0 * nm * nm * aonv * aonv;
+        r.del2 = 2.0 * del1 * f220 * g200 * q22;
+        r.del3 = 3.0 * del1 * f330 * g300 * q33 * aonv;
+        r.del1 = del1 * f311 * g310 * q31 * aonv;
+        r.xlamo = (tle.mo + tle.nodeo + tle.argpo - theta) % TWOPI;
+        r.xfact = sat.mdot + xpidot - rptim + r.dmdt + r.domdt + r.dnodt - sat.no;
+    }
+    r
+}
+
+/// Apply the secular lunar-solar rates and integrate the resonance terms from epoch to t minutes.
+///
+fn dspace(r:&Resonance, argpo:f64, argpdot:f64, t:f64, no:f64, em:&mut f64, argpm:&mut f64, inclm:&mut f64,
+          mm:&mut f64, nodem:&mut f64, nm:&mut f64) {
+    let (fasx2, fasx4, fasx6) = (0.13130908, 2.8843198, 0.37448087);
+    let (g22, g32, g44, g52, g54) = (5.7686396, 0.95240898, 1.8014998, 1.0508330, 4.4108898);
+    let rptim = 4.37526908801129966e-3;
+    let (stepp, step2) = (720.0, 259200.0);
+
+    let theta = (r.gsto + t * rptim) % TWOPI;
+    *em += r.dedt * t;
+    *inclm += r.didt * t;
+    *argpm += r.domdt * t;
+    *nodem += r.dnodt * t;
+    *mm += r.dmdt * t;
+    if r.irez == 0 {
+        return;
+    }
+
+    // Euler-Maclaurin integration of the resonance in steps of 720 minutes from epoch
+    let delt:f64 = if t > 0.0 { stepp } else { -stepp };
+    let (mut atime, mut xni, mut xli) = (0.0, no, r.xlamo);
+    let (xndt, xldot, xnddt, ft);
+    loop {
+        let (ndt, ldot, nddt);
+        if r.irez != 2 {
+            // near synchronous resonance terms
+            ndt = r.del1 * (xli - fasx2).sin() + r.del2 * (2.0 * (xli - fasx4)).sin() + r.del3 * (3.0 * (xli - fasx6)).sin();
+            ldot = xni + r.xfact;
+            nddt = (r.del1 * (xli - fasx2).cos() + 2.0 * r.del2 * (2.0 * (xli - fasx4)).cos()
+                + 3.0 * r.del3 * (3.0 * (xli - fasx6)).cos()) * ldot;
+        } else {
+            // near half-day resonance terms
+            let xomi = argpo + argpdot * atime;
+            let x2omi = xomi + xomi;
+            let x2li = xli + xli;
+            ndt = r.d2201 * (x2omi + xli - g22).sin() + r.d2211 * (xli - g22).sin()
+                + r.d3210 * (xomi + xli - g32).sin() + r.d3222 * (-xomi + xli - g32).sin()
+                + r.d4410 * (x2omi + x2li - g44).sin() + r.d4422 * (x2li - g44).sin()
+                + r.d5220 * (xomi + xli - g52).sin() + r.d5232 * (-xomi + xli - g52).sin()
+                + r.d5421 * (xomi + x2li - g54).sin() + r.d5433 * (-xomi + x2li - g54).sin();
+            ldot = xni + r.xfact;
+            nddt = (r.d2201 * (x2omi + xli - g22).cos() + r.d2211 * (xli - g22).cos()
+                + r.d3210 * (xomi + xli - g32).cos() + r.d3222 * (-xomi + xli - g32).cos()
+                + r.d5220 * (xomi + xli - g52).cos() + r.d5232 * (-xomi + xli - g52).cos()
+                + 2.0 * (r.d4410 * (x2omi + x2li - g44).cos() + r.d4422 * (x2li - g44).cos()
+                + r.d5421 * (xomi + x2li - g54).cos() + r.d5433 * (-xomi + x2li - g54).cos())) * ldot;
+        }
+        if (t - atime).abs() < stepp {
+            xndt = ndt;
+            xldot = ldot;
+            xnddt = nddt;
+            ft = t - atime;
+            break;
+        }
+        xli += ldot * delt + ndt * step2;
+        xni += ndt * delt + nddt * step2;
+        atime += delt;
+    }
+
+    let n = xni + xndt * ft + xnddt * ft * ft * 0.5;
+    let xl = xli + xldot * ft + xndt * ft * ft * 0.5;
+    *mm = if r.irez != 1 { xl - 2.0 * *nodem + 2.0 * theta } else { xl - *nodem - *argpm + theta };
+    *nm = n;
+}
+
+/// Apply the lunar-solar periodics at t minutes to the mean elements, using Lyddane's
+/// modification for inclinations below 0.2 radians.
+///
+fn dpper(p:&Periodics, t:f64, ep:&mut f64, inclp:&mut f64, nodep:&mut f64, argpp:&mut f64, mp:&mut f64) {
+    let (zns, zes, znl, zel) = (1.19459e-5, 0.01675, 1.5835218e-4, 0.05490);
+
+    let zm = p.zmos + zns * t;
+    let zf = zm + 2.0 * zes * zm.sin();
+    let sinzf = zf.sin();
+    let f2 = 0.5 * sinzf * sinzf - 0.25;
+    let f3 = -0.5 * sinzf * zf.cos();
+    let ses = p.se2 * f2 + p.se3 * f3;
+    let sis = p.si2 * f2 + p.si3 * f3;
+    let sls = p.sl2 * f2 + p.sl3 * f3 + p.sl4 * sinzf;
+    let sghs = p.sgh2 * f2 + p.sgh3 * f3 + p.sgh4 * sinzf;
+    let shs = p.sh2 * f2 + p.sh3 * f3;
+    let zm = p.zmol + znl * t;
+    let zf = zm + 2.0 * zel * zm.sin();
+    let sinzf = zf.sin();
+    let f2 = 0.5 * sinzf * sinzf - 0.25;
+    let f3 = -0.5 * sinzf * zf.cos();
+    let sel = p.ee2 * f2 + p.e3 * f3;
+    let sil = p.xi2 * f2 + p.xi3 * f3;
+    let sll = p.xl2 * f2 + p.xl3 * f3 + p.xl4 * sinzf;
+    let sghl = p.xgh2 * f2 + p.xgh3 * f3 + p.xgh4 * sinzf;
+    let shll = p.xh2 * f2 + p.xh3 * f3;
+    let (pe, pinc, pl) = (ses + sel, sis + sil, sls + sll);
+    let (mut pgh, mut ph) = (sghs + sghl, shs + shll);
+
+    *inclp += pinc;
+    *ep += pe;
+    let (sinip, cosip) = (inclp.sin(), inclp.cos());
+    if *inclp >= 0.2 {
+        ph /= sinip;
+        pgh -= cosip * ph;
+        *argpp += pgh;
+        *nodep += ph;
+        *mp += pl;
+    } else {
+        let (sinop, cosop) = (nodep.sin(), nodep.cos());
+        let alfdp = sinip * sinop + ph * cosop + pinc * cosip * sinop;
+        let betdp = sinip * cosop - ph * sinop + pinc * cosip * cosop;
+        *nodep %= TWOPI;
+        let xls = *mp + *argpp + cosip * *nodep + pl + pgh - pinc * *nodep * sinip;
+        let xnoh = *nodep;
+        *nodep = alfdp.atan2(betdp);
+        if (xnoh - *nodep).abs() > PI {
+            if *nodep < xnoh { *nodep += TWOPI } else { *nodep -= TWOPI }
+        }
+        *mp += pl;
+        *argpp = xls - *mp - cosip * *nodep;
+    }
+}
+
+#[test]
+fn test_near_earth() {
+    // Vallado et al. (2006), verification case 00005
+    let tle = parse_tle("", "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753",
+                        "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667").unwrap();
+    assert!((tle.bstar - 0.28098e-4).abs() < 1.0e-12);
+    let sat = Satellite::new(&tle).unwrap();
+    assert!(!sat.deep);
+    let expected = [
+        (0.0, [7022.46529266, -1400.08296755, 0.03995155], [1.893841015, 6.405893759, 4.534807250]),
+        (360.0, [-7154.03120202, -3783.17682504, -3536.19412294], [4.741887409, -4.151817765, -2.093935425])
+    ];
+    for &(tsince, r0, v0) in expected.iter() {
+        let (r, v) = sat.propagate(tsince).unwrap();
+        println!("{} {:?} {:?}", tsince, r, v);
+        for i in 0..3 {
+            assert!((r[i] / 1000.0 - r0[i]).abs() < 1.0e-6);
+            assert!((v[i] / 1000.0 - v0[i]).abs() < 1.0e-9);
+        }
+    }
+}
+
+#[test]
+fn test_deep_space() {
+    // Vallado et al. (2006), verification case 11801, the deep space example of Spacetrack Report #3
+    let tles = parse("SL-6 R/B(2)\n\
+                      1 11801U          80230.29629788  .01431103  00000-0  14311-1      13\n\
+                      2 11801  46.7916 230.4354 7318036  47.4722  10.4117  2.28537848    13\n").unwrap();
+    assert_eq!(tles[0].name, "SL-6 R/B(2)");
+    let sat = Satellite::new(&tles[0]).unwrap();
+    assert!(sat.deep);
+    let (r, v) = sat.propagate(0.0).unwrap();
+    let (r0, v0) = ([7473.37102491, 428.94748312, 5828.74846783], [5.107155391, 6.444680305, -0.186133297]);
+    for i in 0..3 {
+        assert!((r[i] / 1000.0 - r0[i]).abs() < 1.0e-6);
+        assert!((v[i] / 1000.0 - v0[i]).abs() < 1.0e-9);
+    }
+    let (r, _v) = sat.propagate(720.0).unwrap();
+    let r720 = [14271.29083858, 24110.44309009, -4725.76320143];
+    for i in 0..3 {
+        assert!((r[i] / 1000.0 - r720[i]).abs() < 1.0e-6);
+    }
+
+    // the orbit stays between perigee and apogee radii over several days
+    let a:f64 = (xke() / sat.no).powf(X2O3) * RADIUS * 1000.0;
+    for day in 1..5 {
+        let (r, _v) = sat.propagate(1440.0 * day as f64).unwrap();
+        let radius = (r[0] * r[0] + r[1] * r[1] + r[2] * r[2]).sqrt();
+        assert!(radius > 0.95 * a * (1.0 - tles[0].ecco) && radius < 1.05 * a * (1.0 + tles[0].ecco));
+    }
+
+    // a Molniya orbit in half-day resonance and a geostationary orbit in synchronous resonance
+    let molniya = parse_tle("", "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813",
+                            "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656").unwrap();
+    let geo = parse_tle("", "1 28626U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2190",
+                        "2 28626   0.0019 286.9433 0000335  13.7918  55.6504  1.00271173  4999").unwrap();
+    for &(tle, irez) in [(&molniya, 2u8), (&geo, 1u8)].iter() {
+        let sat = Satellite::new(tle).unwrap();
+        assert_eq!(sat.resonance.irez, irez);
+        let a:f64 = (xke() / sat.no).powf(X2O3) * RADIUS * 1000.0;
+        for &minutes in [-1440.0, 720.0, 5000.0, 14400.0].iter() {
+            let (r, _v) = sat.propagate(minutes).unwrap();
+            let radius = (r[0] * r[0] + r[1] * r[1] + r[2] * r[2]).sqrt();
+            assert!(radius > 0.95 * a * (1.0 - tle.ecco) && radius < 1.05 * a * (1.0 + tle.ecco));
+        }
+    }
+}
+
+#[test]
+fn test_passes() {
+    let tle = parse_tle("", "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753",
+                        "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667").unwrap();
+    let sat = Satellite::new(&tle).unwrap();
+    let leaps = time::LeapSeconds::builtin();
+    let observer = Observer {lambda: (-77.0f64).to_radians(), phi: 38.9f64.to_radians(), h: 50.0,
+        ellps: ellipsoid::named("WGS84").unwrap()};
+    let mask:f64 = 10.0f64.to_radians();
+    let found = passes(&sat, &observer, &leaps, None, tle.epoch, tle.epoch + 1.0, 60.0, mask).unwrap();
+    assert!(!found.is_empty());
+    for pass in found.iter() {
+        assert!(pass.rise < pass.culmination && pass.culmination < pass.set);
+        assert!(pass.max_elevation > mask);
+        // the mask is crossed at rise and set, and culmination is a maximum
+        for &t in [pass.rise, pass.set].iter() {
+            if t > tle.epoch && t < tle.epoch + 1.0 {
+                assert!((observer.look(&sat, t, &leaps, None).unwrap().1 - mask).abs() < 1.0e-4);
+            }
+        }
+        let near = observer.look(&sat, pass.culmination + 5.0 / 86400.0, &leaps, None).unwrap().1;
+        assert!(near <= pass.max_elevation + 1.0e-9);
+    }
+    assert!(passes(&sat, &observer, &leaps, None, tle.epoch, tle.epoch + 1.0, 0.0, mask).is_err());
+    assert!(passes(&sat, &observer, &leaps, None, tle.epoch, tle.epoch + 1.0, -60.0, mask).is_err());
+}
+
+#[test]
+fn test_ground_track() {
+    use convert;
+
+    // the geodetic track of case 00005 over a day, half of it at negative x
+    let tle = parse_tle("", "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753",
+                        "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667").unwrap();
+    let sat = Satellite::new(&tle).unwrap();
+    let leaps = time::LeapSeconds::builtin();
+    let wgs84 = ellipsoid::named("WGS84").unwrap();
+    let mut west = 0;
+    for k in 0..144 {
+        let jd = tle.epoch + k as f64 / 144.0;
+        let (x, y, z) = sat.ecef(jd, &leaps, None).unwrap();
+        let (lon, lat, hae) = convert::ecef2lla(x, y, z, wgs84, "olson");
+        let (x2, y2, z2) = convert::lla2ecef(lon.to_radians(), lat.to_radians(), hae, wgs84);
+        assert!((x2 - x).abs() < 1.0e-3 && (y2 - y).abs() < 1.0e-3 && (z2 - z).abs() < 1.0e-3);
+        if x < 0.0 {
+            west += 1;
+            assert!(lon.abs() > 90.0);
+        }
+    }
+    assert!(west > 0);
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+use std::io;
+
+use grid;
+use grid::invalid;
+use time;
+
+/// Sentinel of a missing clock value (microseconds).
+///
+const BAD_CLOCK:f64 = 999999.0;
+
+/// A precise position and clock of a satellite at an epoch of an SP3 file.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Sample {
+    /// epoch in seconds since 1980-01-06 00:00 in the file's time system
+    pub t : f64,
+    /// ECEF position (m)
+    pub x : f64,
+    pub y : f64,
+    pub z : f64,
+    /// clock offset (s), if given
+    pub clock : Option<f64>
+}
+
+/// Precise satellite orbits of an SP3-c or SP3-d file.
+///
+#[derive(Clone, Debug)]
+pub struct Orbits {
+    /// format version, c or d
+    pub version : char,
+    /// time system of the epochs, e.g. GPS, GAL, BDT, UTC, TAI
+    pub time_system : String,
+    /// coordinate system label, e.g. IGS14
+    pub coordinate_system : String,
+    /// epoch interval (s)
+    pub interval : f64,
+    /// samples of each satellite in time order, missing positions left out
+    pub satellites : BTreeMap<String, Vec<Sample>>
+}
+
+/// Seconds since the GPS epoch of a calendar date, without the rounding of a Julian date.
+///
+fn seconds(year:i64, month:i64, day:i64, hour:i64, minute:i64, second:f64) -> f64 {
+    (time::julian_date(year, month, day, 0, 0, 0.0) - time::GPS0) * 86400.0
+        + (hour * 3600 + minute * 60) as f64 + second
+}
+
+/// Parse a fixed width number of an SP3 line.
+///
+fn number(line:&str, a:usize, b:usize) -> io::Result<f64> {
+    let text = line.get(a..b.min(line.len())).unwrap_or("").trim();
+    text.parse::<f64>().map_err(|_| invalid(&format!("could not parse {} in SP3 line {}", text, line)))
+}
+
+/// Parse an SP3-c or SP3-d precise orbit file.
+///
+/// Positions are converted from kilometers to meters and clocks from microseconds to seconds.
+/// Positions of zero and clocks of 999999.999999 mark missing values.
+///
+/// References:
+/// -----------
+/// Hilla, S. (2016). The Extended Standard Product 3 Orbit Format (SP3-d).
+/// https://files.igs.org/pub/data/format/sp3d.pdf
+///
+pub fn parse(text:&str) -> io::Result<Orbits> {
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or("");
+    let version = first.chars().nth(1).unwrap_or(' ');
+    if !first.starts_with('#') || (version != 'c' && version != 'd') {
+        return Err(invalid("file is not SP3-c or SP3-d"));
+    }
+    let coordinate_system = first.get(46..51).unwrap_or("").trim().to_string();
+    let mut orbits = Orbits {version: version, time_system: "GPS".to_string(), coordinate_system: coordinate_system,
+        interval: 0.0, satellites: BTreeMap::new()};
+
+    let mut epoch:Option<f64> = None;
+    let mut time_systems = 0;
+    for line in lines {
+        if line.starts_with("##") {
+            orbits.interval = number(line, 24, 38)?;
+        } else if line.starts_with("%c") {
+            // the first %c line names the time system
+            if time_systems == 0 {
+                let system = line.get(9..12).unwrap_or("").trim();
+                if !system.is_empty() && system != "ccc" {
+                    orbits.time_system = system.to_string();
+                }
+            }
+            time_systems += 1;
+        } else if line.starts_with('*') {
+            let date:Vec<&str> = line[1..].split_whitespace().collect();
+            let fields:Vec<f64> = date.iter().filter_map(|v| v.parse().ok()).collect();
+            if fields.len() != 6 {
+                return Err(invalid(&format!("could not parse the epoch {}", line)));
+            }
+            epoch = Some(seconds(fields[0] as i64, fields[1] as i64, fields[2] as i64, fields[3] as i64,
+                fields[4] as i64, fields[5]));
+        } else if line.starts_with('P') {
+            let t = epoch.ok_or_else(|| invalid("position record before the first epoch"))?;
+            let sat = line.get(1..4).unwrap_or("").replace(' ', "0");
+            let (x, y, z) = (number(line, 4, 18)?, number(line, 18, 32)?, number(line, 32, 46)?);
+            if x == 0.0 && y == 0.0 && z == 0.0 {
+                continue;
+            }
+            let clock = match number(line, 46, 60) {
+                Ok(c) if c < BAD_CLOCK => Some(c * 1.0e-6),
+                _ => None
+            };
+            orbits.satellites.entry(sat).or_insert_with(Vec::new)
+                .push(Sample {t: t, x: x * 1000.0, y: y * 1000.0, z: z * 1000.0, clock: clock});
+        } else if line.starts_with("EOF") {
+            break;
+        }
+    }
+    Ok(orbits)
+}
+
+/// Read an SP3-c or SP3-d precise orbit file.
+///
+pub fn read(path:&str) -> io::Result<Orbits> {
+    let bytes = grid::contents(path)?;
+    parse(&String::from_utf8_lossy(&bytes))
+}
+
+/// Evaluate the Lagrange polynomial through points (ts, ys) at t.
+///
+pub fn lagrange(ts:&[f64], ys:&[f64], t:f64) -> f64 {
+    let mut sum:f64 = 0.0;
+    for i in 0..ts.len() {
+        let mut l:f64 = 1.0;
+        for j in 0..ts.len() {
+            if i != j {
+                l *= (t - ts[j]) / (ts[i] - ts[j]);
+            }
+        }
+        sum += l * ys[i];
+    }
+    sum
+}
+
+/// Evaluate the polynomial through points (ts, ys) at t by Neville's algorithm.
+///
+pub fn neville(ts:&[f64], ys:&[f64], t:f64) -> f64 {
+    let mut p:Vec<f64> = ys.to_vec();
+    let n = ts.len();
+    for k in 1..n {
+        for i in 0..n - k {
+            p[i] = ((t - ts[i + k]) * p[i] + (ts[i] - t) * p[i + 1]) / (ts[i] - ts[i + k]);
+        }
+    }
+    p[0]
+}
+
+impl Orbits {
+
+    /// Convert a UTC Julian date to seconds in the time system of the file.
+    ///
+    /// GLONASS time is UTC(SU), ahead of UTC by three hours.
+    ///
+    pub fn seconds(&self, jd_utc:f64, leaps:&time::LeapSeconds) -> f64 {
+        let tai = time::utc2tai(jd_utc, leaps);
+        let gps = (time::tai2gps(tai) - time::GPS0) * 86400.0;
+        match self.time_system.as_str() {
+            "UTC" => (jd_utc - time::GPS0) * 86400.0,
+            "GLO" => (jd_utc - time::GPS0) * 86400.0 + 3.0 * 3600.0,
+            "TAI" => gps + time::TAI_GPS,
+            "BDT" => gps - 14.0,
+            _ => gps
+        }
+    }
+
+    /// Interpolate the ECEF position, in meters, and clock offset, in seconds, of a satellite at t,
+    /// in seconds of the file's time system, from the window of samples around it.
+    ///
+    /// The method is lagrange or neville; others are an error. None is returned outside the
+    /// samples or across gaps.
+    ///
+    pub fn position(&self, sat:&str, t:f64, window:usize, method:&str) -> io::Result<Option<(f64, f64, f64, Option<f64>)>> {
+        match method {
+            "lagrange" | "neville" => Ok(self.interpolate(sat, t, window, method)),
+            _ => Err(invalid(&format!("unknown interpolation {}", method)))
+        }
+    }
+
+    /// Interpolate a satellite by a known method, or None outside the samples or across gaps.
+    ///
+    fn interpolate(&self, sat:&str, t:f64, window:usize, method:&str) -> Option<(f64, f64, f64, Option<f64>)> {
+        let samples = self.satellites.get(sat)?;
+        let n = window.max(2);
+        if samples.len() < n || t < samples[0].t || t > samples[samples.len() - 1].t {
+            return None;
+        }
+        // center the window on t, shifting it inward at the ends of the file
+        let after = samples.iter().position(|s| s.t >= t).unwrap_or(samples.len() - 1);
+        let start = after.saturating_sub(n / 2).min(samples.len() - n);
+        let used = &samples[start..start + n];
+        if self.interval > 0.0 && used[n - 1].t - used[0].t > (n as f64 - 0.5) * self.interval {
+            return None;
+        }
+
+        // times relative to the first sample keep the polynomial well conditioned
+        let ts:Vec<f64> = used.iter().map(|s| s.t - used[0].t).collect();
+        let dt = t - used[0].t;
+        let interpolate = |ys:Vec<f64>| match method {
+            "neville" => neville(&ts, &ys, dt),
+            _ => lagrange(&ts, &ys, dt)
+        };
+        let x = interpolate(used.iter().map(|s| s.x).collect());
+        let y = interpolate(used.iter().map(|s| s.y).collect());
+        let z = interpolate(used.iter().map(|s| s.z).collect());
+
+        // clocks are not smooth enough for high orders, so they are interpolated linearly
+        let clock = match (samples[after.saturating_sub(1)].clock, samples[after].clock) {
+            (Some(c0), Some(c1)) if after > 0 => {
+                let (t0, t1) = (samples[after - 1].t, samples[after].t);
+                Some(c0 + (c1 - c0) * (t - t0) / (t1 - t0))
+            },
+            (_, Some(c1)) if samples[after].t == t => Some(c1),
+            _ => None
+        };
+        Some((x, y, z, clock))
+    }
+}
+
+#[test]
+fn test_interpolation() {
+    // a circular orbit of 26560 km in an Earth-fixed frame, sampled every 15 minutes
+    let orbit = |t:f64| {
+        let (a, n, i) = (26560.0e3f64, 1.4585e-4f64 - 7.2921151467e-5, 55.0f64.to_radians());
+        let u = n * t;
+        (a * u.cos(), a * u.sin() * i.cos(), a * u.sin() * i.sin())
+    };
+    let mut text = String::from("\
+#dP2020  1  1  0  0  0.00000000      24 ORBIT IGS14 HLM  IGS
+## 2086 259200.00000000   900.00000000 58849 0.0000000000000
++    1   G01  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+%c G  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+/* synthetic orbit
+");
+    for k in 0..24 {
+        let (x, y, z) = orbit(900.0 * k as f64);
+        text.push_str(&format!("*  2020  1  1 {:2} {:2}  0.00000000\n", k / 4, 15 * (k % 4)));
+        text.push_str(&format!("PG01{:14.6}{:14.6}{:14.6}{:14.6}\n", x / 1000.0, y / 1000.0, z / 1000.0,
+            if k == 5 { 999999.999999 } else { 100.0 + k as f64 }));
+    }
+    text.push_str("EOF\n");
+
+    let orbits = parse(&text).unwrap();
+    assert_eq!(orbits.version, 'd');
+    assert_eq!(orbits.time_system, "GPS");
+    assert_eq!(orbits.coordinate_system, "IGS14");
+    assert_eq!(orbits.interval, 900.0);
+    let samples = &orbits.satellites["G01"];
+    assert_eq!(samples.len(), 24);
+    assert!(samples[5].clock.is_none());
+    let t0 = samples[0].t;
+    assert!((t0 - (2086.0 * 604800.0 + 259200.0)).abs() < 1.0e-6);
+
+    for &dt in [450.0, 5555.5, 20250.0, 100.0].iter() {
+        let (x, y, z) = orbit(dt);
+        for method in ["lagrange", "neville"].iter() {
+            let (xi, yi, zi, _) = orbits.position("G01", t0 + dt, 10, method).unwrap().unwrap();
+            assert!((xi - x).abs() < 0.01 && (yi - y).abs() < 0.01 && (zi - z).abs() < 0.01);
+        }
+    }
+    let (_, _, _, clock) = orbits.position("G01", t0 + 450.0, 10, "lagrange").unwrap().unwrap();
+    assert!((clock.unwrap() - 100.5e-6).abs() < 1.0e-12);
+    assert!(orbits.position("G01", t0 + 4000.0, 10, "lagrange").unwrap().unwrap().3.is_none());
+    assert!(orbits.position("G01", t0 - 1.0, 10, "lagrange").unwrap().is_none());
+    assert!(orbits.position("G02", t0, 10, "lagrange").unwrap().is_none());
+    assert!(orbits.position("G01", t0, 10, "spline").is_err());
+
+    // 2020-01-01 is 18 s of GPS time past UTC
+    let (leaps, jd) = (time::LeapSeconds::builtin(), time::julian_date(2020, 1, 1, 0, 0, 0.0));
+    let utc = (jd - time::GPS0) * 86400.0;
+    let mut orbits = orbits;
+    for &(system, offset) in [("GPS", 18.0), ("GAL", 18.0), ("TAI", 37.0), ("BDT", 4.0), ("UTC", 0.0), ("GLO", 10800.0)].iter() {
+        orbits.time_system = system.to_string();
+        assert!((orbits.seconds(jd, &leaps) - utc - offset).abs() < 1.0e-3);
+    }
+}
@@ -0,0 +1,148 @@
+use ellipsoid;
+use lcc;
+
+/// State Plane Coordinate System of 1983 zone definition for a Lambert Conformal Conic zone.
+///
+/// Angles are given in decimal degrees and false origins in meters.
+///
+#[derive(Copy, Clone)]
+pub struct Zone {
+    /// FIPS zone code
+    pub code : &'static str,
+    /// zone name
+    pub name : &'static str,
+    /// first (northern) standard parallel
+    pub phi1 : f64,
+    /// second (southern) standard parallel
+    pub phi2 : f64,
+    /// latitude of the false origin
+    pub phi0 : f64,
+    /// central meridian
+    pub lambda0 : f64,
+    /// false easting (m)
+    pub x0 : f64,
+    /// false northing (m)
+    pub y0 : f64
+}
+
+impl Zone {
+
+    /// Construct the zone projection on an ellipsoid, normally GRS80.
+    ///
+    pub fn projection(&self, ellps:ellipsoid::Ellipsoid) -> lcc::LambertConformalConic {
+        lcc::LambertConformalConic::new_2sp(ellps, self.lambda0.to_radians(), self.phi0.to_radians(),
+            self.phi1.to_radians(), self.phi2.to_radians(), self.x0, self.y0)
+    }
+}
+
+macro_rules! zone {
+    ($code:expr, $name:expr, $phi1:expr, $phi2:expr, $phi0:expr, $lambda0:expr, $x0:expr, $y0:expr) => (
+        Zone { code: $code, name: $name, phi1: $phi1, phi2: $phi2, phi0: $phi0, lambda0: $lambda0, x0: $x0, y0: $y0 }
+    )
+}
+
+/// SPCS83 zones using the Lambert Conformal Conic projection.
+///
+/// Zones on the Transverse Mercator and Oblique Mercator projections are not listed.
+///
+/// References:
+/// -----------
+/// Stem, J. E. (1989). State Plane Coordinate System of 1983. NOAA Manual
+/// NOS NGS 5.
+///
+pub static ZONES: &'static [Zone] = &[
+    zone!("5010", "Alaska 10", 53.83333333333334, 51.83333333333334, 51.0, -176.0, 1000000.0, 0.0),
+    zone!("0301", "Arkansas North", 36.23333333333333, 34.93333333333333, 34.33333333333334, -92.0, 400000.0, 0.0),
+    zone!("0302", "Arkansas South", 34.76666666666667, 33.3, 32.66666666666666, -92.0, 400000.0, 400000.0),
+    zone!("0401", "California 1", 41.66666666666666, 40.0, 39.33333333333334, -122.0, 2000000.0, 500000.0),
+    zone!("0402", "California 2", 39.83333333333334, 38.33333333333334, 37.66666666666666, -122.0, 2000000.0, 500000.0),
+    zone!("0403", "California 3", 38.43333333333333, 37.06666666666667, 36.5, -120.5, 2000000.0, 500000.0),
+    zone!("0404", "California 4", 37.25, 36.0, 35.33333333333334, -119.0, 2000000.0, 500000.0),
+    zone!("0405", "California 5", 35.46666666666667, 34.03333333333333, 33.5, -118.0, 2000000.0, 500000.0),
+    zone!("0406", "California 6", 33.88333333333333, 32.78333333333333, 32.16666666666666, -116.25, 2000000.0, 500000.0),
+    zone!("0501", "Colorado North", 40.78333333333333, 39.71666666666667, 39.33333333333334, -105.5, 914401.8289, 304800.6096),
+    zone!("0502", "Colorado Central", 39.75, 38.45, 37.83333333333334, -105.5, 914401.8289, 304800.6096),
+    zone!("0503", "Colorado South", 38.43333333333333, 37.23333333333333, 36.66666666666666, -105.5, 914401.8289, 304800.6096),
+    zone!("0600", "Connecticut", 41.86666666666667, 41.2, 40.83333333333334, -72.75, 304800.6096, 152400.3048),
+    zone!("0903", "Florida North", 30.75, 29.58333333333333, 29.0, -84.5, 600000.0, 0.0),
+    zone!("1401", "Iowa North", 43.26666666666667, 42.06666666666667, 41.5, -93.5, 1500000.0, 1000000.0),
+    zone!("1402", "Iowa South", 41.78333333333333, 40.61666666666667, 40.0, -93.5, 500000.0, 0.0),
+    zone!("1501", "Kansas North", 39.78333333333333, 38.71666666666667, 38.33333333333334, -98.0, 400000.0, 0.0),
+    zone!("1502", "Kansas South", 38.56666666666667, 37.26666666666667, 36.66666666666666, -98.5, 400000.0, 400000.0),
+    zone!("1600", "Kentucky Single Zone", 37.08333333333334, 38.66666666666666, 36.33333333333334, -85.75, 1500000.0, 1000000.0),
+    zone!("1601", "Kentucky North", 37.96666666666667, 38.96666666666667, 37.5, -84.25, 500000.0, 0.0),
+    zone!("1602", "Kentucky South", 37.93333333333333, 36.73333333333333, 36.33333333333334, -85.75, 500000.0, 500000.0),
+    zone!("1701", "Louisiana North", 32.66666666666666, 31.16666666666667, 30.5, -92.5, 1000000.0, 0.0),
+    zone!("1702", "Louisiana South", 30.7, 29.3, 28.5, -91.33333333333333, 1000000.0, 0.0),
+    zone!("1703", "Louisiana Offshore", 27.83333333333333, 26.16666666666667, 25.5, -91.33333333333333, 1000000.0, 0.0),
+    zone!("1900", "Maryland", 39.45, 38.3, 37.66666666666666, -77.0, 400000.0, 0.0),
+    zone!("2001", "Massachusetts Mainland", 42.68333333333333, 41.71666666666667, 41.0, -71.5, 200000.0, 750000.0),
+    zone!("2002", "Massachusetts Island", 41.48333333333333, 41.28333333333333, 41.0, -70.5, 500000.0, 0.0),
+    zone!("2111", "Michigan North", 47.08333333333334, 45.48333333333333, 44.78333333333333, -87.0, 8000000.0, 0.0),
+    zone!("2112", "Michigan Central", 45.7, 44.18333333333333, 43.31666666666667, -84.36666666666666, 6000000.0, 0.0),
+    zone!("2113", "Michigan South", 43.66666666666666, 42.1, 41.5, -84.36666666666666, 4000000.0, 0.0),
+    zone!("2201", "Minnesota North", 48.63333333333333, 47.03333333333333, 46.5, -93.1, 800000.0, 100000.0),
+    zone!("2202", "Minnesota Central", 47.05, 45.61666666666667, 45.0, -94.25, 800000.0, 100000.0),
+    zone!("2203", "Minnesota South", 45.21666666666667, 43.78333333333333, 43.0, -94.0, 800000.0, 100000.0),
+    zone!("2500", "Montana", 49.0, 45.0, 44.25, -109.5, 600000.0, 0.0),
+    zone!("2600", "Nebraska", 43.0, 40.0, 39.83333333333334, -100.0, 500000.0, 0.0),
+    zone!("3104", "New York Long Island", 41.03333333333333, 40.66666666666666, 40.16666666666666, -74.0, 300000.0, 0.0),
+    zone!("3200", "North Carolina", 36.16666666666666, 34.33333333333334, 33.75, -79.0, 609601.22, 0.0),
+    zone!("3301", "North Dakota North", 48.73333333333333, 47.43333333333333, 47.0, -100.5, 600000.0, 0.0),
+    zone!("3302", "North Dakota South", 47.48333333333333, 46.18333333333333, 45.66666666666666, -100.5, 600000.0, 0.0),
+    zone!("3401", "Ohio North", 41.7, 40.43333333333333, 39.66666666666666, -82.5, 600000.0, 0.0),
+    zone!("3402", "Ohio South", 40.03333333333333, 38.73333333333333, 38.0, -82.5, 600000.0, 0.0),
+    zone!("3501", "Oklahoma North", 36.76666666666667, 35.56666666666667, 35.0, -98.0, 600000.0, 0.0),
+    zone!("3502", "Oklahoma South", 35.23333333333333, 33.93333333333333, 33.33333333333334, -98.0, 600000.0, 0.0),
+    zone!("3601", "Oregon North", 46.0, 44.33333333333334, 43.66666666666666, -120.5, 2500000.0, 0.0),
+    zone!("3602", "Oregon South", 44.0, 42.33333333333334, 41.66666666666666, -120.5, 1500000.0, 0.0),
+    zone!("3701", "Pennsylvania North", 41.95, 40.88333333333333, 40.16666666666666, -77.75, 600000.0, 0.0),
+    zone!("3702", "Pennsylvania South", 40.96666666666667, 39.93333333333333, 39.33333333333334, -77.75, 600000.0, 0.0),
+    zone!("3900", "South Carolina", 34.83333333333334, 32.5, 31.83333333333333, -81.0, 609600.0, 0.0),
+    zone!("4001", "South Dakota North", 45.68333333333333, 44.41666666666666, 43.83333333333334, -100.0, 600000.0, 0.0),
+    zone!("4002", "South Dakota South", 44.4, 42.83333333333334, 42.33333333333334, -100.33333333333333, 600000.0, 0.0),
+    zone!("4100", "Tennessee", 36.41666666666666, 35.25, 34.33333333333334, -86.0, 600000.0, 0.0),
+    zone!("4201", "Texas North", 36.18333333333333, 34.65, 34.0, -101.5, 200000.0, 1000000.0),
+    zone!("4202", "Texas North Central", 33.96666666666667, 32.13333333333333, 31.66666666666667, -98.5, 600000.0, 2000000.0),
+    zone!("4203", "Texas Central", 31.88333333333333, 30.11666666666667, 29.66666666666667, -100.33333333333333, 700000.0, 3000000.0),
+    zone!("4204", "Texas South Central", 30.28333333333333, 28.38333333333333, 27.83333333333333, -99.0, 600000.0, 4000000.0),
+    zone!("4205", "Texas South", 27.83333333333333, 26.16666666666667, 25.66666666666667, -98.5, 300000.0, 5000000.0),
+    zone!("4301", "Utah North", 41.78333333333333, 40.71666666666667, 40.33333333333334, -111.5, 500000.0, 1000000.0),
+    zone!("4302", "Utah Central", 40.65, 39.01666666666667, 38.33333333333334, -111.5, 500000.0, 2000000.0),
+    zone!("4303", "Utah South", 38.35, 37.21666666666667, 36.66666666666666, -111.5, 500000.0, 3000000.0),
+    zone!("4501", "Virginia North", 39.2, 38.03333333333333, 37.66666666666666, -78.5, 3500000.0, 2000000.0),
+    zone!("4502", "Virginia South", 37.96666666666667, 36.76666666666667, 36.33333333333334, -78.5, 3500000.0, 1000000.0),
+    zone!("4601", "Washington North", 48.73333333333333, 47.5, 47.0, -120.83333333333333, 500000.0, 0.0),
+    zone!("4602", "Washington South", 47.33333333333334, 45.83333333333334, 45.33333333333334, -120.5, 500000.0, 0.0),
+    zone!("4701", "West Virginia North", 40.25, 39.0, 38.5, -79.5, 600000.0, 0.0),
+    zone!("4702", "West Virginia South", 38.88333333333333, 37.48333333333333, 37.0, -81.0, 600000.0, 0.0),
+    zone!("4801", "Wisconsin North", 46.76666666666667, 45.56666666666667, 45.16666666666666, -90.0, 600000.0, 0.0),
+    zone!("4802", "Wisconsin Central", 45.5, 44.25, 43.83333333333334, -90.0, 600000.0, 0.0),
+    zone!("4803", "Wisconsin South", 44.06666666666667, 42.73333333333333, 42.0, -90.0, 600000.0, 0.0),
+    zone!("5200", "Puerto Rico and Virgin Islands", 18.43333333333333, 18.03333333333333, 17.83333333333333, -66.43333333333334, 200000.0, 200000.0),
+];
+
+/// Look up an SPCS83 Lambert zone by its FIPS code, e.g. 3702.
+///
+pub fn zone(code:&str) -> Option<Zone> {
+    ZONES.iter().find(|z| z.code == code).map(|z| *z)
+}
+
+#[test]
+fn test_spcs_pennsylvania_south() {
+    use projection::Projection;
+
+    // origin of the zone projects to the false origin
+    let grs80 = ellipsoid::Ellipsoid::new(6378137.000, 298.257222101);
+    let zone = zone("3702").unwrap();
+    let proj = zone.projection(grs80);
+
+    let (x, y) = proj.forward(-77.75f64.to_radians(), zone.phi0.to_radians());
+    assert!((x - 600000.0).abs() < 1.0e-6);
+    assert!(y.abs() < 1.0e-6);
+
+    let (lambda, phi) = proj.inverse(1000000.0, 100000.0);
+    let (x, y) = proj.forward(lambda, phi);
+    assert!((x - 1000000.0).abs() < 1.0e-6);
+    assert!((y - 100000.0).abs() < 1.0e-6);
+}
@@ -0,0 +1,387 @@
+use std::f64::consts::PI;
+use std::io;
+
+use eci;
+use eci::{Epoch, J2000};
+use grid::invalid;
+use time;
+
+/// Radians per arc-second.
+///
+const ARCSEC:f64 = PI / 648000.0;
+
+/// Equatorial radius of the Earth (m) and the mass ratios of the Sun and Moon to the Earth of the
+/// IERS Conventions.
+///
+const RE:f64 = 6378136.6;
+const MASS_RATIO_SUN:f64 = 332946.0482;
+const MASS_RATIO_MOON:f64 = 0.0123000371;
+
+/// Obliquity of the ecliptic at J2000 (radians).
+///
+const OBLIQUITY:f64 = 23.43929111 * PI / 180.0;
+
+/// Rotate ecliptic spherical coordinates of J2000 to equatorial rectangular coordinates.
+///
+fn equatorial(lambda:f64, beta:f64, r:f64) -> [f64; 3] {
+    let (x, y, z) = (r * lambda.cos() * beta.cos(), r * lambda.sin() * beta.cos(), r * beta.sin());
+    [x, y * OBLIQUITY.cos() - z * OBLIQUITY.sin(), y * OBLIQUITY.sin() + z * OBLIQUITY.cos()]
+}
+
+/// Calculate the low precision position of the Sun, in meters, in the mean equator and equinox
+/// of J2000 at a Julian date in terrestrial time.
+///
+/// References:
+/// -----------
+/// Montenbruck, O. & Gill, E. (2000). Satellite Orbits: Models, Methods and Applications,
+/// sec. 3.3.2.
+///
+pub fn sun(jd_tt:f64) -> [f64; 3] {
+    let t:f64 = (jd_tt - J2000) / 36525.0;
+    let m:f64 = (357.5256 + 35999.049 * t).to_radians();
+    let lambda:f64 = (282.9400f64).to_radians() + m + (6892.0 * m.sin() + 72.0 * (2.0 * m).sin()) * ARCSEC;
+    let r:f64 = (149.619 - 2.499 * m.cos() - 0.021 * (2.0 * m).cos()) * 1.0e9;
+
+    // unlike the Moon's, the series is already referred to the equinox of J2000
+    equatorial(lambda, 0.0, r)
+}
+
+/// Calculate the low precision position of the Moon, in meters, in the mean equator and equinox
+/// of J2000 at a Julian date in terrestrial time.
+///
+/// References:
+/// -----------
+/// Montenbruck, O. & Gill, E. (2000). Satellite Orbits: Models, Methods and Applications,
+/// sec. 3.3.2.
+///
+pub fn moon(jd_tt:f64) -> [f64; 3] {
+    let t:f64 = (jd_tt - J2000) / 36525.0;
+    let l0:f64 = 218.31617 + 481267.88088 * t - 1.3972 * t;
+    let l:f64 = (134.96292 + 477198.86753 * t).to_radians();
+    let lp:f64 = (357.52543 + 35999.04944 * t).to_radians();
+    let f:f64 = (93.27283 + 483202.01873 * t).to_radians();
+    let d:f64 = (297.85027 + 445267.11135 * t).to_radians();
+
+    let lambda:f64 = l0.to_radians() + ARCSEC * (22640.0 * l.sin() + 769.0 * (2.0 * l).sin()
+        - 4586.0 * (l - 2.0 * d).sin() + 2370.0 * (2.0 * d).sin() - 668.0 * lp.sin() - 412.0 * (2.0 * f).sin()
+        - 212.0 * (2.0 * l - 2.0 * d).sin() - 206.0 * (l + lp - 2.0 * d).sin() + 192.0 * (l + 2.0 * d).sin()
+        - 165.0 * (lp - 2.0 * d).sin() + 148.0 * (l - lp).sin() - 125.0 * d.sin() - 110.0 * (l + lp).sin()
+        - 55.0 * (2.0 * f - 2.0 * d).sin());
+    let beta:f64 = ARCSEC * (18520.0 * (f + lambda - l0.to_radians() + ARCSEC * (412.0 * (2.0 * f).sin() + 541.0 * lp.sin())).sin()
+        - 526.0 * (f - 2.0 * d).sin() + 44.0 * (l + f - 2.0 * d).sin() - 31.0 * (-l + f - 2.0 * d).sin()
+        - 25.0 * (-2.0 * l + f).sin() - 23.0 * (lp + f - 2.0 * d).sin() + 21.0 * (-l + f).sin()
+        + 11.0 * (-lp + f - 2.0 * d).sin());
+    let r:f64 = 1000.0 * (385000.0 - 20905.0 * l.cos() - 3699.0 * (2.0 * d - l).cos() - 2956.0 * (2.0 * d).cos()
+        - 570.0 * (2.0 * l).cos() + 246.0 * (2.0 * l - 2.0 * d).cos() - 205.0 * (lp - 2.0 * d).cos()
+        - 171.0 * (l + 2.0 * d).cos() - 152.0 * (l + lp - 2.0 * d).cos());
+
+    equatorial(lambda, beta, r)
+}
+
+/// Local geocentric frame of a position: geocentric latitude and longitude with the unit
+/// vectors up, east and north.
+///
+struct Frame {
+    phi : f64,
+    lambda : f64,
+    up : [f64; 3],
+    east : [f64; 3],
+    north : [f64; 3]
+}
+
+fn frame(r:[f64; 3]) -> Frame {
+    let phi:f64 = r[2].atan2((r[0] * r[0] + r[1] * r[1]).sqrt());
+    let lambda:f64 = r[1].atan2(r[0]);
+    let (sp, cp, sl, cl) = (phi.sin(), phi.cos(), lambda.sin(), lambda.cos());
+    Frame {phi: phi, lambda: lambda, up: [cp * cl, cp * sl, sp], east: [-sl, cl, 0.0], north: [-sp * cl, -sp * sl, cp]}
+}
+
+/// Sum up, east and north components, in meters, into an ECEF displacement.
+///
+fn local(f:&Frame, up:f64, east:f64, north:f64) -> [f64; 3] {
+    let mut d = [0.0; 3];
+    for i in 0..3 {
+        d[i] = up * f.up[i] + east * f.east[i] + north * f.north[i];
+    }
+    d
+}
+
+fn norm(v:[f64; 3]) -> f64 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+/// Second degree Legendre polynomial.
+///
+fn p2(s:f64) -> f64 {
+    (3.0 * s * s - 1.0) / 2.0
+}
+
+/// Diurnal tides of step 2: Doodson multipliers of s, h, p, N' and p_s, then the radial and
+/// transverse in-phase and out-of-phase corrections (mm).
+///
+const DIURNAL:[[f64; 9]; 31] = [
+    [-3.0, 0.0, 2.0, 0.0, 0.0, -0.01, 0.0, 0.0, 0.0],
+    [-3.0, 2.0, 0.0, 0.0, 0.0, -0.01, 0.0, 0.0, 0.0],
+    [-2.0, 0.0, 1.0, -1.0, 0.0, -0.02, 0.0, 0.0, 0.0],
+    [-2.0, 0.0, 1.0, 0.0, 0.0, -0.08, 0.0, -0.01, 0.01],
+    [-2.0, 2.0, -1.0, 0.0, 0.0, -0.02, 0.0, 0.0, 0.0],
+    [-1.0, 0.0, 0.0, -1.0, 0.0, -0.10, 0.0, 0.0, 0.0],
+    [-1.0, 0.0, 0.0, 0.0, 0.0, -0.51, 0.0, -0.02, 0.03],
+    [-1.0, 2.0, 0.0, 0.0, 0.0, 0.01, 0.0, 0.0, 0.0],
+    [0.0, -2.0, 1.0, 0.0, 0.0, 0.01, 0.0, 0.0, 0.0],
+    [0.0, 0.0, -1.0, 0.0, 0.0, 0.02, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0, 0.0, 0.06, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 1.0, 0.0, 0.01, 0.0, 0.0, 0.0],
+    [0.0, 2.0, -1.0, 0.0, 0.0, 0.01, 0.0, 0.0, 0.0],
+    [1.0, -3.0, 0.0, 0.0, 1.0, -0.06, 0.0, 0.0, 0.0],
+    [1.0, -2.0, 0.0, -1.0, 0.0, 0.01, 0.0, 0.0, 0.0],
+    [1.0, -2.0, 0.0, 0.0, 0.0, -1.23, -0.07, 0.06, 0.01],
+    [1.0, -1.0, 0.0, 0.0, -1.0, 0.02, 0.0, 0.0, 0.0],
+    [1.0, -1.0, 0.0, 0.0, 1.0, 0.04, 0.0, 0.0, 0.0],
+    [1.0, 0.0, 0.0, -1.0, 0.0, -0.22, 0.01, 0.01, 0.0],
+    [1.0, 0.0, 0.0, 0.0, 0.0, 12.00, -0.78, -0.67, -0.03],
+    [1.0, 0.0, 0.0, 1.0, 0.0, 1.73, -0.12, -0.10, 0.0],
+    [1.0, 0.0, 0.0, 2.0, 0.0, -0.04, 0.0, 0.0, 0.0],
+    [1.0, 1.0, 0.0, 0.0, -1.0, -0.50, -0.01, 0.03, 0.0],
+    [1.0, 1.0, 0.0, 0.0, 1.0, 0.01, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 1.0, -1.0, -0.01, 0.0, 0.0, 0.0],
+    [1.0, 2.0, -2.0, 0.0, 0.0, -0.01, 0.0, 0.0, 0.0],
+    [1.0, 2.0, 0.0, 0.0, 0.0, -0.11, 0.01, 0.01, 0.0],
+    [2.0, -2.0, 1.0, 0.0, 0.0, -0.01, 0.0, 0.0, 0.0],
+    [2.0, 0.0, -1.0, 0.0, 0.0, -0.02, 0.0, 0.0, 0.0],
+    [3.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+];
+
+/// Long period tides of step 2: Doodson multipliers, then the radial and transverse in-phase and
+/// the radial and transverse out-of-phase corrections (mm).
+///
+const LONG_PERIOD:[[f64; 9]; 5] = [
+    [0.0, 0.0, 0.0, 1.0, 0.0, 0.47, 0.23, 0.16, 0.07],
+    [0.0, 2.0, 0.0, 0.0, 0.0, -0.20, -0.12, -0.11, -0.05],
+    [1.0, 0.0, -1.0, 0.0, 0.0, -0.11, -0.08, -0.09, -0.04],
+    [2.0, 0.0, 0.0, 0.0, 0.0, -0.13, -0.11, -0.15, -0.07],
+    [2.0, 0.0, 0.0, 1.0, 0.0, -0.05, -0.05, -0.06, -0.03]
+];
+
+/// Doodson arguments, in degrees, of an epoch: τ, s, h, p, N' and p_s.
+///
+fn doodson(epoch:&Epoch) -> [f64; 6] {
+    let t:f64 = (epoch.jd_tt - J2000) / 36525.0;
+    let (t2, t3, t4) = (t * t, t * t * t, t * t * t * t);
+    let hours:f64 = (epoch.jd_ut1 - 0.5).fract() * 24.0;
+    let s:f64 = 218.31664563 + 481267.88194 * t - 0.0014663889 * t2 + 0.00000185139 * t3;
+    let tau:f64 = hours * 15.0 + 280.4606184 + 36000.7700536 * t + 0.00038793 * t2 - 0.0000000258 * t3 - s;
+    let pr:f64 = 1.396971278 * t + 0.000308889 * t2 + 0.000000021 * t3 + 0.000000007 * t4;
+    let h:f64 = 280.46645 + 36000.7697489 * t + 0.00030322222 * t2 + 0.000000020 * t3 - 0.00000000654 * t4;
+    let p:f64 = 83.35324312 + 4069.01363525 * t - 0.01032172222 * t2 - 0.0000124991 * t3 + 0.00000005263 * t4;
+    let zns:f64 = 234.95544499 + 1934.13626197 * t - 0.00207561111 * t2 - 0.00000213944 * t3 + 0.00000001650 * t4;
+    let ps:f64 = 282.93734098 + 1.71945766667 * t + 0.00045688889 * t2 - 0.00000001778 * t3 - 0.00000000334 * t4;
+    [tau, s + pr, h + pr, p + pr, zns + pr, ps + pr]
+}
+
+/// Calculate the solid earth tide displacement of a station from the ECEF positions of the Sun
+/// and Moon, all in meters.
+///
+/// The displacement is the conventional tide-free one, and includes the permanent tide.
+///
+/// References:
+/// -----------
+/// Petit, G. & Luzum, B. (2010). IERS Conventions (2010). IERS Technical Note 36, sec. 7.1.1.
+///
+pub fn displacement(station:[f64; 3], sun:[f64; 3], moon:[f64; 3], epoch:&Epoch) -> [f64; 3] {
+    let f = frame(station);
+    let rs:f64 = norm(station);
+    let (sp, cp) = (f.phi.sin(), f.phi.cos());
+    let (h2, l2) = (0.6078 - 0.0006 * p2(sp), 0.0847 + 0.0002 * p2(sp));
+    let (h3, l3) = (0.292, 0.015);
+
+    let mut d = [0.0; 3];
+    for &(body, ratio) in [(sun, MASS_RATIO_SUN), (moon, MASS_RATIO_MOON)].iter() {
+        let rb:f64 = norm(body);
+        let scal:f64 = (body[0] * station[0] + body[1] * station[1] + body[2] * station[2]) / (rb * rs);
+        let fac2:f64 = ratio * RE * (RE / rb).powi(3);
+        let fac3:f64 = fac2 * RE / rb;
+
+        // in-phase degree 2 and 3 displacements, eq. 7.5 and 7.6
+        let a2:f64 = 3.0 * l2 * scal;
+        let b2:f64 = 3.0 * (h2 / 2.0 - l2) * scal * scal - h2 / 2.0;
+        let a3:f64 = 1.5 * l3 * (5.0 * scal * scal - 1.0);
+        let b3:f64 = 2.5 * (h3 - 3.0 * l3) * scal.powi(3) + 1.5 * (l3 - h3) * scal;
+        for i in 0..3 {
+            d[i] += fac2 * (a2 * body[i] / rb + b2 * f.up[i]) + fac3 * (a3 * body[i] / rb + b3 * f.up[i]);
+        }
+
+        // out-of-phase and l(1) corrections from the body's geocentric latitude and longitude
+        let phib:f64 = body[2].atan2((body[0] * body[0] + body[1] * body[1]).sqrt());
+        let dl:f64 = f.lambda - body[1].atan2(body[0]);
+        let (s2b, cb2) = ((2.0 * phib).sin(), phib.cos() * phib.cos());
+        let p21:f64 = 3.0 * phib.sin() * phib.cos();
+        let p22:f64 = 3.0 * cb2;
+
+        // diurnal band, eq. 7.10 and 7.12
+        let (dh, dlt, l1) = (-0.0025, -0.0007, 0.0012);
+        let up = -0.75 * dh * s2b * (2.0 * f.phi).sin() * dl.sin();
+        let north = -1.5 * dlt * s2b * (2.0 * f.phi).cos() * dl.sin() - l1 * sp * p21 * sp * dl.cos();
+        let east = -1.5 * dlt * s2b * sp * dl.cos() + l1 * sp * p21 * (2.0 * f.phi).cos() * dl.sin();
+        let diurnal = local(&f, fac2 * up, fac2 * east, fac2 * north);
+
+        // semidiurnal band, eq. 7.11 and 7.13
+        let (dh, dlt, l1) = (-0.0022, -0.0007, 0.0024);
+        let up = -0.75 * dh * cb2 * cp * cp * (2.0 * dl).sin();
+        let north = 0.75 * dlt * cb2 * (2.0 * f.phi).sin() * (2.0 * dl).sin() - 0.5 * l1 * sp * cp * p22 * (2.0 * dl).cos();
+        let east = -1.5 * dlt * cb2 * cp * (2.0 * dl).cos() - 0.5 * l1 * sp * sp * cp * p22 * (2.0 * dl).sin();
+        let semidiurnal = local(&f, fac2 * up, fac2 * east, fac2 * north);
+        for i in 0..3 {
+            d[i] += diurnal[i] + semidiurnal[i];
+        }
+    }
+
+    // frequency dependent corrections of step 2, eq. 7.12 and 7.13 and tables 7.3a and 7.3b
+    let arguments = doodson(epoch);
+    let (mut up, mut east, mut north) = (0.0, 0.0, 0.0);
+    for row in DIURNAL.iter() {
+        let theta:f64 = (arguments[0] + (0..5).map(|k| row[k] * arguments[k + 1]).sum::<f64>()).to_radians() + f.lambda;
+        up += (row[5] * theta.sin() + row[6] * theta.cos()) * (2.0 * f.phi).sin();
+        north += (row[7] * theta.sin() + row[8] * theta.cos()) * (2.0 * f.phi).cos();
+        east += (row[7] * theta.cos() - row[8] * theta.sin()) * sp;
+    }
+    for row in LONG_PERIOD.iter() {
+        let theta:f64 = (0..5).map(|k| row[k] * arguments[k + 1]).sum::<f64>().to_radians();
+        up += (row[5] * theta.cos() + row[7] * theta.sin()) * p2(sp);
+        north += (row[6] * theta.cos() + row[8] * theta.sin()) * (2.0 * f.phi).sin();
+    }
+    let step2 = local(&f, up / 1000.0, east / 1000.0, north / 1000.0);
+    for i in 0..3 {
+        d[i] += step2[i];
+    }
+    d
+}
+
+/// Calculate the permanent part of the solid earth tide displacement of a station, in meters,
+/// which the conventional tide-free displacement includes.
+///
+/// References:
+/// -----------
+/// Petit, G. & Luzum, B. (2010). IERS Conventions (2010). IERS Technical Note 36, eq. 7.14.
+///
+pub fn permanent(station:[f64; 3]) -> [f64; 3] {
+    let f = frame(station);
+    let p = p2(f.phi.sin());
+    local(&f, (-0.1206 + 0.0001 * p) * p, 0.0, (-0.0252 - 0.0001 * p) * (2.0 * f.phi).sin())
+}
+
+/// Calculate the solid earth tide displacement of an ECEF station, in meters, at an epoch, with
+/// the Sun and Moon from their low precision ephemerides.
+///
+/// The tide system is tide-free, for the conventional tide-free displacement, or mean-tide or
+/// zero-tide, which leave out the permanent tide. For station positions the zero-tide and
+/// mean-tide crusts coincide.
+///
+pub fn solid_earth_tide(x:f64, y:f64, z:f64, epoch:&Epoch, system:&str) -> io::Result<(f64, f64, f64)> {
+    let s = sun(epoch.jd_tt);
+    let m = moon(epoch.jd_tt);
+    let sun_ecef = eci::gcrf2ecef(s[0], s[1], s[2], epoch);
+    let moon_ecef = eci::gcrf2ecef(m[0], m[1], m[2], epoch);
+    let d = displacement([x, y, z], [sun_ecef.0, sun_ecef.1, sun_ecef.2], [moon_ecef.0, moon_ecef.1, moon_ecef.2], epoch);
+    match system {
+        "tide-free" => Ok((d[0], d[1], d[2])),
+        "mean-tide" | "zero-tide" => {
+            let p = permanent([x, y, z]);
+            Ok((d[0] - p[0], d[1] - p[1], d[2] - p[2]))
+        },
+        _ => Err(invalid(&format!("unknown tide system {}", system)))
+    }
+}
+
+/// Calculate the IERS 2010 conventional mean pole, in arc-seconds, at a decimal year.
+///
+/// References:
+/// -----------
+/// Petit, G. & Luzum, B. (2010). IERS Conventions (2010). IERS Technical Note 36, eq. 7.25.
+///
+pub fn mean_pole(year:f64) -> (f64, f64) {
+    let t:f64 = year - 2000.0;
+    let (xp, yp) = if year <= 2010.0 {
+        (55.974 + 1.8243 * t + 0.18413 * t * t + 0.007024 * t * t * t,
+         346.346 + 1.7896 * t - 0.10729 * t * t - 0.000908 * t * t * t)
+    } else {
+        (23.513 + 7.6141 * t, 358.891 - 0.6287 * t)
+    };
+    (xp / 1000.0, yp / 1000.0)
+}
+
+/// Calculate the pole tide displacement of an ECEF station, in meters, from the pole
+/// coordinates of the epoch.
+///
+/// References:
+/// -----------
+/// Petit, G. & Luzum, B. (2010). IERS Conventions (2010). IERS Technical Note 36, sec. 7.1.4.
+///
+pub fn pole_tide(x:f64, y:f64, z:f64, epoch:&Epoch) -> (f64, f64, f64) {
+    let f = frame([x, y, z]);
+    let (xm, ym) = mean_pole(time::decimal_year(epoch.jd_tt));
+    let m1:f64 = epoch.xp / ARCSEC - xm;
+    let m2:f64 = -(epoch.yp / ARCSEC - ym);
+    let theta:f64 = PI / 2.0 - f.phi;
+    let (cl, sl) = (f.lambda.cos(), f.lambda.sin());
+
+    let radial:f64 = -33.0 * (2.0 * theta).sin() * (m1 * cl + m2 * sl);
+    let south:f64 = -9.0 * (2.0 * theta).cos() * (m1 * cl + m2 * sl);
+    let east:f64 = 9.0 * theta.cos() * (m1 * sl - m2 * cl);
+    let d = local(&f, radial / 1000.0, east / 1000.0, -south / 1000.0);
+    (d[0], d[1], d[2])
+}
+
+#[test]
+fn test_solid_earth_tide() {
+    // IERS DEHANTTIDEINEL test case, 2009-04-13 00:00 UTC
+    let epoch = Epoch::new(time::julian_date(2009, 4, 13, 0, 0, 0.0), 34.0, 0.0, 0.0, 0.0);
+    let station = [4075578.385, 931852.890, 4801570.154];
+    let sun_ecef = [137859926952.015, 54228127881.4350, 23509422341.6960];
+    let moon_ecef = [-179996231.920342, -312468450.131567, -169288918.592160];
+    let d = displacement(station, sun_ecef, moon_ecef, &epoch);
+    assert!((d[0] - 0.07700420357108125891).abs() < 1.0e-4);
+    assert!((d[1] - 0.06304056321824967613).abs() < 1.0e-4);
+    assert!((d[2] - 0.05516568152597246810).abs() < 1.0e-4);
+
+    // the low precision Sun at J2000 and the Moon of Meeus (1998), example 47.a, in ecliptic
+    // longitude of J2000, latitude and distance
+    let ecliptic = |v:[f64; 3]| {
+        let (y, z) = (v[1] * OBLIQUITY.cos() + v[2] * OBLIQUITY.sin(), -v[1] * OBLIQUITY.sin() + v[2] * OBLIQUITY.cos());
+        (y.atan2(v[0]).to_degrees(), z.atan2((v[0] * v[0] + y * y).sqrt()).to_degrees(), norm(v))
+    };
+    let (lambda, beta, r) = ecliptic(sun(J2000));
+    assert!((lambda + 360.0 - 280.382).abs() < 0.01 && beta.abs() < 1.0e-9 && (r / 149597870700.0 - 0.98333).abs() < 1.0e-4);
+    // Meeus (1998), example 25.a, 1992-10-13 0h TD: 199.90988 degrees of date, less the precession
+    let (lambda, beta, r) = ecliptic(sun(time::julian_date(1992, 10, 13, 0, 0, 0.0)));
+    assert!((lambda.rem_euclid(360.0) - (199.90988 + 0.01397 * 7.2167)).abs() < 0.03 && beta.abs() < 1.0e-9 && (r / 149597870700.0 - 0.99766).abs() < 1.0e-4);
+    let jd = time::julian_date(1992, 4, 12, 0, 0, 0.0);
+    let (lambda, beta, r) = ecliptic(moon(jd));
+    assert!((lambda - 133.271).abs() < 0.05 && (beta + 3.229).abs() < 0.05 && (r / 368409.7e3 - 1.0).abs() < 1.0e-3);
+
+    let (dx, dy, dz) = solid_earth_tide(station[0], station[1], station[2], &epoch, "tide-free").unwrap();
+    assert!(norm([dx, dy, dz]) > 0.01 && norm([dx, dy, dz]) < 0.5);
+    let (mx, my, mz) = solid_earth_tide(station[0], station[1], station[2], &epoch, "mean-tide").unwrap();
+    let p = permanent(station);
+    assert!((dx - mx - p[0]).abs() < 1.0e-12 && (dy - my - p[1]).abs() < 1.0e-12 && (dz - mz - p[2]).abs() < 1.0e-12);
+    assert!(solid_earth_tide(station[0], station[1], station[2], &epoch, "none").is_err());
+    // the permanent tide lifts the equator by about 6 cm and lowers the poles by 12 cm
+    assert!((norm(permanent([RE, 0.0, 0.0])) - 0.0603).abs() < 1.0e-3);
+    assert!((norm(permanent([0.0, 0.0, RE])) - 0.1207).abs() < 1.0e-3);
+}
+
+#[test]
+fn test_pole_tide() {
+    assert!((mean_pole(2009.0).0 - 0.0924).abs() < 1.0e-3);
+    assert!((mean_pole(2015.0).0 - 0.1377).abs() < 1.0e-3 && (mean_pole(2015.0).1 - 0.3495).abs() < 1.0e-3);
+    // a pole displaced 0.1 arc-seconds along the Greenwich meridian from the mean
+    let (xm, ym) = mean_pole(2015.0);
+    let epoch = Epoch::new(time::julian_date(2015, 1, 1, 0, 0, 0.0), 35.0, 0.0, xm + 0.1, ym);
+    let (x, y, z) = ::convert::lla2ecef(0.0, 45.0f64.to_radians(), 0.0, ::ellipsoid::Ellipsoid::new(6378137.0, 298.257223563));
+    let (dx, dy, dz) = pole_tide(x, y, z, &epoch);
+    let f = frame([x, y, z]);
+    let up = dx * f.up[0] + dy * f.up[1] + dz * f.up[2];
+    assert!((up + 0.0033 * (2.0 * (PI / 2.0 - f.phi)).sin()).abs() < 1.0e-5);
+    assert!(norm([dx, dy, dz]) < 0.004);
+}
@@ -0,0 +1,340 @@
+use std::io;
+
+use grid;
+use grid::invalid;
+
+/// Offset of the modified Julian date from the Julian date.
+///
+pub const MJD0:f64 = 2400000.5;
+
+/// Julian date of the GPS time origin, 1980-01-06 00:00 UTC.
+///
+pub const GPS0:f64 = 2444244.5;
+
+/// Difference of TT from TAI (s).
+///
+pub const TT_TAI:f64 = 32.184;
+
+/// Difference of TAI from GPS time (s).
+///
+pub const TAI_GPS:f64 = 19.0;
+
+/// Seconds in a day.
+///
+const DAY:f64 = 86400.0;
+
+/// Calculate the Julian date of a calendar date and time of day in the Gregorian calendar.
+///
+pub fn julian_date(year:i64, month:i64, day:i64, hour:i64, minute:i64, second:f64) -> f64 {
+    // days from 1970-01-01 by the civil calendar, shifting the year to start in March
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    2440587.5 + days as f64 + (hour as f64 * 3600.0 + minute as f64 * 60.0 + second) / DAY
+}
+
+/// Calculate the calendar date and time of day of a Julian date in the Gregorian calendar.
+///
+pub fn calendar(jd:f64) -> (i64, i64, i64, i64, i64, f64) {
+    let shifted:f64 = jd - 2440587.5;
+    let days = shifted.floor() as i64;
+    let mut seconds:f64 = (shifted - shifted.floor()) * DAY;
+
+    // inverse of the civil calendar in julian_date
+    let z = days + 719468;
+    let era = (if z >= 0 { z } else { z - 146096 }) / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    let hour = (seconds / 3600.0).floor() as i64;
+    seconds -= hour as f64 * 3600.0;
+    let minute = (seconds / 60.0).floor() as i64;
+    (year, month, day, hour, minute, seconds - minute as f64 * 60.0)
+}
+
+/// Calculate the number of days of a month of the proleptic Gregorian calendar.
+///
+pub fn days_in_month(year:i64, month:i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+/// Parse a UTC timestamp such as 2004-04-06T07:51:28.386 into a Julian date.
+///
+/// The time of day may be separated by a T or a space and omitted for midnight; a trailing Z is
+/// ignored.
+///
+pub fn parse_utc(text:&str) -> Option<f64> {
+    let text = text.trim().trim_end_matches('Z');
+    let mut parts = text.splitn(2, |c| c == 'T' || c == ' ');
+    let date:Vec<&str> = parts.next()?.split('-').collect();
+    let time:Vec<&str> = match parts.next() {
+        Some(time) => time.split(':').collect(),
+        None => vec!["0", "0", "0"]
+    };
+    if date.len() != 3 || time.len() < 2 || time.len() > 3 {
+        return None;
+    }
+    let (year, month, day) = (date[0].parse().ok()?, date[1].parse().ok()?, date[2].parse().ok()?);
+    let (hour, minute) = (time[0].parse().ok()?, time[1].parse().ok()?);
+    let second:f64 = if time.len() == 3 { time[2].parse().ok()? } else { 0.0 };
+    if month < 1 || month > 12 || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(julian_date(year, month, day, hour, minute, second))
+}
+
+/// Format a UTC Julian date as a timestamp to the millisecond.
+///
+pub fn format_utc(jd:f64) -> String {
+    // round first so the seconds never print as 60
+    let (year, month, day, hour, minute, second) = calendar((jd * DAY * 1000.0).round() / (DAY * 1000.0) + 1.0e-10);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:06.3}Z", year, month, day, hour, minute, (second * 1000.0).floor() / 1000.0)
+}
+
+/// Convert a Julian date to a decimal year, the fraction counted in days of that calendar year.
+///
+pub fn decimal_year(jd:f64) -> f64 {
+    let year = calendar(jd).0;
+    let start = julian_date(year, 1, 1, 0, 0, 0.0);
+    let end = julian_date(year + 1, 1, 1, 0, 0, 0.0);
+
+    year as f64 + (jd - start) / (end - start)
+}
+
+/// Convert a decimal year to a Julian date.
+///
+pub fn from_decimal_year(epoch:f64) -> f64 {
+    let year = epoch.floor() as i64;
+    let start = julian_date(year, 1, 1, 0, 0, 0.0);
+    let end = julian_date(year + 1, 1, 1, 0, 0, 0.0);
+
+    start + (epoch - year as f64) * (end - start)
+}
+
+/// Parse an epoch given either as a decimal year or as a UTC timestamp, returning decimal years.
+///
+pub fn parse_epoch(text:&str) -> Option<f64> {
+    match text.trim().parse::<f64>() {
+        Ok(epoch) => Some(epoch),
+        Err(_) => parse_utc(text).map(decimal_year)
+    }
+}
+
+/// Table of TAI-UTC, in seconds, from each leap second onward.
+///
+#[derive(Clone, Debug)]
+pub struct LeapSeconds {
+    /// modified Julian date in UTC from which each offset applies, and the offset (s)
+    pub table : Vec<(f64, f64)>,
+    /// modified Julian date after which the table is no longer guaranteed, if known
+    pub expires : Option<f64>
+}
+
+/// Dates of the leap seconds from 1972 to 2017, when TAI-UTC became 10 s and then stepped by one.
+///
+const LEAPS:[(i64, i64); 28] = [
+    (1972, 1), (1972, 7), (1973, 1), (1974, 1), (1975, 1), (1976, 1), (1977, 1), (1978, 1),
+    (1979, 1), (1980, 1), (1981, 7), (1982, 7), (1983, 7), (1985, 7), (1988, 1), (1990, 1),
+    (1991, 1), (1992, 7), (1993, 7), (1994, 7), (1996, 1), (1997, 7), (1999, 1), (2006, 1),
+    (2009, 1), (2012, 7), (2015, 7), (2017, 1)
+];
+
+impl LeapSeconds {
+
+    /// Construct the built-in table, current to the leap second of 2017-01-01.
+    ///
+    /// The table matches the leap-seconds.list issued with IERS Bulletin C 68, and expires with it
+    /// on 2025-06-28.
+    ///
+    pub fn builtin() -> LeapSeconds {
+        let table = LEAPS.iter().enumerate()
+            .map(|(i, &(year, month))| (julian_date(year, month, 1, 0, 0, 0.0) - MJD0, 10.0 + i as f64))
+            .collect();
+        LeapSeconds {table: table, expires: Some(julian_date(2025, 6, 28, 0, 0, 0.0) - MJD0)}
+    }
+
+    /// Calculate TAI-UTC, in seconds, at a UTC Julian date.
+    ///
+    /// Before 1972, when UTC was steered with fractional steps and rate offsets, the 1972 value of
+    /// 10 s is returned.
+    ///
+    pub fn tai_utc(&self, jd_utc:f64) -> f64 {
+        let mjd:f64 = jd_utc - MJD0;
+        match self.table.iter().rev().find(|&&(start, _)| start <= mjd) {
+            Some(&(_, offset)) => offset,
+            None => self.table.first().map(|&(_, offset)| offset).unwrap_or(0.0)
+        }
+    }
+
+    /// Check whether a UTC Julian date is past the expiry of the table, after which a leap second
+    /// may have been announced that it does not hold.
+    ///
+    pub fn expired(&self, jd_utc:f64) -> bool {
+        self.expires.map_or(false, |expires| jd_utc - MJD0 > expires)
+    }
+}
+
+/// Parse a leap second table in the NIST/IERS leap-seconds.list format.
+///
+/// Each line gives the NTP time, seconds since 1900-01-01, at which a TAI-UTC offset starts. The
+/// line starting #@ gives the expiry date of the file.
+///
+/// References:
+/// -----------
+/// IERS. leap-seconds.list. https://hpiers.obspm.fr/iers/bul/bulc/ntp/leap-seconds.list
+///
+pub fn parse_leap_seconds(text:&str) -> io::Result<LeapSeconds> {
+    let ntp = |seconds:f64| seconds / DAY + 15020.0;
+    let mut table:Vec<(f64, f64)> = Vec::new();
+    let mut expires = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with("#@") {
+            expires = line[2..].trim().parse::<f64>().ok().map(ntp);
+            continue;
+        }
+        let data = line.split('#').next().unwrap_or("").trim();
+        if data.is_empty() {
+            continue;
+        }
+        let words:Vec<&str> = data.split_whitespace().collect();
+        match (words.get(0).and_then(|v| v.parse::<f64>().ok()), words.get(1).and_then(|v| v.parse::<f64>().ok())) {
+            (Some(seconds), Some(offset)) => table.push((ntp(seconds), offset)),
+            _ => return Err(invalid(&format!("could not parse leap second line {}", line)))
+        }
+    }
+    if table.is_empty() {
+        return Err(invalid("leap second file has no entries"));
+    }
+    table.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    Ok(LeapSeconds {table: table, expires: expires})
+}
+
+/// Read a leap second table from a leap-seconds.list file.
+///
+pub fn read_leap_seconds(path:&str) -> io::Result<LeapSeconds> {
+    let bytes = grid::contents(path)?;
+    parse_leap_seconds(&String::from_utf8_lossy(&bytes))
+}
+
+/// Convert a UTC Julian date to TAI.
+///
+pub fn utc2tai(jd_utc:f64, leaps:&LeapSeconds) -> f64 {
+    jd_utc + leaps.tai_utc(jd_utc) / DAY
+}
+
+/// Convert a TAI Julian date to UTC.
+///
+/// The offset is looked up again at the UTC estimate, so dates just after a leap second resolve
+/// to the new offset.
+///
+pub fn tai2utc(jd_tai:f64, leaps:&LeapSeconds) -> f64 {
+    let estimate:f64 = jd_tai - leaps.tai_utc(jd_tai) / DAY;
+    jd_tai - leaps.tai_utc(estimate) / DAY
+}
+
+/// Convert a TAI Julian date to terrestrial time.
+///
+pub fn tai2tt(jd_tai:f64) -> f64 {
+    jd_tai + TT_TAI / DAY
+}
+
+/// Convert a terrestrial time Julian date to TAI.
+///
+pub fn tt2tai(jd_tt:f64) -> f64 {
+    jd_tt - TT_TAI / DAY
+}
+
+/// Convert a TAI Julian date to GPS time.
+///
+pub fn tai2gps(jd_tai:f64) -> f64 {
+    jd_tai - TAI_GPS / DAY
+}
+
+/// Convert a GPS time Julian date to TAI.
+///
+pub fn gps2tai(jd_gps:f64) -> f64 {
+    jd_gps + TAI_GPS / DAY
+}
+
+/// Split a GPS time Julian date into the GPS week, counted from 1980-01-06 without rollover, and
+/// the seconds of the week.
+///
+pub fn gps_week(jd_gps:f64) -> (i64, f64) {
+    let seconds:f64 = (jd_gps - GPS0) * DAY;
+    let week = (seconds / 604800.0).floor();
+    (week as i64, seconds - week * 604800.0)
+}
+
+/// Calculate the GPS time Julian date of a GPS week and seconds of the week.
+///
+pub fn gps_date(week:i64, sow:f64) -> f64 {
+    GPS0 + (week as f64 * 604800.0 + sow) / DAY
+}
+
+#[test]
+fn test_calendar() {
+    assert!((julian_date(2000, 1, 1, 12, 0, 0.0) - 2451545.0).abs() < 1.0e-9);
+    assert!((julian_date(1858, 11, 17, 0, 0, 0.0) - MJD0).abs() < 1.0e-9);
+    assert!((parse_utc("2004-04-06T07:51:28.386009Z").unwrap() - 2453101.827411875).abs() < 1.0e-8);
+    assert!((parse_utc("1996-10-26").unwrap() - 2450382.5).abs() < 1.0e-9);
+    assert!(parse_utc("2004-13-06T07:51").is_none());
+    assert!(parse_utc("2021-04-31").is_none());
+    assert!(parse_utc("2021-02-29").is_none());
+    assert!(parse_utc("2020-02-29").is_some());
+    assert!(parse_utc("1900-02-29").is_none() && parse_utc("2000-02-29").is_some());
+
+    let (year, month, day, hour, minute, second) = calendar(2453101.827411875);
+    assert_eq!((year, month, day, hour, minute), (2004, 4, 6, 7, 51));
+    assert!((second - 28.386009).abs() < 1.0e-4);
+    assert_eq!(format_utc(julian_date(1999, 12, 31, 23, 59, 59.9996)), "2000-01-01T00:00:00.000Z");
+
+    // 2020 is a leap year, so mid-year falls on 2 July at noon
+    assert!((from_decimal_year(2020.5) - julian_date(2020, 7, 2, 0, 0, 0.0)).abs() < 1.0e-9);
+    assert!((decimal_year(julian_date(2021, 1, 1, 0, 0, 0.0)) - 2021.0).abs() < 1.0e-12);
+    assert!((parse_epoch("2015.0").unwrap() - 2015.0).abs() < 1.0e-12);
+    assert!((parse_epoch("2020-07-02").unwrap() - 2020.5).abs() < 1.0e-12);
+}
+
+#[test]
+fn test_leap_seconds() {
+    let leaps = LeapSeconds::builtin();
+    assert_eq!(leaps.tai_utc(julian_date(2016, 12, 31, 23, 59, 59.0)), 36.0);
+    assert_eq!(leaps.tai_utc(julian_date(2017, 1, 1, 0, 0, 0.0)), 37.0);
+    assert_eq!(leaps.tai_utc(julian_date(1980, 1, 6, 0, 0, 0.0)), 19.0);
+
+    // GPS time was UTC at its origin and is 18 s ahead since 2017
+    let utc = julian_date(2020, 1, 1, 0, 0, 0.0);
+    let gps = tai2gps(utc2tai(utc, &leaps));
+    assert!(((gps - utc) * DAY - 18.0).abs() < 1.0e-4);
+    let (week, sow) = gps_week(gps);
+    assert_eq!(week, 2086);
+    assert!((sow - 259218.0).abs() < 1.0e-4);
+    assert!((gps_date(week, sow) - gps).abs() < 1.0e-9);
+    assert!((tai2utc(gps2tai(gps), &leaps) - utc).abs() < 1.0e-9);
+    assert!(((tai2tt(utc2tai(utc, &leaps)) - utc) * DAY - 69.184).abs() < 1.0e-4);
+
+    let text = "#\tleap-seconds.list\n#@\t3960057600\n2272060800\t10\t# 1 Jan 1972\n3692217600\t37\t# 1 Jan 2017\n";
+    let parsed = parse_leap_seconds(text).unwrap();
+    assert!((parsed.table[0].0 - (julian_date(1972, 1, 1, 0, 0, 0.0) - MJD0)).abs() < 1.0e-9);
+    assert!((parsed.expires.unwrap() - (julian_date(2025, 6, 28, 0, 0, 0.0) - MJD0)).abs() < 1.0e-9);
+    assert_eq!(parsed.tai_utc(julian_date(2020, 1, 1, 0, 0, 0.0)), 37.0);
+    assert!(!parsed.expired(julian_date(2025, 6, 27, 0, 0, 0.0)));
+    assert!(parsed.expired(julian_date(2025, 6, 29, 0, 0, 0.0)));
+    assert!(!leaps.expired(julian_date(2025, 6, 27, 0, 0, 0.0)));
+    assert!(leaps.expired(julian_date(2030, 1, 1, 0, 0, 0.0)));
+}
@@ -0,0 +1,226 @@
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+use ellipsoid;
+use projection::Projection;
+
+/// Latitude limit of the square Web Mercator world (degrees).
+///
+pub const MAX_LATITUDE: f64 = 85.051128779806592;
+
+/// Size of a slippy-map tile (pixels).
+///
+pub const TILE_SIZE: u32 = 256;
+
+/// Deepest supported zoom level, at which tile and pixel indices still fit a u32.
+///
+/// Deeper zoom levels passed to the tile functions are treated as MAX_ZOOM.
+///
+pub const MAX_ZOOM: u32 = 30;
+
+/// Spherical (Pseudo) Mercator projection as used by web maps, EPSG:3857.
+///
+/// Geodetic coordinates on WGS84 are projected as if they were on a sphere of radius a.
+///
+/// References:
+/// -----------
+/// IOGP (2019). Geomatics Guidance Note 7-2, Popular Visualisation Pseudo Mercator,
+/// EPSG method 1024.
+///
+#[derive(Copy, Clone)]
+pub struct WebMercator {
+    /// reference ellipsoid
+    pub ellps : ellipsoid::Ellipsoid
+}
+
+impl WebMercator {
+
+    /// Construct the EPSG:3857 projection on WGS84.
+    ///
+    pub fn new() -> WebMercator {
+        WebMercator {ellps: ellipsoid::Ellipsoid::new(6378137.000, 298.257223563)}
+    }
+}
+
+impl Default for WebMercator {
+
+    fn default() -> WebMercator {
+        WebMercator::new()
+    }
+}
+
+impl Projection for WebMercator {
+
+    fn ellipsoid(&self) -> ellipsoid::Ellipsoid {
+        self.ellps
+    }
+
+    /// Project longitude and latitude, clamping latitude to +/- MAX_LATITUDE.
+    ///
+    fn forward(&self, lambda:f64, phi:f64) -> (f64, f64) {
+        let limit:f64 = MAX_LATITUDE.to_radians();
+        let phi:f64 = phi.max(-limit).min(limit);
+
+        (self.ellps.a * lambda, self.ellps.a * (FRAC_PI_4 + phi / 2.0).tan().ln())
+    }
+
+    fn inverse(&self, x:f64, y:f64) -> (f64, f64) {
+        (x / self.ellps.a, FRAC_PI_2 - 2.0 * (-y / self.ellps.a).exp().atan())
+    }
+}
+
+/// Calculate the fractional position of a point in the world at a zoom level, in tile units.
+///
+fn world(lambda:f64, phi:f64, zoom:u32) -> (f64, f64) {
+    let zoom:u32 = zoom.min(MAX_ZOOM);
+    let limit:f64 = MAX_LATITUDE.to_radians();
+    let phi:f64 = phi.max(-limit).min(limit);
+    let n:f64 = (1u64 << zoom) as f64;
+
+    let u:f64 = (lambda + PI) / (2.0 * PI) * n;
+    let v:f64 = (1.0 - (phi.tan() + 1.0 / phi.cos()).ln() / PI) / 2.0 * n;
+
+    (u, v)
+}
+
+/// Calculate the XYZ tile column and row containing longitude lambda and latitude phi, given in radians.
+///
+/// References:
+/// -----------
+/// OpenStreetMap Wiki, Slippy map tilenames.
+/// https://wiki.openstreetmap.org/wiki/Slippy_map_tilenames
+///
+pub fn tile(lambda:f64, phi:f64, zoom:u32) -> (u32, u32) {
+    let zoom:u32 = zoom.min(MAX_ZOOM);
+    let (u, v) = world(lambda, phi, zoom);
+    let max:f64 = ((1u64 << zoom) - 1) as f64;
+
+    (u.floor().max(0.0).min(max) as u32, v.floor().max(0.0).min(max) as u32)
+}
+
+/// Calculate the pixel column and row of longitude lambda and latitude phi, given in radians,
+/// within its tile at a zoom level.
+///
+pub fn pixel(lambda:f64, phi:f64, zoom:u32) -> (u32, u32) {
+    let (u, v) = world(lambda, phi, zoom);
+    let (x, y) = tile(lambda, phi, zoom);
+    let size:f64 = TILE_SIZE as f64;
+    let max:f64 = size - 1.0;
+
+    let px:f64 = ((u - x as f64) * size).floor().max(0.0).min(max);
+    let py:f64 = ((v - y as f64) * size).floor().max(0.0).min(max);
+
+    (px as u32, py as u32)
+}
+
+/// Calculate the Bing Maps quadkey of a tile.
+///
+/// References:
+/// -----------
+/// Microsoft, Bing Maps Tile System.
+/// https://learn.microsoft.com/en-us/bingmaps/articles/bing-maps-tile-system
+///
+pub fn quadkey(x:u32, y:u32, zoom:u32) -> String {
+    let zoom:u32 = zoom.min(MAX_ZOOM);
+    let mut key = String::with_capacity(zoom as usize);
+    for i in (1..zoom + 1).rev() {
+        let mask:u32 = 1 << (i - 1);
+        let mut digit:u8 = b'0';
+        if x & mask != 0 {
+            digit += 1;
+        }
+        if y & mask != 0 {
+            digit += 2;
+        }
+        key.push(digit as char);
+    }
+    key
+}
+
+/// Recover the tile column, row and zoom level from a Bing Maps quadkey, or None if it is not a
+/// quadkey of at most MAX_ZOOM digits.
+///
+pub fn quadkey_to_tile(key:&str) -> Option<(u32, u32, u32)> {
+    if key.len() > MAX_ZOOM as usize {
+        return None;
+    }
+    let zoom:u32 = key.len() as u32;
+    let mut x:u32 = 0;
+    let mut y:u32 = 0;
+    for (i, c) in key.chars().enumerate() {
+        let mask:u32 = 1 << (zoom - 1 - i as u32);
+        match c {
+            '0' => {},
+            '1' => x |= mask,
+            '2' => y |= mask,
+            '3' => { x |= mask; y |= mask; },
+            _ => return None
+        }
+    }
+    Some((x, y, zoom))
+}
+
+/// Calculate the bounds of a tile as (west, south, east, north) longitude and latitude in radians.
+///
+pub fn tile_bounds(x:u32, y:u32, zoom:u32) -> (f64, f64, f64, f64) {
+    let zoom:u32 = zoom.min(MAX_ZOOM);
+    let n:f64 = (1u64 << zoom) as f64;
+    let lon = |u:f64| u / n * 2.0 * PI - PI;
+    let lat = |v:f64| (PI * (1.0 - 2.0 * v / n)).sinh().atan();
+
+    (lon(x as f64), lat(y as f64 + 1.0), lon(x as f64 + 1.0), lat(y as f64))
+}
+
+#[test]
+fn test_webmercator() {
+    // EPSG Guidance Note 7-2 example, EPSG:3857
+    let expected = (-11169055.58, 2800000.00);
+    let tolerance:f64 = 0.01;
+
+    let proj = WebMercator::new();
+    let lon:f64 = -(100.0 + 20.0/60.0);
+    let lat:f64 = 24.0 + 22.0/60.0 + 54.433/3600.0;
+    let (x, y) = proj.forward(lon.to_radians(), lat.to_radians());
+    println!("x {0} y {1}", x, y);
+    assert!((x - expected.0).abs() < tolerance);
+    assert!((y - expected.1).abs() < tolerance);
+
+    let (lambda, phi) = proj.inverse(x, y);
+    assert!((lambda.to_degrees() - lon).abs() < 1.0e-9);
+    assert!((phi.to_degrees() - lat).abs() < 1.0e-9);
+
+    // the world is square
+    let (x, y) = proj.forward(PI, 90f64.to_radians());
+    assert!((x - y).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_tile() {
+    // the origin falls on the corner of the four zoom 1 tiles
+    let (x, y) = tile(0.0, 0.0, 1);
+    assert_eq!((x, y), (1, 1));
+
+    let (x, y) = tile(-76.26108657f64.to_radians(), 39.99277705f64.to_radians(), 16);
+    assert_eq!((x, y), (18885, 24812));
+    // Bing Maps Tile System example
+    assert_eq!(quadkey(3, 5, 3), "213");
+    assert_eq!(quadkey_to_tile("213"), Some((3, 5, 3)));
+    assert_eq!(quadkey_to_tile("2x3"), None);
+
+    let (west, south, east, north) = tile_bounds(x, y, 16);
+    let lon:f64 = -76.26108657f64.to_radians();
+    let lat:f64 = 39.99277705f64.to_radians();
+    assert!(west <= lon && lon < east && south <= lat && lat < north);
+
+    let (px, py) = pixel(lon, lat, 16);
+    assert!(px < TILE_SIZE && py < TILE_SIZE);
+
+    // the deepest zoom level still indexes the last tile, and deeper ones are capped to it
+    let last:u32 = (1 << MAX_ZOOM) - 1;
+    assert_eq!(tile(PI, -PI / 2.0, MAX_ZOOM), (last, last));
+    assert_eq!(tile(PI, -PI / 2.0, 64), (last, last));
+    assert_eq!(quadkey(last, last, 40).len(), MAX_ZOOM as usize);
+    let key:String = ::std::iter::repeat('3').take(MAX_ZOOM as usize).collect();
+    assert_eq!(quadkey_to_tile(&key), Some((last, last, MAX_ZOOM)));
+    assert_eq!(quadkey_to_tile(&format!("{}0", key)), None);
+    assert_eq!(quadkey_to_tile(&"0".repeat(40)), None);
+}